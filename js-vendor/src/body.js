@@ -4,6 +4,10 @@ function isDataView(obj) {
     return obj && DataView.prototype.isPrototypeOf(obj)
 }
 
+// BODY_HANDLE marks a stream that reads a host body handle, an untouched stream can pass the handle
+// to another hostcall instead of reading the body into JS
+const BODY_HANDLE = Symbol("bodyHandle");
+
 var viewClasses = [
     '[object Int8Array]',
     '[object Uint8Array]',
@@ -48,6 +52,9 @@ class Body {
                 this.#_bodyText = body.toString();
             } else if (body instanceof ArrayBuffer || isArrayBufferView(body)) {
                 this.#_bodyBuffer = body;
+            } else if (typeof body.getReader === "function") {
+                // ReadableStream body, such as the output of pipeThrough
+                this.#_stream = body;
             } else {
                 this.#_bodyText = String(body);
             }
@@ -61,6 +68,8 @@ class Body {
             this.#_stream = new ReadableStream({
                 async pull(controller) {
                     that.#_bodyUsed = true;
+                    // the handle is partly read, it can not be passed on any more
+                    delete that.#_stream[BODY_HANDLE];
                     let { value, done } = hostcall.read_body(body_handle);
                     if (done) {
                         controller.close();
@@ -75,6 +84,7 @@ class Body {
                 // highWaterMark must be 0, any read operation should be affected #_bodyUsed. Otherwise, it will cause the stream had been read.
                 highWaterMark: 0
             });
+            this.#_stream[BODY_HANDLE] = body_handle;
             return;
         }
 
@@ -144,9 +154,13 @@ class Body {
             return new ArrayBuffer();
         }
         if (this.#_stream) {
-            return await this.#read_stream_full();
+            return (await this.#read_stream_full()).buffer;
+        }
+        let body = this.#body_to_arraybuffer();
+        if (body instanceof ArrayBuffer) {
+            return body;
         }
-        return this.#body_to_arraybuffer().buffer;
+        return body.buffer.slice(body.byteOffset, body.byteOffset + body.byteLength);
     }
 
     async text() {
@@ -160,4 +174,5 @@ class Body {
     }
 }
 
+export { BODY_HANDLE };
 export default Body;
\ No newline at end of file
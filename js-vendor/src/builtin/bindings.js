@@ -0,0 +1,29 @@
+// declaredBindings returns a lookup of the bindings declared by env envKey, such as "SESSIONS,FLAGS=feature-flags".
+// A binding without "=" uses its name as the resource name. The bindings are created again when the
+// declaration changes, such as the env of a running instance after the env of the initialization.
+// The lookup has names() to list the declared binding names.
+function declaredBindings(envKey, create) {
+    let declared = null;
+    let bindings = new Map();
+    function load() {
+        let current = hostcall.read_env(envKey) || "";
+        if (current !== declared) {
+            declared = current;
+            bindings = new Map();
+            for (const item of declared.split(",")) {
+                let [binding, resource] = item.split("=").map(part => part.trim());
+                if (binding) {
+                    bindings.set(binding.toUpperCase(), create(resource || binding));
+                }
+            }
+        }
+        return bindings;
+    }
+    function lookup(name) {
+        return load().get(name);
+    }
+    lookup.names = () => Array.from(load().keys());
+    return lookup;
+}
+
+export { declaredBindings };
@@ -0,0 +1,253 @@
+import Body, { BODY_HANDLE } from "../body";
+import { declaredBindings } from "./bindings";
+
+// Object storage bindings, the objects are stored by the bucket hostcalls, see src/bucket.rs.
+// The bindings are declared by env LAND_BUCKET_BINDINGS, such as "ASSETS,UPLOADS=user-uploads".
+// Object bodies are passed as host body handles when possible, so they are not buffered in JS.
+
+// HTTP_METADATA maps the httpMetadata fields to their headers
+const HTTP_METADATA = {
+    contentType: "content-type",
+    contentLanguage: "content-language",
+    contentDisposition: "content-disposition",
+    contentEncoding: "content-encoding",
+    cacheControl: "cache-control",
+};
+
+function checkKey(key) {
+    if (typeof key !== "string" || key === "") {
+        throw new TypeError("Object key must be a non-empty string");
+    }
+    return key;
+}
+
+function encodeHttpMetadata(metadata) {
+    let headers = {};
+    if (!metadata) {
+        return headers;
+    }
+    let get = metadata instanceof Headers ? name => metadata.get(HTTP_METADATA[name]) : name => metadata[name];
+    for (const name of Object.keys(HTTP_METADATA)) {
+        let value = get(name);
+        if (value !== undefined && value !== null) {
+            headers[HTTP_METADATA[name]] = String(value);
+        }
+    }
+    return headers;
+}
+
+function decodeHttpMetadata(headers) {
+    let metadata = {};
+    for (const [name, header] of Object.entries(HTTP_METADATA)) {
+        if (headers[header] !== undefined) {
+            metadata[name] = headers[header];
+        }
+    }
+    return metadata;
+}
+
+// custom metadata keys are lowercase and the values are encoded, so they are safe in headers
+function encodeCustomMetadata(metadata) {
+    let encoded = {};
+    for (const [key, value] of Object.entries(metadata || {})) {
+        encoded[key.toLowerCase()] = encodeURIComponent(String(value));
+    }
+    return encoded;
+}
+
+function decodeCustomMetadata(metadata) {
+    let decoded = {};
+    for (const [key, value] of Object.entries(metadata || {})) {
+        decoded[key] = decodeURIComponent(value);
+    }
+    return decoded;
+}
+
+function checkRange(range) {
+    if (range === undefined || range === null) {
+        return undefined;
+    }
+    for (const name of ["offset", "length", "suffix"]) {
+        if (range[name] !== undefined && (!Number.isInteger(range[name]) || range[name] < 0)) {
+            throw new TypeError(`Object range ${name} must be a non-negative integer`);
+        }
+    }
+    if (range.suffix !== undefined && (range.offset !== undefined || range.length !== undefined)) {
+        throw new TypeError("Object range suffix can not be used with offset or length");
+    }
+    return { offset: range.offset, length: range.length, suffix: range.suffix };
+}
+
+// putBody returns the body handle of an untouched host stream, or the bytes of value
+async function putBody(value) {
+    if (value === null || value === undefined) {
+        return "";
+    }
+    if (typeof value === "string" || value instanceof ArrayBuffer) {
+        return value;
+    }
+    if (ArrayBuffer.isView(value)) {
+        return value.buffer.slice(value.byteOffset, value.byteOffset + value.byteLength);
+    }
+    if (typeof value.getReader === "function") {
+        if (value[BODY_HANDLE] && !value.locked) {
+            return value[BODY_HANDLE];
+        }
+        return await new Response(value).arrayBuffer();
+    }
+    if (typeof value.arrayBuffer === "function") {
+        return await value.arrayBuffer();
+    }
+    throw new TypeError("Object body must be a string, ArrayBuffer, ArrayBufferView, ReadableStream or Blob");
+}
+
+class BucketObject {
+    #object;
+
+    constructor(object) {
+        this.#object = object;
+    }
+
+    get key() {
+        return this.#object.key;
+    }
+
+    get size() {
+        return this.#object.size;
+    }
+
+    get etag() {
+        return this.#object.etag;
+    }
+
+    get httpEtag() {
+        return `"${this.#object.etag}"`;
+    }
+
+    get uploaded() {
+        return new Date(this.#object.uploaded);
+    }
+
+    get httpMetadata() {
+        return decodeHttpMetadata(this.#object.httpMetadata);
+    }
+
+    get customMetadata() {
+        return decodeCustomMetadata(this.#object.customMetadata);
+    }
+
+    get range() {
+        return this.#object.range;
+    }
+
+    writeHttpMetadata(headers) {
+        for (const [name, value] of Object.entries(this.#object.httpMetadata)) {
+            headers.set(name, value);
+        }
+    }
+
+    get [Symbol.toStringTag]() {
+        return "BucketObject";
+    }
+}
+
+class BucketObjectBody extends BucketObject {
+    #body;
+
+    constructor(object) {
+        super(object);
+        this.#body = object.body_handle ? new Body(null, object.body_handle) : new Body(object.body);
+    }
+
+    get body() {
+        return this.#body.stream;
+    }
+
+    get bodyUsed() {
+        return this.#body.bodyUsed;
+    }
+
+    async arrayBuffer() {
+        return await this.#body.arrayBuffer();
+    }
+
+    async text() {
+        return await this.#body.text();
+    }
+
+    async json() {
+        return await this.#body.json();
+    }
+
+    async blob() {
+        return new Blob([await this.#body.arrayBuffer()], { type: this.httpMetadata.contentType || "" });
+    }
+
+    get [Symbol.toStringTag]() {
+        return "BucketObjectBody";
+    }
+}
+
+class Bucket {
+    #bucket;
+
+    constructor(bucket) {
+        this.#bucket = bucket;
+    }
+
+    async get(key, options) {
+        let range = checkRange(options && options.range);
+        let object = hostcall.bucket_get(this.#bucket, checkKey(key), range);
+        return object ? new BucketObjectBody(object) : null;
+    }
+
+    async head(key) {
+        let object = hostcall.bucket_head(this.#bucket, checkKey(key));
+        return object ? new BucketObject(object) : null;
+    }
+
+    async put(key, value, options) {
+        checkKey(key);
+        options = options || {};
+        let body = await putBody(value);
+        let object = hostcall.bucket_put(this.#bucket, key, body, {
+            httpMetadata: encodeHttpMetadata(options.httpMetadata),
+            customMetadata: encodeCustomMetadata(options.customMetadata),
+        });
+        return new BucketObject(object);
+    }
+
+    async delete(keys) {
+        for (const key of Array.isArray(keys) ? keys : [keys]) {
+            hostcall.bucket_delete(this.#bucket, checkKey(key));
+        }
+    }
+
+    async list(options) {
+        options = options || {};
+        let result = hostcall.bucket_list(this.#bucket, {
+            prefix: options.prefix ? String(options.prefix) : "",
+            delimiter: options.delimiter ? String(options.delimiter) : "",
+            cursor: options.cursor ? String(options.cursor) : "",
+            limit: options.limit === undefined ? 1000 : Number(options.limit),
+        });
+        let list = {
+            objects: result.objects.map(object => new BucketObject(object)),
+            delimitedPrefixes: result.delimitedPrefixes,
+            truncated: !!result.truncated,
+        };
+        if (list.truncated) {
+            list.cursor = result.cursor;
+        }
+        return list;
+    }
+
+    get [Symbol.toStringTag]() {
+        return "Bucket";
+    }
+}
+
+// bucketBinding returns the Bucket of the binding name, undefined if it is not declared
+const bucketBinding = declaredBindings("LAND_BUCKET_BINDINGS", bucket => new Bucket(bucket));
+
+export { Bucket, BucketObject, BucketObjectBody, bucketBinding };
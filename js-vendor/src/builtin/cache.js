@@ -0,0 +1,209 @@
+// Cache API, the responses are stored by the cache hostcalls, see src/cache.rs.
+// The hostcalls use the platform cache when LAND_BINDINGS_URL is set, or an in-instance LRU cache.
+
+// DEFAULT_TTL is used when the response has no max-age, s-maxage or Expires
+const DEFAULT_TTL = 3600;
+
+// VARY_PREFIX stores the request header values of the Vary headers with the cached response
+const VARY_PREFIX = "x-land-vary-";
+
+// The entry of an url is the latest put response. The responses with Vary are stored as variants too,
+// keyed by the url, the Vary header values and the generation of the url entry. A new generation starts
+// when the url entry is missing, so the variants of a deleted or expired entry are not matched again.
+const GENERATION_HEADER = "x-land-cache-generation";
+
+function toRequest(request) {
+    if (request instanceof Request) {
+        return request;
+    }
+    return new Request(String(request));
+}
+
+function cacheKey(request) {
+    let url = new URL(request.url);
+    url.hash = "";
+    return url.toString();
+}
+
+function variantKey(request, generation, names) {
+    let values = names.map(name => `${name}=${encodeURIComponent(request.headers.get(name) || "")}`);
+    return `${cacheKey(request)}\nvary:${generation}:${values.join("&")}`;
+}
+
+// unpack splits the stored headers into the response headers, the Vary values and the generation
+function unpack(cached) {
+    let headers = new Headers();
+    let vary = {};
+    let generation = null;
+    for (const [name, value] of Object.entries(cached.headers)) {
+        if (name.startsWith(VARY_PREFIX)) {
+            vary[name.slice(VARY_PREFIX.length)] = value;
+        } else if (name === GENERATION_HEADER) {
+            generation = value;
+        } else {
+            headers.set(name, value);
+        }
+    }
+    return { headers, vary, generation };
+}
+
+function varyMatches(request, vary) {
+    return Object.entries(vary).every(([name, value]) => (request.headers.get(name) || "") === value);
+}
+
+function parseCacheControl(value) {
+    let directives = {};
+    for (const part of (value || "").split(",")) {
+        let [name, ...rest] = part.split("=");
+        name = name.trim().toLowerCase();
+        if (name) {
+            directives[name] = rest.join("=").trim().replace(/^"|"$/g, "");
+        }
+    }
+    return directives;
+}
+
+function varyHeaders(response) {
+    return (response.headers.get("vary") || "")
+        .split(",")
+        .map(name => name.trim().toLowerCase())
+        .filter(name => name);
+}
+
+// responseTtl returns the seconds response can be cached, 0 if it must not be stored
+function responseTtl(response) {
+    let directives = parseCacheControl(response.headers.get("cache-control"));
+    if ("no-store" in directives || "private" in directives || "no-cache" in directives) {
+        return 0;
+    }
+    for (const name of ["s-maxage", "max-age"]) {
+        if (name in directives) {
+            let seconds = parseInt(directives[name], 10);
+            return Number.isNaN(seconds) ? 0 : Math.max(seconds, 0);
+        }
+    }
+    let expires = response.headers.get("expires");
+    if (expires) {
+        let time = Date.parse(expires);
+        return Number.isNaN(time) ? 0 : Math.max(Math.floor((time - Date.now()) / 1000), 0);
+    }
+    return DEFAULT_TTL;
+}
+
+function checkMethod(request, options) {
+    return request.method === "GET" || !!(options && options.ignoreMethod);
+}
+
+class Cache {
+    #name;
+
+    constructor(name) {
+        this.#name = name;
+    }
+
+    async match(request, options) {
+        request = toRequest(request);
+        if (!checkMethod(request, options)) {
+            return undefined;
+        }
+        let cached = hostcall.cache_match(this.#name, cacheKey(request));
+        if (!cached) {
+            return undefined;
+        }
+        let entry = unpack(cached);
+        // ignoreVary matches the latest put response of the url
+        if (!(options && options.ignoreVary) && !varyMatches(request, entry.vary)) {
+            if (!entry.generation) {
+                return undefined;
+            }
+            cached = hostcall.cache_match(this.#name, variantKey(request, entry.generation, Object.keys(entry.vary)));
+            if (!cached) {
+                return undefined;
+            }
+            entry = unpack(cached);
+            if (!varyMatches(request, entry.vary)) {
+                return undefined;
+            }
+        }
+        return new Response(cached.body, { status: cached.status, headers: entry.headers });
+    }
+
+    async put(request, response) {
+        request = toRequest(request);
+        if (request.method !== "GET") {
+            throw new TypeError("Cache.put() only supports GET requests");
+        }
+        if (!(response instanceof Response)) {
+            throw new TypeError("Cache.put() expects a Response");
+        }
+        if (response.status === 206) {
+            throw new TypeError("Cache.put() does not support partial (206) responses");
+        }
+        let vary = varyHeaders(response);
+        if (vary.includes("*")) {
+            throw new TypeError("Cache.put() does not support responses with 'Vary: *'");
+        }
+        if (response.bodyUsed) {
+            throw new TypeError("Cache.put() response body is already used");
+        }
+        let body = await response.arrayBuffer();
+        let ttl = responseTtl(response);
+        // responses with Set-Cookie are per user, they are never shared by the cache
+        if (ttl <= 0 || response.headers.has("set-cookie")) {
+            return undefined;
+        }
+        let headers = {};
+        for (const [name, value] of response.headers.entries()) {
+            headers[name] = value;
+        }
+        for (const name of vary) {
+            headers[VARY_PREFIX + name] = request.headers.get(name) || "";
+        }
+        let entry = { status: response.status, headers, body_handle: 0, body };
+        if (vary.length > 0) {
+            let current = hostcall.cache_match(this.#name, cacheKey(request));
+            let generation = (current && unpack(current).generation) || crypto.randomUUID();
+            headers[GENERATION_HEADER] = generation;
+            hostcall.cache_put(this.#name, variantKey(request, generation, vary), entry, ttl);
+        }
+        hostcall.cache_put(this.#name, cacheKey(request), entry, ttl);
+        return undefined;
+    }
+
+    async delete(request, options) {
+        request = toRequest(request);
+        if (!checkMethod(request, options)) {
+            return false;
+        }
+        return hostcall.cache_delete(this.#name, cacheKey(request));
+    }
+
+    get [Symbol.toStringTag]() {
+        return "Cache";
+    }
+}
+
+class CacheStorage {
+    #default = new Cache("default");
+    #caches = new Map();
+
+    get default() {
+        return this.#default;
+    }
+
+    async open(name) {
+        name = String(name);
+        if (!this.#caches.has(name)) {
+            this.#caches.set(name, new Cache(`named:${name}`));
+        }
+        return this.#caches.get(name);
+    }
+
+    get [Symbol.toStringTag]() {
+        return "CacheStorage";
+    }
+}
+
+globalThis.Cache = Cache;
+globalThis.CacheStorage = CacheStorage;
+globalThis.caches = new CacheStorage();
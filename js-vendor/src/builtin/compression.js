@@ -0,0 +1,88 @@
+// CompressionStream and DecompressionStream are implemented in Rust, see src/compression.rs.
+// The native object is captured here and removed from globalThis.
+const native = globalThis.compression;
+delete globalThis.compression;
+
+const COMPRESSION_FORMATS = ["gzip", "deflate", "deflate-raw"];
+const DECOMPRESSION_FORMATS = ["gzip", "deflate", "deflate-raw", "br", "brotli", "zstd"];
+
+function toArrayBuffer(chunk) {
+    if (chunk instanceof ArrayBuffer) {
+        return chunk;
+    }
+    if (ArrayBuffer.isView(chunk)) {
+        return chunk.buffer.slice(chunk.byteOffset, chunk.byteOffset + chunk.byteLength);
+    }
+    throw new TypeError("The provided value is not of type '(ArrayBuffer or ArrayBufferView)'");
+}
+
+// createTransform returns a TransformStream that passes chunks through the native codec
+function createTransform(format, compress) {
+    let codec = new native.Codec(format, compress);
+    return new TransformStream({
+        transform(chunk, controller) {
+            let output = codec.write(toArrayBuffer(chunk));
+            if (output.byteLength > 0) {
+                controller.enqueue(new Uint8Array(output));
+            }
+        },
+        flush(controller) {
+            let output = codec.finish();
+            if (output.byteLength > 0) {
+                controller.enqueue(new Uint8Array(output));
+            }
+        },
+        cancel() {
+            codec.close();
+        },
+    });
+}
+
+class CompressionStream {
+    #transform;
+
+    constructor(format) {
+        if (COMPRESSION_FORMATS.indexOf(format) === -1) {
+            throw new TypeError(`Unsupported compression format: '${format}'`);
+        }
+        this.#transform = createTransform(format, true);
+    }
+
+    get readable() {
+        return this.#transform.readable;
+    }
+
+    get writable() {
+        return this.#transform.writable;
+    }
+
+    get [Symbol.toStringTag]() {
+        return "CompressionStream";
+    }
+}
+
+class DecompressionStream {
+    #transform;
+
+    constructor(format) {
+        if (DECOMPRESSION_FORMATS.indexOf(format) === -1) {
+            throw new TypeError(`Unsupported decompression format: '${format}'`);
+        }
+        this.#transform = createTransform(format, false);
+    }
+
+    get readable() {
+        return this.#transform.readable;
+    }
+
+    get writable() {
+        return this.#transform.writable;
+    }
+
+    get [Symbol.toStringTag]() {
+        return "DecompressionStream";
+    }
+}
+
+globalThis.CompressionStream = CompressionStream;
+globalThis.DecompressionStream = DecompressionStream;
@@ -0,0 +1,134 @@
+import { createKey, getKeyMaterial, checkUsages } from "./key";
+import { native } from "./native";
+import { cryptoError, toArrayBuffer, base64UrlEncode, base64UrlDecode, callNative } from "./util";
+
+const JWK_SUFFIX = {
+    "AES-GCM": "GCM",
+    "AES-CBC": "CBC",
+    "AES-CTR": "CTR",
+    "AES-KW": "KW",
+};
+
+function allowedUsages(name) {
+    if (name === "AES-KW") {
+        return ["wrapKey", "unwrapKey"];
+    }
+    return ["encrypt", "decrypt", "wrapKey", "unwrapKey"];
+}
+
+function checkLength(length) {
+    if (length !== 128 && length !== 192 && length !== 256) {
+        throw cryptoError("OperationError", "AES key length must be 128, 192 or 256 bits");
+    }
+}
+
+function generateKey(algorithm, extractable, usages) {
+    checkUsages(usages, allowedUsages(algorithm.name));
+    checkLength(algorithm.length);
+    if (usages.length === 0) {
+        throw cryptoError("SyntaxError", "Usages cannot be empty when creating a key");
+    }
+    let material = native.random_bytes(algorithm.length / 8);
+    return createKey("secret", extractable, { name: algorithm.name, length: algorithm.length }, usages, material);
+}
+
+function importKey(format, keyData, algorithm, extractable, usages) {
+    checkUsages(usages, allowedUsages(algorithm.name));
+    let material;
+    if (format === "raw") {
+        material = toArrayBuffer(keyData).slice(0);
+    } else if (format === "jwk") {
+        if (keyData.kty !== "oct") {
+            throw cryptoError("DataError", "JWK 'kty' must be 'oct'");
+        }
+        material = base64UrlDecode(keyData.k);
+        let expectedAlg = `A${material.byteLength * 8}${JWK_SUFFIX[algorithm.name]}`;
+        if (keyData.alg !== undefined && keyData.alg !== expectedAlg) {
+            throw cryptoError("DataError", `JWK 'alg' must be '${expectedAlg}'`);
+        }
+        if (keyData.ext === false && extractable) {
+            throw cryptoError("DataError", "JWK 'ext' is false but the key is extractable");
+        }
+        if (Array.isArray(keyData.key_ops)) {
+            for (const usage of usages) {
+                if (keyData.key_ops.indexOf(usage) === -1) {
+                    throw cryptoError("DataError", `JWK 'key_ops' does not contain '${usage}'`);
+                }
+            }
+        }
+    } else {
+        throw cryptoError("NotSupportedError", `Format '${format}' is not supported for ${algorithm.name}`);
+    }
+    checkLength(material.byteLength * 8);
+    return createKey("secret", extractable, { name: algorithm.name, length: material.byteLength * 8 }, usages, material);
+}
+
+function exportKey(format, key) {
+    let material = getKeyMaterial(key);
+    if (format === "raw") {
+        return material.slice(0);
+    }
+    if (format === "jwk") {
+        return {
+            kty: "oct",
+            k: base64UrlEncode(material),
+            alg: `A${key.algorithm.length}${JWK_SUFFIX[key.algorithm.name]}`,
+            ext: key.extractable,
+            key_ops: key.usages,
+        };
+    }
+    throw cryptoError("NotSupportedError", `Format '${format}' is not supported for ${key.algorithm.name}`);
+}
+
+function nativeParams(algorithm) {
+    let params = { name: algorithm.name };
+    if (algorithm.iv !== undefined) {
+        params.iv = toArrayBuffer(algorithm.iv);
+    }
+    if (algorithm.additionalData !== undefined) {
+        params.additionalData = toArrayBuffer(algorithm.additionalData);
+    }
+    if (algorithm.tagLength !== undefined) {
+        params.tagLength = algorithm.tagLength;
+    }
+    if (algorithm.counter !== undefined) {
+        params.counter = toArrayBuffer(algorithm.counter);
+    }
+    if (algorithm.length !== undefined) {
+        params.length = algorithm.length;
+    }
+    return params;
+}
+
+function encrypt(algorithm, key, data) {
+    let params = nativeParams(algorithm);
+    return callNative(() => native.aes_encrypt(params, getKeyMaterial(key), toArrayBuffer(data)));
+}
+
+function decrypt(algorithm, key, data) {
+    let params = nativeParams(algorithm);
+    return callNative(() => native.aes_decrypt(params, getKeyMaterial(key), toArrayBuffer(data)));
+}
+
+function wrapKey(_algorithm, key, data) {
+    return callNative(() => native.aes_wrap_key(getKeyMaterial(key), toArrayBuffer(data)));
+}
+
+function unwrapKey(_algorithm, key, data) {
+    return callNative(() => native.aes_unwrap_key(getKeyMaterial(key), toArrayBuffer(data)));
+}
+
+function getKeyLength(algorithm) {
+    checkLength(algorithm.length);
+    return algorithm.length;
+}
+
+const cipher = { generateKey, importKey, exportKey, encrypt, decrypt, getKeyLength };
+const keyWrap = { generateKey, importKey, exportKey, wrapKey, unwrapKey, getKeyLength };
+
+export default {
+    "AES-GCM": cipher,
+    "AES-CBC": cipher,
+    "AES-CTR": cipher,
+    "AES-KW": keyWrap,
+};
@@ -0,0 +1,227 @@
+import { CryptoKey, createKey, getKeyMaterial, checkUsages } from "./key";
+import { native } from "./native";
+import { cryptoError, toArrayBuffer, base64UrlEncode, base64UrlDecode, normalizeAlgorithm, callNative } from "./util";
+
+const JWK_MEMBERS = ["n", "e", "d", "p", "q", "dp", "dq", "qi", "x", "y"];
+const CURVES = ["P-256", "P-384"];
+
+const JWK_KTY = {
+    "RSASSA-PKCS1-v1_5": "RSA",
+    "RSA-PSS": "RSA",
+    "ECDSA": "EC",
+    "Ed25519": "OKP",
+    "ECDH": "EC",
+    "X25519": "OKP",
+};
+
+// usagesOf returns the usages allowed for private and public keys of the algorithm
+function usagesOf(algorithm) {
+    if (algorithm.name === "ECDH" || algorithm.name === "X25519") {
+        return { private: ["deriveKey", "deriveBits"], public: [] };
+    }
+    return { private: ["sign"], public: ["verify"] };
+}
+
+// keyKind returns the kind of native key for the algorithm
+function keyKind(algorithm) {
+    if (algorithm.name === "ECDSA" || algorithm.name === "ECDH") {
+        if (CURVES.indexOf(algorithm.namedCurve) === -1) {
+            throw cryptoError("NotSupportedError", `Unrecognized namedCurve '${algorithm.namedCurve}'`);
+        }
+        return algorithm.namedCurve;
+    }
+    if (algorithm.name === "Ed25519" || algorithm.name === "X25519") {
+        return algorithm.name;
+    }
+    return "RSA";
+}
+
+function normalizeHash(hash) {
+    if (hash === undefined) {
+        return undefined;
+    }
+    let name = normalizeAlgorithm(hash).name.toUpperCase();
+    if (["SHA-1", "SHA-256", "SHA-384", "SHA-512"].indexOf(name) === -1) {
+        throw cryptoError("NotSupportedError", `Unrecognized hash algorithm '${name}'`);
+    }
+    return name;
+}
+
+function jwkAlg(algorithm) {
+    let bits = algorithm.hash ? algorithm.hash.name.slice(4) : "";
+    if (algorithm.name === "RSASSA-PKCS1-v1_5") {
+        return `RS${bits}`;
+    }
+    if (algorithm.name === "RSA-PSS") {
+        return `PS${bits}`;
+    }
+    return undefined;
+}
+
+function keyAlgorithm(algorithm, imported) {
+    if (algorithm.name === "ECDSA" || algorithm.name === "ECDH") {
+        return { name: algorithm.name, namedCurve: algorithm.namedCurve };
+    }
+    if (algorithm.name === "Ed25519" || algorithm.name === "X25519") {
+        return { name: algorithm.name };
+    }
+    let hash = normalizeHash(algorithm.hash);
+    if (!hash) {
+        throw new TypeError(`${algorithm.name} requires a hash algorithm`);
+    }
+    return {
+        name: algorithm.name,
+        modulusLength: imported.modulus_length,
+        publicExponent: new Uint8Array(imported.public_exponent),
+        hash: { name: hash },
+    };
+}
+
+function importKey(format, keyData, algorithm, extractable, usages) {
+    let kind = keyKind(algorithm);
+    let data = keyData;
+    if (format === "jwk") {
+        if (keyData.kty !== JWK_KTY[algorithm.name]) {
+            throw cryptoError("DataError", `JWK 'kty' must be '${JWK_KTY[algorithm.name]}'`);
+        }
+        if (keyData.crv !== undefined && keyData.crv !== kind) {
+            throw cryptoError("DataError", `JWK 'crv' must be '${kind}'`);
+        }
+        if (keyData.ext === false && extractable) {
+            throw cryptoError("DataError", "JWK 'ext' is false but the key is extractable");
+        }
+        data = {};
+        for (const member of JWK_MEMBERS) {
+            if (keyData[member] !== undefined) {
+                data[member] = base64UrlDecode(keyData[member]);
+            }
+        }
+    } else if (format === "spki" || format === "pkcs8" || format === "raw") {
+        data = toArrayBuffer(keyData);
+    } else {
+        throw cryptoError("NotSupportedError", `Format '${format}' is not supported for ${algorithm.name}`);
+    }
+    let imported;
+    try {
+        imported = native.key_import(kind, format, data);
+    } catch (error) {
+        throw cryptoError("DataError", error.message);
+    }
+    let type = imported.private ? "private" : "public";
+    checkUsages(usages, usagesOf(algorithm)[type]);
+    if (type === "private" && usages.length === 0) {
+        throw cryptoError("SyntaxError", "Usages cannot be empty when creating a key");
+    }
+    let keyAlg = keyAlgorithm(algorithm, imported);
+    if (format === "jwk" && keyData.alg !== undefined && jwkAlg(keyAlg) !== undefined && keyData.alg !== jwkAlg(keyAlg)) {
+        throw cryptoError("DataError", `JWK 'alg' must be '${jwkAlg(keyAlg)}'`);
+    }
+    return createKey(type, extractable, keyAlg, usages, imported.der);
+}
+
+function exportKey(format, key) {
+    let kind = keyKind(key.algorithm);
+    let isPrivate = key.type === "private";
+    if ((format === "spki" || format === "raw") && isPrivate) {
+        throw cryptoError("InvalidAccessError", `Format '${format}' requires a public key`);
+    }
+    if (format === "pkcs8" && !isPrivate) {
+        throw cryptoError("InvalidAccessError", "Format 'pkcs8' requires a private key");
+    }
+    let exported = callNative(() => native.key_export(kind, isPrivate, format, getKeyMaterial(key)));
+    if (format !== "jwk") {
+        return exported;
+    }
+    let jwk = { kty: JWK_KTY[key.algorithm.name] };
+    if (kind !== "RSA") {
+        jwk.crv = kind;
+    }
+    for (const member of JWK_MEMBERS) {
+        if (exported[member] !== undefined) {
+            jwk[member] = base64UrlEncode(exported[member]);
+        }
+    }
+    let alg = jwkAlg(key.algorithm);
+    if (alg) {
+        jwk.alg = alg;
+    }
+    jwk.ext = key.extractable;
+    jwk.key_ops = key.usages;
+    return jwk;
+}
+
+function generateKey(algorithm, extractable, usages) {
+    if (keyKind(algorithm) === "RSA") {
+        throw cryptoError("NotSupportedError", `generateKey is not supported for ${algorithm.name}`);
+    }
+    let allowed = usagesOf(algorithm);
+    checkUsages(usages, allowed.private.concat(allowed.public));
+    let keyAlg = keyAlgorithm(algorithm);
+    let pair = callNative(() => native.key_generate(keyKind(algorithm)));
+    let privateUsages = usages.filter(usage => allowed.private.indexOf(usage) !== -1);
+    if (privateUsages.length === 0) {
+        throw cryptoError("SyntaxError", "Usages cannot be empty when creating a key");
+    }
+    return {
+        privateKey: createKey("private", extractable, keyAlg, privateUsages, pair.private_key),
+        publicKey: createKey("public", true, keyAlg, usages.filter(usage => allowed.public.indexOf(usage) !== -1), pair.public_key),
+    };
+}
+
+function nativeParams(algorithm, key) {
+    let params = { name: algorithm.name };
+    if (key.algorithm.hash) {
+        params.hash = key.algorithm.hash.name;
+    } else if (algorithm.name === "ECDSA") {
+        params.hash = normalizeHash(algorithm.hash);
+    }
+    if (algorithm.name === "RSA-PSS") {
+        params.saltLength = algorithm.saltLength;
+    }
+    return params;
+}
+
+function sign(algorithm, key, data) {
+    let params = nativeParams(algorithm, key);
+    return callNative(() => native.sign(params, keyKind(key.algorithm), getKeyMaterial(key), toArrayBuffer(data)));
+}
+
+function verify(algorithm, key, signature, data) {
+    let params = nativeParams(algorithm, key);
+    return callNative(() => native.verify(params, keyKind(key.algorithm), getKeyMaterial(key), toArrayBuffer(signature), toArrayBuffer(data)));
+}
+
+// deriveBits computes the shared secret with algorithm.public, truncated to length bits
+function deriveBits(algorithm, key, length) {
+    let publicKey = algorithm.public;
+    if (!(publicKey instanceof CryptoKey) || publicKey.type !== "public") {
+        throw cryptoError("InvalidAccessError", "algorithm.public must be a public CryptoKey");
+    }
+    if (publicKey.algorithm.name !== key.algorithm.name || publicKey.algorithm.namedCurve !== key.algorithm.namedCurve) {
+        throw cryptoError("InvalidAccessError", "algorithm.public must use the same algorithm as the base key");
+    }
+    let secret = callNative(() => native.ecdh(keyKind(key.algorithm), getKeyMaterial(key), getKeyMaterial(publicKey)));
+    if (length === null || length === undefined) {
+        return secret;
+    }
+    if (length > secret.byteLength * 8) {
+        throw cryptoError("OperationError", `length ${length} is too large for ${key.algorithm.name}`);
+    }
+    let bytes = new Uint8Array(secret.slice(0, Math.ceil(length / 8)));
+    if (length % 8 !== 0) {
+        bytes[bytes.length - 1] &= 0xff << (8 - length % 8);
+    }
+    return bytes.buffer;
+}
+
+const signature = { importKey, exportKey, generateKey, sign, verify };
+const keyAgreement = { importKey, exportKey, generateKey, deriveBits };
+
+export default {
+    "RSASSA-PKCS1-v1_5": signature,
+    "RSA-PSS": signature,
+    "ECDSA": signature,
+    "Ed25519": signature,
+    "ECDH": keyAgreement,
+    "X25519": keyAgreement,
+};
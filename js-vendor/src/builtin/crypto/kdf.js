@@ -0,0 +1,43 @@
+import { createKey, getKeyMaterial, checkUsages } from "./key";
+import { native } from "./native";
+import { cryptoError, toArrayBuffer, normalizeAlgorithm, callNative } from "./util";
+
+// PBKDF2 and HKDF keys can only be imported as raw secrets and never exported
+
+function importKey(format, keyData, algorithm, extractable, usages) {
+    if (format !== "raw") {
+        throw cryptoError("NotSupportedError", `Format '${format}' is not supported for ${algorithm.name}`);
+    }
+    if (extractable) {
+        throw cryptoError("SyntaxError", `${algorithm.name} keys are not extractable`);
+    }
+    checkUsages(usages, ["deriveKey", "deriveBits"]);
+    return createKey("secret", false, { name: algorithm.name }, usages, toArrayBuffer(keyData).slice(0));
+}
+
+function normalizeHash(hash) {
+    if (hash === undefined) {
+        throw new TypeError("hash is required");
+    }
+    return normalizeAlgorithm(hash).name.toUpperCase();
+}
+
+function deriveBits(algorithm, key, length) {
+    if (length === null || length === undefined || length % 8 !== 0) {
+        throw cryptoError("OperationError", "length must be a multiple of 8");
+    }
+    let hash = normalizeHash(algorithm.hash);
+    let salt = toArrayBuffer(algorithm.salt);
+    if (algorithm.name === "PBKDF2") {
+        return callNative(() => native.pbkdf2(hash, getKeyMaterial(key), salt, algorithm.iterations, length));
+    }
+    let info = toArrayBuffer(algorithm.info);
+    return callNative(() => native.hkdf(hash, getKeyMaterial(key), salt, info, length));
+}
+
+const kdf = { importKey, deriveBits };
+
+export default {
+    "PBKDF2": kdf,
+    "HKDF": kdf,
+};
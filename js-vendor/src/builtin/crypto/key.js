@@ -0,0 +1,68 @@
+import { cryptoError } from "./util";
+
+// keyMaterial keeps the raw key data out of reach of user code
+const keyMaterial = new WeakMap();
+const constructToken = Symbol("CryptoKey");
+
+class CryptoKey {
+    #_type;
+    #_extractable;
+    #_algorithm;
+    #_usages;
+
+    constructor(token, type, extractable, algorithm, usages, material) {
+        if (token !== constructToken) {
+            throw new TypeError("Illegal constructor");
+        }
+        this.#_type = type;
+        this.#_extractable = extractable;
+        this.#_algorithm = Object.freeze(algorithm);
+        this.#_usages = usages;
+        keyMaterial.set(this, material);
+    }
+
+    get [Symbol.toStringTag]() {
+        return 'CryptoKey';
+    }
+
+    get type() {
+        return this.#_type;
+    }
+
+    get extractable() {
+        return this.#_extractable;
+    }
+
+    get algorithm() {
+        return this.#_algorithm;
+    }
+
+    get usages() {
+        return this.#_usages.slice();
+    }
+}
+
+function createKey(type, extractable, algorithm, usages, material) {
+    return new CryptoKey(constructToken, type, extractable, algorithm, usages, material);
+}
+
+function getKeyMaterial(key) {
+    if (!(key instanceof CryptoKey)) {
+        throw new TypeError("parameter is not of type 'CryptoKey'");
+    }
+    return keyMaterial.get(key);
+}
+
+// checkUsages verifies requested usages are all allowed by the algorithm
+function checkUsages(usages, allowed) {
+    if (!Array.isArray(usages)) {
+        throw new TypeError("usages is not an array");
+    }
+    for (const usage of usages) {
+        if (allowed.indexOf(usage) === -1) {
+            throw cryptoError("SyntaxError", `Cannot create a key using the specified key usage '${usage}'`);
+        }
+    }
+}
+
+export { CryptoKey, createKey, getKeyMaterial, checkUsages };
@@ -0,0 +1,45 @@
+import { CryptoKey } from "./key";
+import { SubtleCrypto } from "./subtle";
+import { native } from "./native";
+import { cryptoError } from "./util";
+
+// crypto is the WebCrypto global, the native helpers stay in ./native
+const crypto = {};
+
+const integerArrays = [
+    '[object Int8Array]',
+    '[object Uint8Array]',
+    '[object Uint8ClampedArray]',
+    '[object Int16Array]',
+    '[object Uint16Array]',
+    '[object Int32Array]',
+    '[object Uint32Array]',
+    '[object BigInt64Array]',
+    '[object BigUint64Array]',
+]
+
+crypto.getRandomValues = function (array) {
+    if (integerArrays.indexOf(Object.prototype.toString.call(array)) === -1) {
+        throw cryptoError("TypeMismatchError", "The data provided is not an integer typed array");
+    }
+    if (array.byteLength > 65536) {
+        throw cryptoError("QuotaExceededError", `The ArrayBufferView's byte length (${array.byteLength}) exceeds 65536`);
+    }
+    let bytes = new Uint8Array(native.random_bytes(array.byteLength));
+    new Uint8Array(array.buffer, array.byteOffset, array.byteLength).set(bytes);
+    return array;
+}
+
+crypto.randomUUID = function () {
+    let bytes = new Uint8Array(native.random_bytes(16));
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+    let hex = Array.from(bytes, b => b.toString(16).padStart(2, "0")).join("");
+    return `${hex.slice(0, 8)}-${hex.slice(8, 12)}-${hex.slice(12, 16)}-${hex.slice(16, 20)}-${hex.slice(20)}`;
+}
+
+crypto.subtle = new SubtleCrypto();
+
+globalThis.crypto = crypto;
+globalThis.CryptoKey = CryptoKey;
+globalThis.SubtleCrypto = SubtleCrypto;
@@ -0,0 +1,4 @@
+// native is the native crypto object built in src/crypto/mod.rs.
+// It is captured here and removed from globalThis, the crypto global only has the WebCrypto APIs.
+export const native = globalThis.landCrypto;
+delete globalThis.landCrypto;
@@ -0,0 +1,143 @@
+import { CryptoKey } from "./key";
+import { cryptoError, toArrayBuffer, normalizeAlgorithm } from "./util";
+import aes from "./aes";
+import asymmetric from "./asymmetric";
+import kdf from "./kdf";
+
+// algorithms maps the algorithm name to its implementation,
+// each implementation provides the operations it supports
+const algorithms = Object.assign({}, aes, asymmetric, kdf);
+
+function lookup(algorithm, operation) {
+    let normalized = normalizeAlgorithm(algorithm);
+    let name = Object.keys(algorithms).find(key => key.toUpperCase() === normalized.name.toUpperCase());
+    if (!name || typeof algorithms[name][operation] !== "function") {
+        throw cryptoError("NotSupportedError", `Algorithm '${normalized.name}' does not support '${operation}'`);
+    }
+    normalized.name = name;
+    return [normalized, algorithms[name]];
+}
+
+function checkKey(key, algorithm, usage) {
+    if (!(key instanceof CryptoKey)) {
+        throw new TypeError("parameter is not of type 'CryptoKey'");
+    }
+    if (key.algorithm.name !== algorithm.name) {
+        throw cryptoError("InvalidAccessError", `The key is not a ${algorithm.name} key`);
+    }
+    if (key.usages.indexOf(usage) === -1) {
+        throw cryptoError("InvalidAccessError", `The key does not support the '${usage}' operation`);
+    }
+}
+
+function serializeKey(format, exported) {
+    if (format === "jwk") {
+        return new TextEncoder().encode(JSON.stringify(exported)).buffer;
+    }
+    return exported;
+}
+
+function deserializeKey(format, bytes) {
+    if (format === "jwk") {
+        return JSON.parse(new TextDecoder().decode(bytes));
+    }
+    return bytes;
+}
+
+class SubtleCrypto {
+    get [Symbol.toStringTag]() {
+        return 'SubtleCrypto';
+    }
+
+    async encrypt(algorithm, key, data) {
+        let [normalized, impl] = lookup(algorithm, "encrypt");
+        checkKey(key, normalized, "encrypt");
+        return impl.encrypt(normalized, key, data);
+    }
+
+    async decrypt(algorithm, key, data) {
+        let [normalized, impl] = lookup(algorithm, "decrypt");
+        checkKey(key, normalized, "decrypt");
+        return impl.decrypt(normalized, key, data);
+    }
+
+    async sign(algorithm, key, data) {
+        let [normalized, impl] = lookup(algorithm, "sign");
+        checkKey(key, normalized, "sign");
+        return impl.sign(normalized, key, data);
+    }
+
+    async verify(algorithm, key, signature, data) {
+        let [normalized, impl] = lookup(algorithm, "verify");
+        checkKey(key, normalized, "verify");
+        return impl.verify(normalized, key, signature, data);
+    }
+
+    async deriveBits(algorithm, baseKey, length) {
+        let [normalized, impl] = lookup(algorithm, "deriveBits");
+        checkKey(baseKey, normalized, "deriveBits");
+        return impl.deriveBits(normalized, baseKey, length);
+    }
+
+    async deriveKey(algorithm, baseKey, derivedKeyType, extractable, keyUsages) {
+        let [normalized, impl] = lookup(algorithm, "deriveBits");
+        let [derivedAlgorithm, derivedImpl] = lookup(derivedKeyType, "getKeyLength");
+        checkKey(baseKey, normalized, "deriveKey");
+        let length = derivedImpl.getKeyLength(derivedAlgorithm);
+        let bits = impl.deriveBits(normalized, baseKey, length);
+        return this.importKey("raw", bits, derivedKeyType, extractable, keyUsages);
+    }
+
+    async generateKey(algorithm, extractable, keyUsages) {
+        let [normalized, impl] = lookup(algorithm, "generateKey");
+        return impl.generateKey(normalized, !!extractable, keyUsages);
+    }
+
+    async importKey(format, keyData, algorithm, extractable, keyUsages) {
+        let [normalized, impl] = lookup(algorithm, "importKey");
+        return impl.importKey(format, keyData, normalized, !!extractable, keyUsages);
+    }
+
+    async exportKey(format, key) {
+        if (!(key instanceof CryptoKey)) {
+            throw new TypeError("parameter is not of type 'CryptoKey'");
+        }
+        if (!key.extractable) {
+            throw cryptoError("InvalidAccessError", "key is not extractable");
+        }
+        let [_normalized, impl] = lookup(key.algorithm, "exportKey");
+        return impl.exportKey(format, key);
+    }
+
+    async wrapKey(format, key, wrappingKey, wrapAlgorithm) {
+        let normalized, impl, operation;
+        try {
+            [normalized, impl] = lookup(wrapAlgorithm, "wrapKey");
+            operation = "wrapKey";
+        } catch (error) {
+            [normalized, impl] = lookup(wrapAlgorithm, "encrypt");
+            operation = "encrypt";
+        }
+        checkKey(wrappingKey, normalized, "wrapKey");
+        let exported = await this.exportKey(format, key);
+        let bytes = serializeKey(format, exported);
+        return impl[operation](normalized, wrappingKey, bytes);
+    }
+
+    async unwrapKey(format, wrappedKey, unwrappingKey, unwrapAlgorithm, unwrappedKeyAlgorithm, extractable, keyUsages) {
+        let normalized, impl, operation;
+        try {
+            [normalized, impl] = lookup(unwrapAlgorithm, "unwrapKey");
+            operation = "unwrapKey";
+        } catch (error) {
+            [normalized, impl] = lookup(unwrapAlgorithm, "decrypt");
+            operation = "decrypt";
+        }
+        checkKey(unwrappingKey, normalized, "unwrapKey");
+        let bytes = impl[operation](normalized, unwrappingKey, toArrayBuffer(wrappedKey));
+        let keyData = deserializeKey(format, bytes);
+        return this.importKey(format, keyData, unwrappedKeyAlgorithm, extractable, keyUsages);
+    }
+}
+
+export { SubtleCrypto, algorithms };
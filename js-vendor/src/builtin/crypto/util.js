@@ -0,0 +1,80 @@
+// DOMException is not provided by QuickJS, WebCrypto reports errors with it
+class DOMException extends Error {
+    constructor(message, name) {
+        super(message);
+        this.name = name || "Error";
+    }
+}
+
+if (typeof globalThis.DOMException !== "function") {
+    globalThis.DOMException = DOMException;
+}
+
+function cryptoError(name, message) {
+    return new globalThis.DOMException(message, name);
+}
+
+// toArrayBuffer copies BufferSource into an ArrayBuffer that native crypto functions accept
+function toArrayBuffer(data) {
+    if (data instanceof ArrayBuffer) {
+        return data;
+    }
+    if (ArrayBuffer.isView(data)) {
+        return data.buffer.slice(data.byteOffset, data.byteOffset + data.byteLength);
+    }
+    throw new TypeError("Failed to convert value to 'BufferSource'");
+}
+
+function base64UrlEncode(buffer) {
+    let bytes = new Uint8Array(buffer);
+    let binary = "";
+    for (let i = 0; i < bytes.length; i++) {
+        binary += String.fromCharCode(bytes[i]);
+    }
+    return btoa(binary).replace(/\+/g, "-").replace(/\//g, "_").replace(/=+$/, "");
+}
+
+function base64UrlDecode(str) {
+    if (typeof str !== "string") {
+        throw cryptoError("DataError", "Invalid base64url value in JWK");
+    }
+    let base64 = str.replace(/-/g, "+").replace(/_/g, "/");
+    while (base64.length % 4 !== 0) {
+        base64 += "=";
+    }
+    let binary = atob(base64);
+    let bytes = new Uint8Array(binary.length);
+    for (let i = 0; i < binary.length; i++) {
+        bytes[i] = binary.charCodeAt(i);
+    }
+    return bytes.buffer;
+}
+
+// normalizeAlgorithm converts string or object algorithm identifiers to an object with a name
+function normalizeAlgorithm(algorithm) {
+    if (typeof algorithm === "string") {
+        return { name: algorithm };
+    }
+    if (algorithm && typeof algorithm.name === "string") {
+        return Object.assign({}, algorithm);
+    }
+    throw new TypeError("Algorithm: Unrecognized name");
+}
+
+// callNative converts errors thrown by native crypto functions to OperationError
+function callNative(fn) {
+    try {
+        return fn();
+    } catch (error) {
+        throw cryptoError("OperationError", error.message);
+    }
+}
+
+export {
+    cryptoError,
+    toArrayBuffer,
+    base64UrlEncode,
+    base64UrlDecode,
+    normalizeAlgorithm,
+    callNative,
+};
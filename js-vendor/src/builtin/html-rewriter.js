@@ -0,0 +1,360 @@
+// HTMLRewriter is implemented in Rust with lol_html, see src/html_rewriter.rs.
+// The native object is captured here and removed from globalThis.
+const native = globalThis.htmlRewriter;
+delete globalThis.htmlRewriter;
+
+function toArrayBuffer(chunk) {
+    if (chunk instanceof ArrayBuffer) {
+        return chunk;
+    }
+    if (ArrayBuffer.isView(chunk)) {
+        return chunk.buffer.slice(chunk.byteOffset, chunk.byteOffset + chunk.byteLength);
+    }
+    if (typeof chunk === "string") {
+        return new TextEncoder().encode(chunk).buffer;
+    }
+    throw new TypeError("The provided value is not of type '(ArrayBuffer or ArrayBufferView)'");
+}
+
+// Content records the mutations of a node, they are applied by the native rewriter after the handler returns
+class Content {
+    #mutations = [];
+    #removed = false;
+
+    record(op, args, html) {
+        this.#mutations.push({ op, args, html: !!html });
+        return this;
+    }
+
+    mutate(op, content, options) {
+        return this.record(op, content === undefined ? [] : [String(content)], options && options.html);
+    }
+
+    markRemoved(op) {
+        this.#removed = true;
+        return this.mutate(op);
+    }
+
+    get removed() {
+        return this.#removed;
+    }
+
+    get mutations() {
+        return this.#mutations;
+    }
+
+    before(content, options) {
+        return this.mutate("before", content, options);
+    }
+
+    after(content, options) {
+        return this.mutate("after", content, options);
+    }
+
+    replace(content, options) {
+        this.mutate("replace", content, options);
+        this.#removed = true;
+        return this;
+    }
+
+    remove() {
+        return this.markRemoved("remove");
+    }
+}
+
+class Element extends Content {
+    #snapshot;
+
+    constructor(snapshot) {
+        super();
+        this.#snapshot = snapshot;
+    }
+
+    get tagName() {
+        return this.#snapshot.tagName;
+    }
+
+    set tagName(name) {
+        name = String(name);
+        this.mutate("setTagName", name);
+        this.#snapshot.tagName = name.toLowerCase();
+    }
+
+    get namespaceURI() {
+        return this.#snapshot.namespaceURI;
+    }
+
+    get selfClosing() {
+        return this.#snapshot.selfClosing;
+    }
+
+    get canHaveContent() {
+        return this.#snapshot.canHaveContent;
+    }
+
+    get attributes() {
+        return this.#snapshot.attributes.map(([name, value]) => [name, value])[Symbol.iterator]();
+    }
+
+    getAttribute(name) {
+        name = String(name).toLowerCase();
+        let attribute = this.#snapshot.attributes.find(([key]) => key === name);
+        return attribute ? attribute[1] : null;
+    }
+
+    hasAttribute(name) {
+        return this.getAttribute(name) !== null;
+    }
+
+    setAttribute(name, value) {
+        name = String(name);
+        value = String(value);
+        this.record("setAttribute", [name, value]);
+        let key = name.toLowerCase();
+        let attribute = this.#snapshot.attributes.find(([k]) => k === key);
+        if (attribute) {
+            attribute[1] = value;
+        } else {
+            this.#snapshot.attributes.push([key, value]);
+        }
+        return this;
+    }
+
+    removeAttribute(name) {
+        name = String(name);
+        this.record("removeAttribute", [name]);
+        let key = name.toLowerCase();
+        this.#snapshot.attributes = this.#snapshot.attributes.filter(([k]) => k !== key);
+        return this;
+    }
+
+    prepend(content, options) {
+        return this.mutate("prepend", content, options);
+    }
+
+    append(content, options) {
+        return this.mutate("append", content, options);
+    }
+
+    setInnerContent(content, options) {
+        return this.mutate("setInnerContent", content, options);
+    }
+
+    removeAndKeepContent() {
+        return this.markRemoved("removeAndKeepContent");
+    }
+
+    get [Symbol.toStringTag]() {
+        return "Element";
+    }
+}
+
+class Text extends Content {
+    #snapshot;
+
+    constructor(snapshot) {
+        super();
+        this.#snapshot = snapshot;
+    }
+
+    get text() {
+        return this.#snapshot.text;
+    }
+
+    get lastInTextNode() {
+        return this.#snapshot.lastInTextNode;
+    }
+
+    get [Symbol.toStringTag]() {
+        return "Text";
+    }
+}
+
+class Comment extends Content {
+    #snapshot;
+
+    constructor(snapshot) {
+        super();
+        this.#snapshot = snapshot;
+    }
+
+    get text() {
+        return this.#snapshot.text;
+    }
+
+    set text(text) {
+        text = String(text);
+        this.mutate("setText", text);
+        this.#snapshot.text = text;
+    }
+
+    get [Symbol.toStringTag]() {
+        return "Comment";
+    }
+}
+
+class Doctype {
+    #snapshot;
+
+    constructor(snapshot) {
+        this.#snapshot = snapshot;
+    }
+
+    get name() {
+        return this.#snapshot.name;
+    }
+
+    get publicId() {
+        return this.#snapshot.publicId;
+    }
+
+    get systemId() {
+        return this.#snapshot.systemId;
+    }
+
+    get mutations() {
+        return [];
+    }
+
+    get [Symbol.toStringTag]() {
+        return "Doctype";
+    }
+}
+
+class DocumentEnd extends Content {
+    append(content, options) {
+        return this.mutate("append", content, options);
+    }
+
+    get [Symbol.toStringTag]() {
+        return "DocumentEnd";
+    }
+}
+
+// NODES maps the dispatch kind to the handler method and the node class
+const NODES = {
+    element: ["element", Element],
+    comments: ["comments", Comment],
+    text: ["text", Text],
+    doctype: ["doctype", Doctype],
+    doc_comments: ["comments", Comment],
+    doc_text: ["text", Text],
+    end: ["end", DocumentEnd],
+};
+
+// ELEMENT_KINDS are dispatched to the handlers of HTMLRewriter.on() at index, others to all onDocument() handlers
+const ELEMENT_KINDS = ["element", "comments", "text"];
+
+function isFunction(handlers, name) {
+    return !!handlers && typeof handlers[name] === "function";
+}
+
+class HTMLRewriter {
+    #elementHandlers = [];
+    #documentHandlers = [];
+
+    on(selector, handlers) {
+        if (typeof handlers !== "object" || handlers === null) {
+            throw new TypeError("HTMLRewriter.on() handlers must be an object");
+        }
+        this.#elementHandlers.push({ selector: String(selector), handlers });
+        return this;
+    }
+
+    onDocument(handlers) {
+        if (typeof handlers !== "object" || handlers === null) {
+            throw new TypeError("HTMLRewriter.onDocument() handlers must be an object");
+        }
+        this.#documentHandlers.push(handlers);
+        return this;
+    }
+
+    #spec() {
+        let documentHas = name => this.#documentHandlers.some(handlers => isFunction(handlers, name));
+        return {
+            elements: this.#elementHandlers.map(({ selector, handlers }) => ({
+                selector,
+                element: isFunction(handlers, "element"),
+                comments: isFunction(handlers, "comments"),
+                text: isFunction(handlers, "text"),
+            })),
+            doctype: documentHas("doctype"),
+            comments: documentHas("comments"),
+            text: documentHas("text"),
+            end: documentHas("end"),
+        };
+    }
+
+    transform(response) {
+        if (!(response instanceof Response)) {
+            throw new TypeError("HTMLRewriter.transform() expects a Response");
+        }
+        if (response.body === null) {
+            return response;
+        }
+        let elementHandlers = this.#elementHandlers.slice();
+        let documentHandlers = this.#documentHandlers.slice();
+        // a handler error is kept here, the native call fails and the error is thrown again to the stream
+        let failure = null;
+        let dispatch = (kind, index, snapshot) => {
+            let [method, NodeClass] = NODES[kind];
+            let node = kind === "end" ? new DocumentEnd() : new NodeClass(snapshot);
+            let targets = ELEMENT_KINDS.includes(kind)
+                ? [elementHandlers[index].handlers]
+                : documentHandlers.filter(handlers => isFunction(handlers, method));
+            try {
+                for (const handlers of targets) {
+                    let result = handlers[method](node);
+                    if (result && typeof result.then === "function") {
+                        throw new TypeError("HTMLRewriter handlers must be synchronous, async handlers are not supported");
+                    }
+                }
+            } catch (error) {
+                failure = error;
+                return null;
+            }
+            return node.mutations;
+        };
+        let rewriter = new native.Rewriter(this.#spec());
+        let run = fn => {
+            try {
+                return fn();
+            } catch (error) {
+                let thrown = failure || error;
+                failure = null;
+                throw thrown;
+            }
+        };
+        let transform = new TransformStream({
+            transform(chunk, controller) {
+                let output = run(() => rewriter.write(toArrayBuffer(chunk), dispatch));
+                if (output.byteLength > 0) {
+                    controller.enqueue(new Uint8Array(output));
+                }
+            },
+            flush(controller) {
+                let output = run(() => rewriter.end(dispatch));
+                if (output.byteLength > 0) {
+                    controller.enqueue(new Uint8Array(output));
+                }
+            },
+            cancel() {
+                rewriter.close();
+            },
+        });
+        // the rewritten body length is unknown until the stream ends
+        let headers = new Headers(response.headers);
+        headers.delete("content-length");
+        return new Response(response.body.pipeThrough(transform), {
+            status: response.status,
+            statusText: response.statusText,
+            headers,
+        });
+    }
+
+    get [Symbol.toStringTag]() {
+        return "HTMLRewriter";
+    }
+}
+
+globalThis.HTMLRewriter = HTMLRewriter;
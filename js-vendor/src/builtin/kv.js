@@ -0,0 +1,158 @@
+import { declaredBindings } from "./bindings";
+
+// KV namespace bindings, the values are stored by the kv hostcalls, see src/kv.rs.
+// The bindings are declared by env LAND_KV_BINDINGS, such as "SESSIONS,FLAGS=feature-flags",
+// a binding without "=" uses its name as the namespace.
+
+const VALUE_TYPES = ["text", "json", "arrayBuffer", "stream"];
+
+function checkKey(key) {
+    if (typeof key !== "string" || key === "") {
+        throw new TypeError("KV key must be a non-empty string");
+    }
+    return key;
+}
+
+function readType(options) {
+    let type = typeof options === "string" ? options : (options && options.type) || "text";
+    if (VALUE_TYPES.indexOf(type) === -1) {
+        throw new TypeError(`Unknown KV value type: '${type}'`);
+    }
+    return type;
+}
+
+function decodeValue(buffer, type) {
+    switch (type) {
+        case "json":
+            return JSON.parse(new TextDecoder().decode(buffer));
+        case "arrayBuffer":
+            return buffer;
+        case "stream":
+            return new Response(buffer).body;
+        default:
+            return new TextDecoder().decode(buffer);
+    }
+}
+
+// metadata is sent as encoded JSON, so it is safe in the headers of the bindings service
+function encodeMetadata(metadata) {
+    if (metadata === undefined || metadata === null) {
+        return undefined;
+    }
+    return encodeURIComponent(JSON.stringify(metadata));
+}
+
+function decodeMetadata(metadata) {
+    if (metadata === undefined || metadata === null) {
+        return null;
+    }
+    return JSON.parse(decodeURIComponent(metadata));
+}
+
+async function encodeValue(value) {
+    if (typeof value === "string") {
+        return value;
+    }
+    if (value instanceof ArrayBuffer) {
+        return value;
+    }
+    if (ArrayBuffer.isView(value)) {
+        return value.buffer.slice(value.byteOffset, value.byteOffset + value.byteLength);
+    }
+    if (value && typeof value.getReader === "function") {
+        return await new Response(value).arrayBuffer();
+    }
+    throw new TypeError("KV put() value must be a string, ArrayBuffer, ArrayBufferView or ReadableStream");
+}
+
+// expirationOf returns the absolute expiration in unix seconds, 0 if the value never expires
+function expirationOf(options) {
+    if (!options) {
+        return 0;
+    }
+    if (options.expiration !== undefined) {
+        let expiration = Number(options.expiration);
+        if (!Number.isInteger(expiration) || expiration <= Date.now() / 1000) {
+            throw new TypeError("KV put() expiration must be a unix time in the future");
+        }
+        return expiration;
+    }
+    if (options.expirationTtl !== undefined) {
+        let ttl = Number(options.expirationTtl);
+        if (!Number.isInteger(ttl) || ttl <= 0) {
+            throw new TypeError("KV put() expirationTtl must be a positive integer");
+        }
+        return Math.floor(Date.now() / 1000) + ttl;
+    }
+    return 0;
+}
+
+class KVNamespace {
+    #namespace;
+
+    constructor(namespace) {
+        this.#namespace = namespace;
+    }
+
+    async get(key, options) {
+        return (await this.getWithMetadata(key, options)).value;
+    }
+
+    async getWithMetadata(key, options) {
+        let type = readType(options);
+        let entry = hostcall.kv_get(this.#namespace, checkKey(key));
+        if (!entry) {
+            return { value: null, metadata: null };
+        }
+        return {
+            value: decodeValue(entry.value, type),
+            metadata: decodeMetadata(entry.metadata),
+        };
+    }
+
+    async put(key, value, options) {
+        checkKey(key);
+        let body = await encodeValue(value);
+        hostcall.kv_put(this.#namespace, key, body, {
+            expiration: expirationOf(options),
+            metadata: encodeMetadata(options && options.metadata),
+        });
+    }
+
+    async delete(key) {
+        hostcall.kv_delete(this.#namespace, checkKey(key));
+    }
+
+    async list(options) {
+        options = options || {};
+        let result = hostcall.kv_list(this.#namespace, {
+            prefix: options.prefix ? String(options.prefix) : "",
+            limit: options.limit === undefined ? 1000 : Number(options.limit),
+            cursor: options.cursor ? String(options.cursor) : "",
+        });
+        let keys = result.keys.map(item => {
+            let key = { name: item.name };
+            if (item.expiration) {
+                key.expiration = item.expiration;
+            }
+            if (item.metadata !== undefined && item.metadata !== null) {
+                key.metadata = decodeMetadata(item.metadata);
+            }
+            return key;
+        });
+        let list = { keys, list_complete: !!result.list_complete };
+        if (!list.list_complete) {
+            list.cursor = result.cursor;
+        }
+        return list;
+    }
+
+    get [Symbol.toStringTag]() {
+        return "KVNamespace";
+    }
+}
+
+// kvBinding returns the KVNamespace of the binding name, undefined if it is not declared
+const kvBinding = declaredBindings("LAND_KV_BINDINGS", namespace => new KVNamespace(namespace));
+
+export { KVNamespace, kvBinding };
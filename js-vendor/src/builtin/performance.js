@@ -0,0 +1,182 @@
+// Performance API, the clock is the monotonic host clock of the performance hostcalls, see src/performance.rs.
+// The clock starts with every request, so performance.now() is the time since the request began
+// and the marks and measures of a previous request are dropped.
+
+class PerformanceEntry {
+    #name;
+    #entryType;
+    #startTime;
+    #duration;
+
+    constructor(name, entryType, startTime, duration) {
+        this.#name = name;
+        this.#entryType = entryType;
+        this.#startTime = startTime;
+        this.#duration = duration;
+    }
+
+    get name() {
+        return this.#name;
+    }
+
+    get entryType() {
+        return this.#entryType;
+    }
+
+    get startTime() {
+        return this.#startTime;
+    }
+
+    get duration() {
+        return this.#duration;
+    }
+
+    toJSON() {
+        return {
+            name: this.name,
+            entryType: this.entryType,
+            startTime: this.startTime,
+            duration: this.duration,
+            detail: this.detail,
+        };
+    }
+}
+
+class PerformanceMark extends PerformanceEntry {
+    constructor(name, options) {
+        let startTime = options && options.startTime !== undefined ? options.startTime : performance.now();
+        if (typeof startTime !== "number" || !(startTime >= 0)) {
+            throw new TypeError("PerformanceMark startTime must be a non-negative number");
+        }
+        super(String(name), "mark", startTime, 0);
+        this.detail = options && options.detail !== undefined ? options.detail : null;
+    }
+
+    get [Symbol.toStringTag]() {
+        return "PerformanceMark";
+    }
+}
+
+class PerformanceMeasure extends PerformanceEntry {
+    constructor(name, startTime, duration, detail) {
+        super(name, "measure", startTime, duration);
+        this.detail = detail;
+    }
+
+    get [Symbol.toStringTag]() {
+        return "PerformanceMeasure";
+    }
+}
+
+class Performance {
+    #entries = [];
+    #timeOrigin;
+
+    // entries returns the entries of the running request
+    #current() {
+        let timeOrigin = hostcall.performance_time_origin();
+        if (timeOrigin !== this.#timeOrigin) {
+            this.#timeOrigin = timeOrigin;
+            this.#entries = [];
+        }
+        return this.#entries;
+    }
+
+    // time returns the startTime of the latest mark named name, or the number
+    #time(value) {
+        if (typeof value === "number") {
+            return value;
+        }
+        let marks = this.getEntriesByName(String(value), "mark");
+        if (marks.length === 0) {
+            throw new SyntaxError(`The mark '${value}' does not exist`);
+        }
+        return marks[marks.length - 1].startTime;
+    }
+
+    get timeOrigin() {
+        return hostcall.performance_time_origin();
+    }
+
+    now() {
+        return hostcall.performance_now();
+    }
+
+    mark(name, options) {
+        let mark = new PerformanceMark(name, options);
+        this.#current().push(mark);
+        return mark;
+    }
+
+    // measure(name, startMark, endMark) or measure(name, { start, end, duration, detail }),
+    // the measures are added to the Server-Timing header when it is enabled
+    measure(name, startOrOptions, endMark) {
+        let start = 0;
+        let end;
+        let detail = null;
+        if (startOrOptions && typeof startOrOptions === "object") {
+            let { start: startOption, end: endOption, duration } = startOrOptions;
+            if (startOption !== undefined && endOption !== undefined && duration !== undefined) {
+                throw new TypeError("PerformanceMeasure options can not have start, end and duration");
+            }
+            if (startOption !== undefined) {
+                start = this.#time(startOption);
+            }
+            if (endOption !== undefined) {
+                end = this.#time(endOption);
+            }
+            if (duration !== undefined) {
+                if (end === undefined) {
+                    end = start + duration;
+                } else {
+                    start = end - duration;
+                }
+            }
+            detail = startOrOptions.detail !== undefined ? startOrOptions.detail : null;
+        } else if (startOrOptions !== undefined) {
+            start = this.#time(startOrOptions);
+        }
+        if (endMark !== undefined) {
+            end = this.#time(endMark);
+        }
+        if (end === undefined) {
+            end = this.now();
+        }
+        let measure = new PerformanceMeasure(String(name), start, end - start, detail);
+        this.#current().push(measure);
+        hostcall.server_timing_measure(measure.name, Math.max(measure.duration, 0));
+        return measure;
+    }
+
+    getEntries() {
+        return this.#current().slice();
+    }
+
+    getEntriesByName(name, type) {
+        return this.#current().filter(entry => entry.name === name && (type === undefined || entry.entryType === type));
+    }
+
+    getEntriesByType(type) {
+        return this.#current().filter(entry => entry.entryType === type);
+    }
+
+    clearMarks(name) {
+        this.#entries = this.#current().filter(entry => entry.entryType !== "mark" || (name !== undefined && entry.name !== name));
+    }
+
+    clearMeasures(name) {
+        this.#entries = this.#current().filter(entry => entry.entryType !== "measure" || (name !== undefined && entry.name !== name));
+    }
+
+    toJSON() {
+        return { timeOrigin: this.timeOrigin };
+    }
+
+    get [Symbol.toStringTag]() {
+        return "Performance";
+    }
+}
+
+const performance = new Performance();
+
+export { Performance, PerformanceEntry, PerformanceMark, PerformanceMeasure, performance };
@@ -0,0 +1,119 @@
+import { declaredBindings } from "./bindings";
+
+// Queue bindings, the messages are sent by the queue_send hostcall, see src/queue.rs.
+// The producer bindings are declared by env LAND_QUEUE_BINDINGS, such as "JOBS,EMAILS=email-queue".
+// The platform delivers the messages to the queue(batch, env, ctx) handler of the consumer script.
+
+const CONTENT_TYPES = ["json", "text", "bytes"];
+
+function toMessage(body, options) {
+    let contentType = (options && options.contentType) || "json";
+    if (CONTENT_TYPES.indexOf(contentType) === -1) {
+        throw new TypeError(`Unknown queue content type: '${contentType}'`);
+    }
+    if (body === undefined) {
+        throw new TypeError("Queue message body must not be undefined");
+    }
+    if (contentType === "bytes" && ArrayBuffer.isView(body)) {
+        body = body.buffer.slice(body.byteOffset, body.byteOffset + body.byteLength);
+    }
+    return { body, contentType };
+}
+
+class Queue {
+    #queue;
+
+    constructor(queue) {
+        this.#queue = queue;
+    }
+
+    async send(body, options) {
+        hostcall.queue_send(this.#queue, [toMessage(body, options)]);
+    }
+
+    async sendBatch(messages) {
+        if (!messages || typeof messages[Symbol.iterator] !== "function") {
+            throw new TypeError("Queue sendBatch() expects an iterable of messages");
+        }
+        hostcall.queue_send(this.#queue, Array.from(messages, message => toMessage(message.body, message)));
+    }
+
+    get [Symbol.toStringTag]() {
+        return "Queue";
+    }
+}
+
+// Message is a delivered message, ack() and retry() decide its outcome over the batch outcome
+class Message {
+    #message;
+    #outcomes;
+
+    constructor(message, outcomes) {
+        this.#message = message;
+        this.#outcomes = outcomes;
+    }
+
+    get id() {
+        return this.#message.id;
+    }
+
+    get timestamp() {
+        return new Date(this.#message.timestamp);
+    }
+
+    get body() {
+        return this.#message.body;
+    }
+
+    get attempts() {
+        return this.#message.attempts;
+    }
+
+    ack() {
+        this.#outcomes.messages[this.id] = "ack";
+    }
+
+    retry() {
+        this.#outcomes.messages[this.id] = "retry";
+    }
+
+    get [Symbol.toStringTag]() {
+        return "Message";
+    }
+}
+
+class MessageBatch {
+    #outcomes;
+
+    constructor(batch, outcomes) {
+        this.#outcomes = outcomes;
+        this.queue = batch.queue;
+        this.messages = batch.messages.map(message => new Message(message, outcomes));
+    }
+
+    ackAll() {
+        this.#outcomes.batch = "ack";
+    }
+
+    retryAll() {
+        this.#outcomes.batch = "retry";
+    }
+
+    get [Symbol.toStringTag]() {
+        return "MessageBatch";
+    }
+}
+
+// queueOutcomes returns the outcome of every message, the messages without an outcome are acked
+// if the handler succeeds, or retried if it fails
+function queueOutcomes(batch, outcomes, failed) {
+    let result = {};
+    for (const message of batch.messages) {
+        result[message.id] = outcomes.messages[message.id] || outcomes.batch || (failed ? "retry" : "ack");
+    }
+    return result;
+}
+
+const queueBinding = declaredBindings("LAND_QUEUE_BINDINGS", queue => new Queue(queue));
+
+export { Queue, Message, MessageBatch, queueOutcomes, queueBinding };
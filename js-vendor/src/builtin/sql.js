@@ -0,0 +1,117 @@
+import { declaredBindings } from "./bindings";
+
+// SQL database bindings, the statements are run by the sql_query hostcall, see src/sql.rs.
+// The bindings are declared by env LAND_SQL_BINDINGS, such as "DB,ANALYTICS=analytics-db".
+// The values are converted in the hostcall: integers beyond Number.MAX_SAFE_INTEGER are BigInt,
+// blobs are Uint8Array and NULL is null.
+
+// STATEMENT keeps the database, the sql and the bound parameters of a SqlStatement
+const STATEMENT = Symbol("statement");
+
+function bindValue(value, index) {
+    if (value === undefined) {
+        throw new TypeError(`SQL parameter ${index + 1} is undefined, use null for NULL`);
+    }
+    if (ArrayBuffer.isView(value)) {
+        return value.buffer.slice(value.byteOffset, value.byteOffset + value.byteLength);
+    }
+    return value;
+}
+
+function checkStatement(statement) {
+    if (!(statement instanceof SqlStatement)) {
+        throw new TypeError("SQL batch() expects SqlStatement values");
+    }
+    return statement[STATEMENT];
+}
+
+function toResult(result) {
+    return { success: true, results: result.rows, meta: result.meta };
+}
+
+class SqlStatement {
+    constructor(database, sql, params) {
+        this[STATEMENT] = { database, sql, params };
+    }
+
+    // bind returns a new statement with the parameters, so a prepared statement can be reused
+    bind(...values) {
+        let { database, sql } = this[STATEMENT];
+        return new SqlStatement(database, sql, values.map(bindValue));
+    }
+
+    async first(column) {
+        let [result] = hostcall.sql_query(this[STATEMENT].database, [this[STATEMENT]], false);
+        let row = result.rows[0];
+        if (row === undefined) {
+            return null;
+        }
+        if (column === undefined) {
+            return row;
+        }
+        if (!(column in row)) {
+            throw new Error(`SQL column '${column}' is not in the result`);
+        }
+        return row[column];
+    }
+
+    async all() {
+        let [result] = hostcall.sql_query(this[STATEMENT].database, [this[STATEMENT]], false);
+        return toResult(result);
+    }
+
+    async run() {
+        return await this.all();
+    }
+
+    async raw(options) {
+        let [result] = hostcall.sql_query(this[STATEMENT].database, [this[STATEMENT]], true);
+        if (options && options.columnNames) {
+            return [result.columns, ...result.rows];
+        }
+        return result.rows;
+    }
+
+    get [Symbol.toStringTag]() {
+        return "SqlStatement";
+    }
+}
+
+class SqlDatabase {
+    #database;
+
+    constructor(database) {
+        this.#database = database;
+    }
+
+    prepare(sql) {
+        if (typeof sql !== "string" || sql.trim() === "") {
+            throw new TypeError("SQL prepare() expects a non-empty string");
+        }
+        return new SqlStatement(this.#database, sql, []);
+    }
+
+    // batch runs the statements in one transaction, they are rolled back if one of them fails
+    async batch(statements) {
+        if (!Array.isArray(statements)) {
+            throw new TypeError("SQL batch() expects an array of statements");
+        }
+        let queries = statements.map(checkStatement);
+        if (queries.some(query => query.database !== this.#database)) {
+            throw new TypeError("SQL batch() statements must be prepared by the same database");
+        }
+        if (queries.length === 0) {
+            return [];
+        }
+        return hostcall.sql_query(this.#database, queries, false).map(toResult);
+    }
+
+    get [Symbol.toStringTag]() {
+        return "SqlDatabase";
+    }
+}
+
+// sqlBinding returns the SqlDatabase of the binding name, undefined if it is not declared
+const sqlBinding = declaredBindings("LAND_SQL_BINDINGS", database => new SqlDatabase(database));
+
+export { SqlDatabase, SqlStatement, sqlBinding };
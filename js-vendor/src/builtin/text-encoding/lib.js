@@ -1,11 +1,104 @@
-// This is free and unencumbered software released into the public domain.
-// See LICENSE.md for more information.
+// TextEncoder and TextDecoder are implemented in Rust, see src/encoding.rs.
+// The native object is captured here and removed from globalThis.
+const native = globalThis.textEncoding;
+delete globalThis.textEncoding;
 
-var encoding = require("./encoding.js");
+const LONE_SURROGATE = /[\uD800-\uDBFF](?![\uDC00-\uDFFF])|(?<![\uD800-\uDBFF])[\uDC00-\uDFFF]/;
+const LONE_SURROGATES = new RegExp(LONE_SURROGATE.source, "g");
 
-globalThis.TextEncoder = encoding.TextEncoder;
-globalThis.TextDecoder = encoding.TextDecoder;
+// toWellFormed replaces lone surrogates with U+FFFD, they can not be converted to UTF-8
+function toWellFormed(input) {
+    let str = String(input);
+    if (LONE_SURROGATE.test(str)) {
+        return str.replace(LONE_SURROGATES, "\uFFFD");
+    }
+    return str;
+}
 
-require("./encodeInto.js");
+function toArrayBuffer(input) {
+    if (input === undefined) {
+        return new ArrayBuffer(0);
+    }
+    if (input instanceof ArrayBuffer) {
+        return input;
+    }
+    if (ArrayBuffer.isView(input)) {
+        return input.buffer.slice(input.byteOffset, input.byteOffset + input.byteLength);
+    }
+    throw new TypeError("The provided value is not of type '(ArrayBuffer or ArrayBufferView)'");
+}
 
-require("./text-encoder-stream.js");
+class TextEncoder {
+    get encoding() {
+        return "utf-8";
+    }
+
+    encode(input = "") {
+        return new Uint8Array(native.encode(toWellFormed(input)));
+    }
+
+    encodeInto(input, destination) {
+        if (!(destination instanceof Uint8Array)) {
+            throw new TypeError("The provided value is not of type 'Uint8Array'");
+        }
+        let result = native.encode_into(toWellFormed(input), destination.length);
+        let bytes = new Uint8Array(result.bytes);
+        destination.set(bytes);
+        return { read: result.read, written: bytes.length };
+    }
+
+    get [Symbol.toStringTag]() {
+        return "TextEncoder";
+    }
+}
+
+class TextDecoder {
+    #encoding;
+    #fatal;
+    #ignoreBOM;
+    #decoder;
+
+    constructor(label = "utf-8", options = {}) {
+        let encoding = native.encoding_name(String(label));
+        if (encoding === null) {
+            throw new RangeError(`The encoding label provided ('${label}') is invalid.`);
+        }
+        this.#encoding = encoding;
+        this.#fatal = Boolean(options && options.fatal);
+        this.#ignoreBOM = Boolean(options && options.ignoreBOM);
+        this.#decoder = new native.StreamDecoder(encoding, this.#fatal, this.#ignoreBOM);
+    }
+
+    get encoding() {
+        return this.#encoding;
+    }
+
+    get fatal() {
+        return this.#fatal;
+    }
+
+    get ignoreBOM() {
+        return this.#ignoreBOM;
+    }
+
+    decode(input, options = {}) {
+        let stream = Boolean(options && options.stream);
+        try {
+            return this.#decoder.decode(toArrayBuffer(input), stream);
+        } catch (error) {
+            if (this.#fatal) {
+                throw new TypeError(error.message);
+            }
+            throw error;
+        }
+    }
+
+    get [Symbol.toStringTag]() {
+        return "TextDecoder";
+    }
+}
+
+globalThis.TextEncoder = TextEncoder;
+globalThis.TextDecoder = TextDecoder;
+
+import "./text-encoder-stream";
@@ -1,3 +1,45 @@
+globalThis.TextEncoderStream = class {
+    #encoder = new TextEncoder();
+    #pending = "";
+
+    #transform = new TransformStream({
+        transform: (chunk, controller) => {
+            let input = this.#pending + String(chunk);
+            this.#pending = "";
+            // keep a trailing high surrogate until its pair arrives in the next chunk
+            let last = input.charCodeAt(input.length - 1);
+            if (last >= 0xD800 && last <= 0xDBFF) {
+                this.#pending = input.slice(-1);
+                input = input.slice(0, -1);
+            }
+            if (input) {
+                controller.enqueue(this.#encoder.encode(input));
+            }
+        },
+        flush: controller => {
+            if (this.#pending) {
+                controller.enqueue(this.#encoder.encode(this.#pending));
+            }
+        }
+    });
+
+    get encoding() {
+        return "utf-8";
+    }
+
+    get readable() {
+        return this.#transform.readable;
+    }
+
+    get writable() {
+        return this.#transform.writable;
+    }
+
+    get [Symbol.toStringTag]() {
+        return "TextEncoderStream";
+    }
+}
+
 globalThis.TextDecoderStream = class {
     #handle;
 
@@ -1,13 +1,64 @@
+import { kvBinding } from "./builtin/kv";
+import { bucketBinding } from "./builtin/bucket";
+import { sqlBinding } from "./builtin/sql";
+import { queueBinding } from "./builtin/queue";
+
+// BINDINGS are checked before the env vars
+const BINDINGS = [kvBinding, bucketBinding, sqlBinding, queueBinding];
+
+function binding(name) {
+    for (const lookup of BINDINGS) {
+        let value = lookup(name);
+        if (value) {
+            return value;
+        }
+    }
+    return undefined;
+}
+
+// envValue returns the binding or the var of prop, the vars declared by LAND_JSON_VARS are parsed JSON,
+// null if prop is not set
+function envValue(prop) {
+    let env_key = prop.toUpperCase();
+    return binding(env_key) || hostcall.env_var(env_key);
+}
 
 class Env {
     constructor() {
         return new Proxy(this, {
-            get: function (_target, prop) {
-                let env_key = prop.toUpperCase();
-                return hostcall.read_env(env_key);
-            }
+            get: function (target, prop) {
+                if (typeof prop !== "string") {
+                    return undefined;
+                }
+                let value = envValue(prop);
+                // inherited members such as toString are kept, so env can be converted to a string
+                if (value === null && prop in target) {
+                    return Reflect.get(target, prop);
+                }
+                return value;
+            },
+            has: function (_target, prop) {
+                return typeof prop === "string" && envValue(prop) !== null;
+            },
+            ownKeys: function () {
+                let keys = new Set(hostcall.env_keys());
+                for (const lookup of BINDINGS) {
+                    lookup.names().forEach(name => keys.add(name));
+                }
+                return Array.from(keys);
+            },
+            getOwnPropertyDescriptor: function (_target, prop) {
+                if (typeof prop !== "string") {
+                    return undefined;
+                }
+                let value = envValue(prop);
+                if (value === null) {
+                    return undefined;
+                }
+                return { value, writable: false, enumerable: true, configurable: true };
+            },
         });
     }
 }
 
-export default Env;
\ No newline at end of file
+export default Env;
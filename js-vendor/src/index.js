@@ -1,8 +1,9 @@
 import "./builtin/text-encoding/lib";
 import "./builtin/base64";
-import "./builtin/console";
+import "./builtin/crypto/lib";
 
 import "web-streams-polyfill/polyfill";
+import "./builtin/compression";
 // Blob and File need WebStreams to work
 import { Blob, File } from "blob-polyfill";
 globalThis.Blob = Blob;
@@ -13,6 +14,9 @@ import "./builtin/url/lib";
 
 // import "./timer";
 import Env from "./env";
+// landEnv is the env of the land:env module, it can be read out of the handlers,
+// the module captures it and removes it from globalThis
+globalThis.landEnv = new Env();
 
 import { Headers } from "headers-polyfill";
 globalThis.Headers = Headers;
@@ -26,6 +30,30 @@ globalThis.Request = Request;
 import Response from "./response";
 globalThis.Response = Response;
 
+// HTMLRewriter needs Response and TransformStream to work
+import "./builtin/html-rewriter";
+import "./builtin/cache";
+import { KVNamespace } from "./builtin/kv";
+globalThis.KVNamespace = KVNamespace;
+import { Bucket, BucketObject, BucketObjectBody } from "./builtin/bucket";
+globalThis.Bucket = Bucket;
+globalThis.BucketObject = BucketObject;
+globalThis.BucketObjectBody = BucketObjectBody;
+import { SqlDatabase, SqlStatement } from "./builtin/sql";
+globalThis.SqlDatabase = SqlDatabase;
+globalThis.SqlStatement = SqlStatement;
+import { Queue, Message, MessageBatch, queueOutcomes } from "./builtin/queue";
+globalThis.Queue = Queue;
+globalThis.Message = Message;
+globalThis.MessageBatch = MessageBatch;
+
+import { Performance, PerformanceEntry, PerformanceMark, PerformanceMeasure, performance } from "./builtin/performance";
+globalThis.Performance = Performance;
+globalThis.PerformanceEntry = PerformanceEntry;
+globalThis.PerformanceMark = PerformanceMark;
+globalThis.PerformanceMeasure = PerformanceMeasure;
+globalThis.performance = performance;
+
 import fetch from "./fetch";
 globalThis.fetch = fetch;
 
@@ -53,12 +81,12 @@ function responseWithPromise(promise) {
         }
         globalThis.globalResponse = output;
     }).catch(error => {
-        let errorBytes = new TextEncoder().encode(error.toString() + "\n" + error.stack);
+        // the error is rendered by the engine, see src/error_page.rs
         globalThis.globalResponse = {
             status: 500,
             headers: {},
             body_handle: 0,
-            body: errorBytes.buffer,
+            error,
         };
     })
 }
@@ -82,4 +110,42 @@ function callHandler(input) {
 }
 
 globalThis.callHandler = callHandler;
-globalThis.globalResponse = null;
\ No newline at end of file
+globalThis.globalResponse = null;
+
+function callQueueHandler(input) {
+    if (!globalThis.handler || typeof globalThis.handler.queue !== "function") {
+        throw new Error("No queue handler function defined");
+    }
+    globalThis.globalQueueResult = null;
+    const outcomes = { batch: null, messages: {} };
+    const batch = new MessageBatch(input, outcomes);
+    const pending = [];
+    const ctx = {
+        waitUntil(promise) {
+            pending.push(promise);
+        },
+    };
+    let result;
+    try {
+        result = Promise.resolve(globalThis.handler.queue(batch, new Env(), ctx));
+    } catch (error) {
+        result = Promise.reject(error);
+    }
+    // the outcome is marked explicitly, a handler can reject with a falsy reason such as undefined
+    result.then(() => ({ failed: false }), error => ({ failed: true, error })).then(async settled => {
+        await Promise.allSettled(pending);
+        let error = null;
+        if (settled.failed) {
+            error = String(settled.error);
+            let stack = settled.error && settled.error.stack;
+            console.error("queue handler error:", stack ? error + "\n" + stack : error);
+        }
+        globalThis.globalQueueResult = {
+            outcomes: queueOutcomes(input, outcomes, settled.failed),
+            error,
+        };
+    });
+}
+
+globalThis.callQueueHandler = callQueueHandler;
+globalThis.globalQueueResult = null;
\ No newline at end of file
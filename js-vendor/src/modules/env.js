@@ -0,0 +1,7 @@
+// The land:env module exports the env of the script, it can be imported by any module of the script.
+// The env object of js-vendor/src/index.js is captured here and removed from globalThis.
+const env = globalThis.landEnv;
+delete globalThis.landEnv;
+
+export { env };
+export default env;
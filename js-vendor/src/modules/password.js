@@ -0,0 +1,70 @@
+// The land:password module wraps the native password object, see src/password.rs.
+// The native object is captured here and removed from globalThis.
+const native = globalThis.landPassword;
+delete globalThis.landPassword;
+
+function option(options, name, value) {
+    if (options && options[name] !== undefined) {
+        if (typeof options[name] !== "number" || !Number.isInteger(options[name])) {
+            throw new TypeError(`${name} must be an integer`);
+        }
+        return options[name];
+    }
+    return value;
+}
+
+export const argon2id = {
+    async hash(password, options) {
+        return native.argon2id_hash(
+            password,
+            option(options, "memoryCost", 19456),
+            option(options, "timeCost", 2),
+            option(options, "parallelism", 1),
+        );
+    },
+    async verify(password, hash) {
+        return native.argon2id_verify(password, hash);
+    },
+};
+
+export const bcrypt = {
+    async hash(password, options) {
+        return native.bcrypt_hash(password, option(options, "cost", 10));
+    },
+    async verify(password, hash) {
+        return native.bcrypt_verify(password, hash);
+    },
+};
+
+export const scrypt = {
+    async hash(password, options) {
+        return native.scrypt_hash(
+            password,
+            option(options, "logN", 15),
+            option(options, "r", 8),
+            option(options, "p", 1),
+        );
+    },
+    async verify(password, hash) {
+        return native.scrypt_verify(password, hash);
+    },
+};
+
+// verify detects the algorithm from the PHC string or bcrypt prefix of hash
+export async function verify(password, hash) {
+    if (typeof hash !== "string") {
+        throw new TypeError("hash must be a string");
+    }
+    if (hash.startsWith("$argon2id$")) {
+        return argon2id.verify(password, hash);
+    }
+    if (hash.startsWith("$scrypt$")) {
+        return scrypt.verify(password, hash);
+    }
+    if (/^\$2[abxy]\$/.test(hash)) {
+        return bcrypt.verify(password, hash);
+    }
+    throw new Error("Unrecognized password hash format");
+}
+
+export default { argon2id, bcrypt, scrypt, verify };
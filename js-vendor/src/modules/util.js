@@ -0,0 +1,21 @@
+// The land:util module wraps the native util object, see src/inspect.rs.
+// The native object is captured here and removed from globalThis.
+const native = globalThis.landUtil;
+delete globalThis.landUtil;
+
+export function format(...args) {
+    return native.format(...args);
+}
+
+export function inspect(value, options) {
+    let depth = options && options.depth !== undefined ? options.depth : 2;
+    if (depth === null || depth === Infinity) {
+        depth = -1;
+    }
+    if (typeof depth !== "number" || Number.isNaN(depth)) {
+        throw new TypeError("depth must be a number, null or Infinity");
+    }
+    return native.inspect(value, depth);
+}
+
+export default { format, inspect };
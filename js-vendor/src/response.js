@@ -61,6 +61,20 @@ class Response {
         return this.#_type;
     }
 
+    clone() {
+        if (this.bodyUsed) {
+            throw new TypeError("Failed to execute 'clone' on 'Response': Response body is already used");
+        }
+        // both responses read the same body, so the body is split into two streams
+        let [body, cloned] = this.#_body.stream.tee();
+        this.#_body = new Body(body);
+        return new Response(cloned, {
+            status: this.#_status,
+            statusText: this.#_statusText,
+            headers: new Headers(this.#_headers),
+        });
+    }
+
     async arrayBuffer() {
         return await this.#_body.arrayBuffer();
     }
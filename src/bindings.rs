@@ -0,0 +1,184 @@
+use crate::trace;
+use anyhow::{anyhow, Result};
+use http::{HeaderName, HeaderValue};
+use land_sdk::http::{Body, RequestOptions, Response};
+use std::collections::HashMap;
+
+// BINDINGS_URL_ENV is the endpoint of the platform bindings service, such as the shared cache.
+// The bindings hostcalls fall back to in-instance implementations when it is not set.
+const BINDINGS_URL_ENV: &str = "LAND_BINDINGS_URL";
+
+// BINDINGS_TIMEOUT is the timeout of a bindings call in seconds
+const BINDINGS_TIMEOUT: u32 = 10;
+
+/// BindingResponse is the response of a bindings call with the whole body
+#[derive(Debug, Default)]
+pub struct BindingResponse {
+    pub status: u16,
+    pub headers: HashMap<String, String>,
+    pub body: Vec<u8>,
+}
+
+impl BindingResponse {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// endpoint returns the bindings service url without trailing slash, None if it is not configured
+pub fn endpoint() -> Option<String> {
+    crate::env::var(BINDINGS_URL_ENV)
+        .map(|url| url.trim().trim_end_matches('/').to_string())
+        .filter(|url| !url.is_empty())
+}
+
+/// encode_segment percent-encodes value as one path segment of a bindings url
+pub fn encode_segment(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+/// encode_hex returns bytes as lowercase hex, the bindings service carries bytes in JSON as hex
+pub fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// decode_hex returns the bytes of hex
+pub fn decode_hex(hex: &str) -> Result<Vec<u8>> {
+    if hex.len() % 2 != 0 {
+        return Err(anyhow!("Hex value has an odd length"));
+    }
+    // the pairs are bytes, a non-ASCII character is not a hex digit and is never split
+    hex.as_bytes()
+        .chunks(2)
+        .map(|pair| {
+            std::str::from_utf8(pair)
+                .ok()
+                .and_then(|pair| u8::from_str_radix(pair, 16).ok())
+                .ok_or_else(|| anyhow!("Hex value is invalid"))
+        })
+        .collect()
+}
+
+/// send sends a request with body to path of the bindings service, the response body is not read
+pub fn send(
+    method: &str,
+    path: &str,
+    headers: &[(String, String)],
+    body: Body,
+) -> Result<Response> {
+    let url = endpoint().ok_or_else(|| anyhow!("{} is not set", BINDINGS_URL_ENV))?;
+    // the span is named by the binding type, such as "bindings kv"
+    let binding = path
+        .trim_start_matches('/')
+        .split('/')
+        .next()
+        .unwrap_or_default();
+    let mut span = trace::start(&format!("bindings {}", binding), trace::Kind::Client);
+    span.attribute("http.request.method", method);
+    let mut builder = http::Request::builder()
+        .method(method)
+        .uri(format!("{}{}", url, path));
+    if let Some(request_headers) = builder.headers_mut() {
+        for (name, value) in headers {
+            request_headers.insert(
+                HeaderName::from_bytes(name.as_bytes())?,
+                HeaderValue::from_bytes(value.as_bytes())?,
+            );
+        }
+        if let Some(traceparent) = span.traceparent() {
+            request_headers.insert(
+                HeaderName::from_static(trace::TRACEPARENT_HEADER),
+                HeaderValue::from_str(&traceparent)?,
+            );
+        }
+    }
+    let request = builder.body(body)?;
+    let options = RequestOptions {
+        timeout: BINDINGS_TIMEOUT,
+        redirect: land_sdk::http::RedirectPolicy::Error,
+    };
+    let response = match land_sdk::http::fetch(request, options) {
+        Ok(response) => response,
+        Err(err) => {
+            span.fail(&err);
+            span.end();
+            return Err(err.into());
+        }
+    };
+    span.attribute("http.response.status_code", response.status().as_u16());
+    if response.status().as_u16() >= 500 {
+        span.fail(format!("Response status {}", response.status().as_u16()));
+    }
+    span.end();
+    if response.status().as_u16() >= 500 {
+        let status = response.status().as_u16();
+        let body = read_body(response.body().body_handle())?;
+        return Err(anyhow!(
+            "Bindings call {} {} failed with status {}: {}",
+            method,
+            path,
+            status,
+            String::from_utf8_lossy(&body)
+        ));
+    }
+    Ok(response)
+}
+
+/// headers_of returns the headers of response
+pub fn headers_of(response: &Response) -> HashMap<String, String> {
+    response
+        .headers()
+        .iter()
+        .map(|(key, value)| {
+            (
+                key.as_str().to_string(),
+                value.to_str().unwrap_or_default().to_string(),
+            )
+        })
+        .collect()
+}
+
+/// read_body reads the whole body of handle
+pub fn read_body(handle: u32) -> Result<Vec<u8>> {
+    let body = Body::from_handle(handle);
+    let mut bytes = vec![];
+    loop {
+        let (chunk, done) = body.read(0)?;
+        bytes.extend_from_slice(&chunk);
+        if done {
+            break;
+        }
+    }
+    Ok(bytes)
+}
+
+/// call sends a request to path of the bindings service and reads the whole response body
+pub fn call(
+    method: &str,
+    path: &str,
+    headers: &[(String, String)],
+    body: Option<Vec<u8>>,
+) -> Result<BindingResponse> {
+    let body = match body {
+        Some(body) => Body::from(body.as_slice()),
+        None => Body::empty(),
+    };
+    let response = send(method, path, headers, body)?;
+    Ok(BindingResponse {
+        status: response.status().as_u16(),
+        headers: headers_of(&response),
+        body: read_body(response.body().body_handle())?,
+    })
+}
@@ -0,0 +1,525 @@
+use crate::bindings::{self, encode_segment};
+use crate::hostcall::{arg_to_bytes, arg_to_string, check_args, to_js_error};
+use anyhow::{anyhow, Result};
+use land_sdk::http::Body;
+use once_cell::sync::Lazy;
+use rquickjs::{prelude::Rest, Array, ArrayBuffer, Ctx, FromJs, Function, Object, Value};
+use sha2::Digest;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const MAX_KEY_SIZE: usize = 1024;
+const MAX_LIST_LIMIT: u32 = 1000;
+
+// The object metadata is carried in headers of the bindings service,
+// HTTP_PREFIX for the http metadata such as content-type, META_PREFIX for the custom metadata
+const SIZE_HEADER: &str = "x-land-object-size";
+const ETAG_HEADER: &str = "x-land-object-etag";
+const UPLOADED_HEADER: &str = "x-land-object-uploaded";
+const HTTP_PREFIX: &str = "x-land-object-http-";
+const META_PREFIX: &str = "x-land-object-meta-";
+
+// MEMORY is the in-instance bucket used when the bindings service is not configured,
+// it is not shared by other instances
+static MEMORY: Lazy<Mutex<BTreeMap<(String, String), (ObjectMeta, Vec<u8>)>>> =
+    Lazy::new(|| Mutex::new(BTreeMap::new()));
+
+/// ObjectMeta is the metadata of a stored object
+#[derive(Debug, Clone, Default)]
+pub struct ObjectMeta {
+    pub key: String,
+    pub size: u64,
+    pub etag: String,
+    pub uploaded: u64,
+    pub http_metadata: HashMap<String, String>,
+    pub custom_metadata: HashMap<String, String>,
+}
+
+impl ObjectMeta {
+    fn from_headers(key: &str, headers: &HashMap<String, String>) -> Result<Self> {
+        let header = |name: &str| headers.get(name).map(|value| value.as_str());
+        let prefixed = |prefix: &str| {
+            headers
+                .iter()
+                .filter_map(|(name, value)| {
+                    name.strip_prefix(prefix)
+                        .map(|name| (name.to_string(), value.clone()))
+                })
+                .collect::<HashMap<_, _>>()
+        };
+        Ok(Self {
+            key: key.to_string(),
+            size: header(SIZE_HEADER)
+                .and_then(|size| size.parse().ok())
+                .ok_or_else(|| anyhow!("Object response has no {} header", SIZE_HEADER))?,
+            etag: header(ETAG_HEADER).unwrap_or_default().to_string(),
+            uploaded: header(UPLOADED_HEADER)
+                .and_then(|uploaded| uploaded.parse().ok())
+                .unwrap_or(0),
+            http_metadata: prefixed(HTTP_PREFIX),
+            custom_metadata: prefixed(META_PREFIX),
+        })
+    }
+
+    fn to_headers(&self) -> Vec<(String, String)> {
+        let mut headers = vec![];
+        for (name, value) in self.http_metadata.iter() {
+            headers.push((format!("{}{}", HTTP_PREFIX, name), value.clone()));
+        }
+        for (name, value) in self.custom_metadata.iter() {
+            headers.push((format!("{}{}", META_PREFIX, name), value.clone()));
+        }
+        headers
+    }
+
+    fn into_js<'js>(self, ctx: &Ctx<'js>) -> rquickjs::Result<Object<'js>> {
+        let object = Object::new(ctx.clone())?;
+        object.set("key", self.key)?;
+        object.set("size", self.size as f64)?;
+        object.set("etag", self.etag)?;
+        object.set("uploaded", self.uploaded as f64)?;
+        object.set("httpMetadata", self.http_metadata)?;
+        object.set("customMetadata", self.custom_metadata)?;
+        Ok(object)
+    }
+}
+
+/// PutMetadata is the http and custom metadata of bucket_put
+#[derive(Debug, Default)]
+pub struct PutMetadata {
+    pub http_metadata: HashMap<String, String>,
+    pub custom_metadata: HashMap<String, String>,
+}
+
+impl<'js> FromJs<'js> for PutMetadata {
+    fn from_js(ctx: &Ctx<'js>, value: Value<'js>) -> rquickjs::Result<Self> {
+        if !value.is_object() {
+            return Err(ctx.throw(
+                rquickjs::String::from_str(ctx.clone(), "PutMetadata need from an object")?
+                    .into_value(),
+            ));
+        }
+        let metadata_object = value.as_object().unwrap();
+        Ok(Self {
+            http_metadata: metadata_object.get("httpMetadata")?,
+            custom_metadata: metadata_object.get("customMetadata")?,
+        })
+    }
+}
+
+/// ByteRange is the range of bucket_get, offset and length or the last suffix bytes
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ByteRange {
+    pub offset: Option<u64>,
+    pub length: Option<u64>,
+    pub suffix: Option<u64>,
+}
+
+impl<'js> FromJs<'js> for ByteRange {
+    fn from_js(ctx: &Ctx<'js>, value: Value<'js>) -> rquickjs::Result<Self> {
+        if !value.is_object() {
+            return Err(ctx.throw(
+                rquickjs::String::from_str(ctx.clone(), "ByteRange need from an object")?
+                    .into_value(),
+            ));
+        }
+        let range_object = value.as_object().unwrap();
+        let number = |key: &str| -> rquickjs::Result<Option<u64>> {
+            let value: Value = range_object.get(key)?;
+            Ok(value.as_number().map(|n| n.max(0.0) as u64))
+        };
+        Ok(Self {
+            offset: number("offset")?,
+            length: number("length")?,
+            suffix: number("suffix")?,
+        })
+    }
+}
+
+impl ByteRange {
+    fn header(&self) -> Option<String> {
+        match (self.offset, self.length, self.suffix) {
+            (_, _, Some(suffix)) => Some(format!("bytes=-{}", suffix)),
+            (Some(offset), Some(length), _) if length > 0 => {
+                Some(format!("bytes={}-{}", offset, offset + length - 1))
+            }
+            (Some(offset), None, _) => Some(format!("bytes={}-", offset)),
+            (None, Some(length), _) if length > 0 => Some(format!("bytes=0-{}", length - 1)),
+            _ => None,
+        }
+    }
+
+    /// resolve returns the offset and length of the range in an object of size
+    fn resolve(&self, size: u64) -> (u64, u64) {
+        if let Some(suffix) = self.suffix {
+            let length = suffix.min(size);
+            return (size - length, length);
+        }
+        let offset = self.offset.unwrap_or(0).min(size);
+        let length = self.length.unwrap_or(size - offset).min(size - offset);
+        (offset, length)
+    }
+}
+
+/// ListOptions is the options of bucket_list
+#[derive(Debug, Default)]
+pub struct ListOptions {
+    pub prefix: String,
+    pub delimiter: String,
+    pub cursor: String,
+    pub limit: u32,
+}
+
+impl<'js> FromJs<'js> for ListOptions {
+    fn from_js(ctx: &Ctx<'js>, value: Value<'js>) -> rquickjs::Result<Self> {
+        if !value.is_object() {
+            return Err(ctx.throw(
+                rquickjs::String::from_str(ctx.clone(), "ListOptions need from an object")?
+                    .into_value(),
+            ));
+        }
+        let options_object = value.as_object().unwrap();
+        let string = |key: &str| -> rquickjs::Result<String> {
+            let value: Value = options_object.get(key)?;
+            Ok(arg_to_string(&value).unwrap_or_default())
+        };
+        let limit: Value = options_object.get("limit")?;
+        Ok(Self {
+            prefix: string("prefix")?,
+            delimiter: string("delimiter")?,
+            cursor: string("cursor")?,
+            limit: (limit.as_number().unwrap_or(MAX_LIST_LIMIT as f64) as u32)
+                .clamp(1, MAX_LIST_LIMIT),
+        })
+    }
+}
+
+/// ObjectBody is the body of a got object, a body handle of the bindings response or the bytes
+pub enum ObjectBody {
+    Handle(u32),
+    Bytes(Vec<u8>),
+}
+
+fn check_key(key: &str) -> Result<()> {
+    if key.is_empty() {
+        return Err(anyhow!("Object key must not be empty"));
+    }
+    if key.len() > MAX_KEY_SIZE {
+        return Err(anyhow!("Object key is longer than {} bytes", MAX_KEY_SIZE));
+    }
+    Ok(())
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+fn object_path(bucket: &str, key: &str) -> String {
+    format!("/bucket/{}/{}", encode_segment(bucket), encode_segment(key))
+}
+
+/// get returns the metadata, the returned range and the body of key in bucket, None if it is missing
+pub fn get(
+    bucket: &str,
+    key: &str,
+    range: Option<ByteRange>,
+) -> Result<Option<(ObjectMeta, Option<(u64, u64)>, ObjectBody)>> {
+    check_key(key)?;
+    if bindings::endpoint().is_none() {
+        let memory = MEMORY.lock().unwrap();
+        let (meta, body) = match memory.get(&(bucket.to_string(), key.to_string())) {
+            Some(object) => object,
+            None => return Ok(None),
+        };
+        return Ok(Some(match range {
+            Some(range) => {
+                let (offset, length) = range.resolve(meta.size);
+                let bytes = body[offset as usize..(offset + length) as usize].to_vec();
+                (
+                    meta.clone(),
+                    Some((offset, length)),
+                    ObjectBody::Bytes(bytes),
+                )
+            }
+            None => (meta.clone(), None, ObjectBody::Bytes(body.clone())),
+        }));
+    }
+    let mut headers = vec![];
+    if let Some(range) = range.and_then(|range| range.header()) {
+        headers.push(("range".to_string(), range));
+    }
+    let response = bindings::send("GET", &object_path(bucket, key), &headers, Body::empty())?;
+    if response.status().as_u16() == 404 {
+        return Ok(None);
+    }
+    let response_headers = bindings::headers_of(&response);
+    let meta = ObjectMeta::from_headers(key, &response_headers)?;
+    // content-range is "bytes {start}-{end}/{size}"
+    let range = response_headers
+        .get("content-range")
+        .and_then(|value| value.strip_prefix("bytes "))
+        .and_then(|value| value.split_once('/'))
+        .and_then(|(range, _)| range.split_once('-'))
+        .and_then(|(start, end)| Some((start.parse::<u64>().ok()?, end.parse::<u64>().ok()?)))
+        .map(|(start, end)| (start, end + 1 - start));
+    let body = ObjectBody::Handle(response.body().body_handle());
+    Ok(Some((meta, range, body)))
+}
+
+/// head returns the metadata of key in bucket, None if it is missing
+pub fn head(bucket: &str, key: &str) -> Result<Option<ObjectMeta>> {
+    check_key(key)?;
+    if bindings::endpoint().is_none() {
+        let memory = MEMORY.lock().unwrap();
+        let object = memory.get(&(bucket.to_string(), key.to_string()));
+        return Ok(object.map(|(meta, _)| meta.clone()));
+    }
+    let response = bindings::call("HEAD", &object_path(bucket, key), &[], None)?;
+    if response.status == 404 {
+        return Ok(None);
+    }
+    Ok(Some(ObjectMeta::from_headers(key, &response.headers)?))
+}
+
+/// put stores body as key in bucket, the body is streamed from a body handle or bytes
+pub fn put(bucket: &str, key: &str, body: ObjectBody, metadata: PutMetadata) -> Result<ObjectMeta> {
+    check_key(key)?;
+    if bindings::endpoint().is_none() {
+        let bytes = match body {
+            ObjectBody::Bytes(bytes) => bytes,
+            ObjectBody::Handle(handle) => {
+                let body = Body::from_handle(handle);
+                let mut bytes = vec![];
+                loop {
+                    let (chunk, done) = body.read(0)?;
+                    bytes.extend_from_slice(&chunk);
+                    if done {
+                        break;
+                    }
+                }
+                bytes
+            }
+        };
+        let meta = ObjectMeta {
+            key: key.to_string(),
+            size: bytes.len() as u64,
+            etag: sha2::Sha256::digest(&bytes)[..16]
+                .iter()
+                .map(|byte| format!("{:02x}", byte))
+                .collect(),
+            uploaded: now_millis(),
+            http_metadata: metadata.http_metadata,
+            custom_metadata: metadata.custom_metadata,
+        };
+        MEMORY
+            .lock()
+            .unwrap()
+            .insert((bucket.to_string(), key.to_string()), (meta.clone(), bytes));
+        return Ok(meta);
+    }
+    let meta = ObjectMeta {
+        http_metadata: metadata.http_metadata,
+        custom_metadata: metadata.custom_metadata,
+        ..Default::default()
+    };
+    let body = match body {
+        ObjectBody::Bytes(bytes) => Body::from(bytes.as_slice()),
+        ObjectBody::Handle(handle) => Body::from_handle(handle),
+    };
+    let response = bindings::send("PUT", &object_path(bucket, key), &meta.to_headers(), body)?;
+    ObjectMeta::from_headers(key, &bindings::headers_of(&response))
+}
+
+/// delete removes key from bucket
+pub fn delete(bucket: &str, key: &str) -> Result<()> {
+    check_key(key)?;
+    if bindings::endpoint().is_none() {
+        MEMORY
+            .lock()
+            .unwrap()
+            .remove(&(bucket.to_string(), key.to_string()));
+        return Ok(());
+    }
+    bindings::call("DELETE", &object_path(bucket, key), &[], None)?;
+    Ok(())
+}
+
+/// list returns {objects, delimitedPrefixes, truncated, cursor} of bucket
+pub fn list<'js>(ctx: &Ctx<'js>, bucket: &str, options: ListOptions) -> Result<Value<'js>> {
+    if bindings::endpoint().is_some() {
+        let path = format!(
+            "/bucket/{}?prefix={}&delimiter={}&cursor={}&limit={}",
+            encode_segment(bucket),
+            encode_segment(&options.prefix),
+            encode_segment(&options.delimiter),
+            encode_segment(&options.cursor),
+            options.limit
+        );
+        let response = bindings::call("GET", &path, &[], None)?;
+        return Ok(ctx.json_parse(response.body)?);
+    }
+    let memory = MEMORY.lock().unwrap();
+    let objects = Array::new(ctx.clone())?;
+    let mut prefixes = BTreeSet::new();
+    let mut count = 0;
+    let mut last_key = None;
+    let mut truncated = false;
+    let keys = memory
+        .range((bucket.to_string(), options.cursor.clone())..)
+        .take_while(|((name, _), _)| name == bucket)
+        .filter(|((_, key), _)| key.starts_with(&options.prefix) && *key > options.cursor);
+    for ((_, key), (meta, _)) in keys {
+        // the keys with the delimiter after the prefix are rolled up into a delimited prefix
+        let delimited = if options.delimiter.is_empty() {
+            None
+        } else {
+            key[options.prefix.len()..]
+                .find(&options.delimiter)
+                .map(|index| {
+                    key[..options.prefix.len() + index + options.delimiter.len()].to_string()
+                })
+        };
+        if let Some(prefix) = &delimited {
+            if prefixes.contains(prefix) {
+                last_key = Some(key.clone());
+                continue;
+            }
+        }
+        if count == options.limit {
+            truncated = true;
+            break;
+        }
+        match delimited {
+            Some(prefix) => {
+                prefixes.insert(prefix);
+            }
+            None => {
+                objects.set(objects.len(), meta.clone().into_js(ctx)?)?;
+            }
+        }
+        count += 1;
+        last_key = Some(key.clone());
+    }
+    let result = Object::new(ctx.clone())?;
+    result.set("objects", objects)?;
+    result.set(
+        "delimitedPrefixes",
+        prefixes.into_iter().collect::<Vec<String>>(),
+    )?;
+    result.set("truncated", truncated)?;
+    if truncated {
+        result.set("cursor", last_key)?;
+    }
+    Ok(Value::from_object(result))
+}
+
+fn object_result<'js>(
+    ctx: &Ctx<'js>,
+    meta: ObjectMeta,
+    range: Option<(u64, u64)>,
+    body: Option<ObjectBody>,
+) -> rquickjs::Result<Value<'js>> {
+    let object = meta.into_js(ctx)?;
+    if let Some((offset, length)) = range {
+        let range_object = Object::new(ctx.clone())?;
+        range_object.set("offset", offset as f64)?;
+        range_object.set("length", length as f64)?;
+        object.set("range", range_object)?;
+    }
+    match body {
+        Some(ObjectBody::Handle(handle)) => object.set("body_handle", handle)?,
+        Some(ObjectBody::Bytes(bytes)) => {
+            object.set("body", ArrayBuffer::new(ctx.clone(), bytes)?)?
+        }
+        None => {}
+    }
+    Ok(Value::from_object(object))
+}
+
+/// build the bucket hostcalls, they are set on the hostcall object
+pub fn build<'js>(ctx: Ctx<'js>, hostcall: &Object<'js>) -> rquickjs::Result<()> {
+    // bucket_get(bucket, key, range) returns the object with body_handle or body, or null
+    let get_callback = Function::new(
+        ctx.clone(),
+        |cx: Ctx<'js>, args: Rest<Value<'js>>| -> Result<Value<'js>, rquickjs::Error> {
+            check_args(&args, 2)?;
+            let bucket = arg_to_string(&args[0]).map_err(|e| to_js_error(cx.clone(), e))?;
+            let key = arg_to_string(&args[1]).map_err(|e| to_js_error(cx.clone(), e))?;
+            let range = match args.get(2) {
+                Some(range) if range.is_object() => Some(ByteRange::from_js(&cx, range.clone())?),
+                _ => None,
+            };
+            match get(&bucket, &key, range).map_err(|e| to_js_error(cx.clone(), e))? {
+                Some((meta, range, body)) => object_result(&cx, meta, range, Some(body)),
+                None => Ok(Value::new_null(cx.clone())),
+            }
+        },
+    )?;
+
+    // bucket_head(bucket, key) returns the object metadata or null
+    let head_callback = Function::new(
+        ctx.clone(),
+        |cx: Ctx<'js>, args: Rest<Value<'js>>| -> Result<Value<'js>, rquickjs::Error> {
+            check_args(&args, 2)?;
+            let bucket = arg_to_string(&args[0]).map_err(|e| to_js_error(cx.clone(), e))?;
+            let key = arg_to_string(&args[1]).map_err(|e| to_js_error(cx.clone(), e))?;
+            match head(&bucket, &key).map_err(|e| to_js_error(cx.clone(), e))? {
+                Some(meta) => object_result(&cx, meta, None, None),
+                None => Ok(Value::new_null(cx.clone())),
+            }
+        },
+    )?;
+
+    // bucket_put(bucket, key, body, metadata) stores body, a body handle number or bytes
+    let put_callback = Function::new(
+        ctx.clone(),
+        |cx: Ctx<'js>, args: Rest<Value<'js>>| -> Result<Value<'js>, rquickjs::Error> {
+            check_args(&args, 4)?;
+            let bucket = arg_to_string(&args[0]).map_err(|e| to_js_error(cx.clone(), e))?;
+            let key = arg_to_string(&args[1]).map_err(|e| to_js_error(cx.clone(), e))?;
+            let body = match args[2].as_number() {
+                Some(handle) => ObjectBody::Handle(handle as u32),
+                None => ObjectBody::Bytes(
+                    arg_to_bytes(&args[2]).map_err(|e| to_js_error(cx.clone(), e))?,
+                ),
+            };
+            let metadata = PutMetadata::from_js(&cx, args[3].clone())?;
+            let meta =
+                put(&bucket, &key, body, metadata).map_err(|e| to_js_error(cx.clone(), e))?;
+            object_result(&cx, meta, None, None)
+        },
+    )?;
+
+    // bucket_delete(bucket, key) removes key
+    let delete_callback = Function::new(
+        ctx.clone(),
+        |cx: Ctx<'js>, args: Rest<Value<'js>>| -> Result<(), rquickjs::Error> {
+            check_args(&args, 2)?;
+            let bucket = arg_to_string(&args[0]).map_err(|e| to_js_error(cx.clone(), e))?;
+            let key = arg_to_string(&args[1]).map_err(|e| to_js_error(cx.clone(), e))?;
+            delete(&bucket, &key).map_err(|e| to_js_error(cx.clone(), e))
+        },
+    )?;
+
+    // bucket_list(bucket, {prefix, delimiter, cursor, limit}) returns {objects, delimitedPrefixes, truncated, cursor}
+    let list_callback = Function::new(
+        ctx.clone(),
+        |cx: Ctx<'js>, args: Rest<Value<'js>>| -> Result<Value<'js>, rquickjs::Error> {
+            check_args(&args, 2)?;
+            let bucket = arg_to_string(&args[0]).map_err(|e| to_js_error(cx.clone(), e))?;
+            let options = ListOptions::from_js(&cx, args[1].clone())?;
+            list(&cx, &bucket, options).map_err(|e| to_js_error(cx.clone(), e))
+        },
+    )?;
+
+    hostcall.set("bucket_get", get_callback)?;
+    hostcall.set("bucket_head", head_callback)?;
+    hostcall.set("bucket_put", put_callback)?;
+    hostcall.set("bucket_delete", delete_callback)?;
+    hostcall.set("bucket_list", list_callback)?;
+    Ok(())
+}
@@ -0,0 +1,247 @@
+use crate::bindings::{self, encode_segment};
+use crate::entity::JsHttpObject;
+use crate::hostcall::{arg_to_string, check_args, to_js_error};
+use anyhow::{anyhow, Result};
+use once_cell::sync::Lazy;
+use rquickjs::{prelude::Rest, ArrayBuffer, Ctx, FromJs, Function, Object, Value};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// LRU_MAX_BYTES is the size of the in-instance fallback cache, the least recently used entries are evicted
+const LRU_MAX_BYTES: usize = 16 * 1024 * 1024;
+
+// STATUS_HEADER and TTL_HEADER carry the cached response status and ttl to the bindings service,
+// the cached response headers are sent with HEADER_PREFIX
+const STATUS_HEADER: &str = "x-land-cache-status";
+const TTL_HEADER: &str = "x-land-cache-ttl";
+const HEADER_PREFIX: &str = "x-land-cache-header-";
+
+static LRU: Lazy<Mutex<Lru>> = Lazy::new(|| Mutex::new(Lru::default()));
+
+/// CachedResponse is a response stored in the cache
+#[derive(Debug, Clone, Default)]
+pub struct CachedResponse {
+    pub status: u16,
+    pub headers: HashMap<String, String>,
+    pub body: Vec<u8>,
+}
+
+impl CachedResponse {
+    fn size(&self) -> usize {
+        self.body.len()
+            + self
+                .headers
+                .iter()
+                .map(|(key, value)| key.len() + value.len())
+                .sum::<usize>()
+    }
+
+    fn into_js<'js>(self, ctx: &Ctx<'js>) -> rquickjs::Result<Value<'js>> {
+        let object = Object::new(ctx.clone())?;
+        object.set("status", self.status)?;
+        let headers = Object::new(ctx.clone())?;
+        for (key, value) in self.headers.iter() {
+            headers.set(key, value)?;
+        }
+        object.set("headers", headers)?;
+        object.set("body", ArrayBuffer::new(ctx.clone(), self.body)?)?;
+        Ok(Value::from_object(object))
+    }
+}
+
+struct LruEntry {
+    response: CachedResponse,
+    expires_at: u64,
+    used: u64,
+}
+
+/// Lru is the in-instance cache used when the bindings service is not configured
+#[derive(Default)]
+struct Lru {
+    entries: HashMap<String, LruEntry>,
+    size: usize,
+    tick: u64,
+}
+
+impl Lru {
+    fn get(&mut self, key: &str) -> Option<CachedResponse> {
+        let now = now_millis();
+        self.tick += 1;
+        let expired = match self.entries.get_mut(key) {
+            Some(entry) if entry.expires_at > now => {
+                entry.used = self.tick;
+                return Some(entry.response.clone());
+            }
+            Some(_) => true,
+            None => false,
+        };
+        if expired {
+            self.remove(key);
+        }
+        None
+    }
+
+    fn put(&mut self, key: String, response: CachedResponse, ttl: u64) {
+        self.remove(&key);
+        let size = response.size();
+        if size > LRU_MAX_BYTES {
+            return;
+        }
+        while self.size + size > LRU_MAX_BYTES {
+            let oldest = self
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.used)
+                .map(|(key, _)| key.clone());
+            match oldest {
+                Some(oldest) => self.remove(&oldest),
+                None => break,
+            };
+        }
+        self.tick += 1;
+        self.size += size;
+        self.entries.insert(
+            key,
+            LruEntry {
+                response,
+                expires_at: now_millis().saturating_add(ttl.saturating_mul(1000)),
+                used: self.tick,
+            },
+        );
+    }
+
+    fn remove(&mut self, key: &str) -> bool {
+        match self.entries.remove(key) {
+            Some(entry) => {
+                self.size -= entry.response.size();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+fn lru_key(name: &str, key: &str) -> String {
+    format!("{}\n{}", name, key)
+}
+
+fn cache_path(name: &str, key: &str) -> String {
+    format!("/cache/{}/{}", encode_segment(name), encode_segment(key))
+}
+
+/// get returns the cached response of key in cache name, None if it is missing or expired
+pub fn get(name: &str, key: &str) -> Result<Option<CachedResponse>> {
+    if bindings::endpoint().is_none() {
+        return Ok(LRU.lock().unwrap().get(&lru_key(name, key)));
+    }
+    let response = bindings::call("GET", &cache_path(name, key), &[], None)?;
+    if response.status == 404 {
+        return Ok(None);
+    }
+    let status = response
+        .header(STATUS_HEADER)
+        .and_then(|status| status.parse::<u16>().ok())
+        .ok_or_else(|| anyhow!("Cache response has no {} header", STATUS_HEADER))?;
+    let headers = response
+        .headers
+        .iter()
+        .filter_map(|(key, value)| {
+            let name = key.to_ascii_lowercase();
+            name.strip_prefix(HEADER_PREFIX)
+                .map(|name| (name.to_string(), value.clone()))
+        })
+        .collect();
+    Ok(Some(CachedResponse {
+        status,
+        headers,
+        body: response.body,
+    }))
+}
+
+/// put stores response as key in cache name for ttl seconds
+pub fn put(name: &str, key: &str, response: CachedResponse, ttl: u64) -> Result<()> {
+    if bindings::endpoint().is_none() {
+        LRU.lock().unwrap().put(lru_key(name, key), response, ttl);
+        return Ok(());
+    }
+    let mut headers = vec![
+        (STATUS_HEADER.to_string(), response.status.to_string()),
+        (TTL_HEADER.to_string(), ttl.to_string()),
+    ];
+    for (key, value) in response.headers {
+        headers.push((
+            format!("{}{}", HEADER_PREFIX, key.to_ascii_lowercase()),
+            value,
+        ));
+    }
+    bindings::call("PUT", &cache_path(name, key), &headers, Some(response.body))?;
+    Ok(())
+}
+
+/// delete removes key from cache name, returns whether it was cached
+pub fn delete(name: &str, key: &str) -> Result<bool> {
+    if bindings::endpoint().is_none() {
+        return Ok(LRU.lock().unwrap().remove(&lru_key(name, key)));
+    }
+    let response = bindings::call("DELETE", &cache_path(name, key), &[], None)?;
+    Ok(response.status != 404)
+}
+
+/// build the cache hostcalls, they are set on the hostcall object
+pub fn build<'js>(ctx: Ctx<'js>, hostcall: &Object<'js>) -> rquickjs::Result<()> {
+    // cache_match(name, key) returns {status, headers, body} or null
+    let match_callback = Function::new(
+        ctx.clone(),
+        |cx: Ctx<'js>, args: Rest<Value<'js>>| -> Result<Value<'js>, rquickjs::Error> {
+            check_args(&args, 2)?;
+            let name = arg_to_string(&args[0]).map_err(|e| to_js_error(cx.clone(), e))?;
+            let key = arg_to_string(&args[1]).map_err(|e| to_js_error(cx.clone(), e))?;
+            match get(&name, &key).map_err(|e| to_js_error(cx.clone(), e))? {
+                Some(response) => response.into_js(&cx),
+                None => Ok(Value::new_null(cx.clone())),
+            }
+        },
+    )?;
+
+    // cache_put(name, key, response, ttl) stores a buffered response for ttl seconds
+    let put_callback = Function::new(
+        ctx.clone(),
+        |cx: Ctx<'js>, args: Rest<Value<'js>>| -> Result<(), rquickjs::Error> {
+            check_args(&args, 4)?;
+            let name = arg_to_string(&args[0]).map_err(|e| to_js_error(cx.clone(), e))?;
+            let key = arg_to_string(&args[1]).map_err(|e| to_js_error(cx.clone(), e))?;
+            let js_response = JsHttpObject::from_js(&cx, args[2].clone())?;
+            let ttl = args[3].as_number().unwrap_or(0.0).max(0.0) as u64;
+            let response = CachedResponse {
+                status: js_response.status,
+                headers: js_response.headers,
+                body: js_response.body.unwrap_or_default(),
+            };
+            put(&name, &key, response, ttl).map_err(|e| to_js_error(cx.clone(), e))
+        },
+    )?;
+
+    // cache_delete(name, key) returns whether the key was cached
+    let delete_callback = Function::new(
+        ctx.clone(),
+        |cx: Ctx<'js>, args: Rest<Value<'js>>| -> Result<bool, rquickjs::Error> {
+            check_args(&args, 2)?;
+            let name = arg_to_string(&args[0]).map_err(|e| to_js_error(cx.clone(), e))?;
+            let key = arg_to_string(&args[1]).map_err(|e| to_js_error(cx.clone(), e))?;
+            delete(&name, &key).map_err(|e| to_js_error(cx.clone(), e))
+        },
+    )?;
+
+    hostcall.set("cache_match", match_callback)?;
+    hostcall.set("cache_put", put_callback)?;
+    hostcall.set("cache_delete", delete_callback)?;
+    Ok(())
+}
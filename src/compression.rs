@@ -0,0 +1,282 @@
+use crate::hostcall::{arg_to_bytes, to_js_error};
+use anyhow::{anyhow, Result};
+use flate2::{write, Compression};
+use rquickjs::{class::Trace, ArrayBuffer, Class, Ctx, Object, Value};
+use std::cell::RefCell;
+use std::io::Write;
+use std::rc::Rc;
+
+// MAX_DECOMPRESSED limits the output of a decompression stream, so a small compressed body
+// can not expand to exhaust the memory of the instance
+const MAX_DECOMPRESSED: usize = 64 * 1024 * 1024;
+
+// ZSTD_STEP is the zstd input passed to the decoder at once, a zstd block of a few bytes can decode
+// to 128 KiB, so the input is passed in small steps and the output limit is checked between them.
+// The step is doubled while it does not hold a whole block.
+const ZSTD_STEP: usize = 256;
+
+// ZSTD_FRAME_HEADER is the maximum size of a zstd frame header, with the magic number
+const ZSTD_FRAME_HEADER: usize = 18;
+
+#[derive(Default)]
+struct Buffer {
+    bytes: Vec<u8>,
+    total: usize,
+    limit: Option<usize>,
+}
+
+/// Output collects the bytes written by a codec, they are taken after each chunk.
+/// The total output of a decoder is limited to MAX_DECOMPRESSED.
+#[derive(Clone, Default)]
+struct Output(Rc<RefCell<Buffer>>);
+
+impl Output {
+    fn limited(limit: usize) -> Self {
+        Self(Rc::new(RefCell::new(Buffer {
+            limit: Some(limit),
+            ..Buffer::default()
+        })))
+    }
+
+    fn take(&self) -> Vec<u8> {
+        std::mem::take(&mut self.0.borrow_mut().bytes)
+    }
+}
+
+impl Write for Output {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let mut buffer = self.0.borrow_mut();
+        if let Some(limit) = buffer.limit {
+            if buffer.total + buf.len() > limit {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::Other,
+                    format!("Decompressed data exceeds {} bytes", limit),
+                ));
+            }
+        }
+        buffer.total += buf.len();
+        buffer.bytes.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// ZstdDecoder decodes the zstd frames incrementally, the input is kept until it holds a whole block
+struct ZstdDecoder {
+    decoder: ruzstd::FrameDecoder,
+    input: Vec<u8>,
+    // started is true while a frame is decoded, frames is the count of the started frames
+    started: bool,
+    frames: usize,
+}
+
+impl ZstdDecoder {
+    fn new() -> Self {
+        Self {
+            decoder: ruzstd::FrameDecoder::new(),
+            input: Vec::new(),
+            started: false,
+            frames: 0,
+        }
+    }
+
+    // decode writes the output of the complete blocks of the input,
+    // the input must be complete frames if finish is true
+    fn decode(&mut self, output: &mut Output, finish: bool) -> Result<()> {
+        let mut buffer = vec![0u8; 64 * 1024];
+        let mut step = ZSTD_STEP;
+        loop {
+            if !self.started {
+                if self.input.is_empty() {
+                    break;
+                }
+                if !finish && self.input.len() < ZSTD_FRAME_HEADER {
+                    return Ok(());
+                }
+                let mut source = self.input.as_slice();
+                self.decoder
+                    .reset(&mut source)
+                    .map_err(|e| anyhow!("Invalid zstd data: {:?}", e))?;
+                let consumed = self.input.len() - source.len();
+                self.input.drain(..consumed);
+                self.started = true;
+                self.frames += 1;
+            }
+            let end = step.min(self.input.len());
+            let (read, written) = self
+                .decoder
+                .decode_from_to(&self.input[..end], &mut buffer)
+                .map_err(|e| anyhow!("Invalid zstd data: {:?}", e))?;
+            self.input.drain(..read);
+            output.write_all(&buffer[..written])?;
+            if self.decoder.is_finished() && self.decoder.can_collect() == 0 {
+                self.started = false;
+                continue;
+            }
+            if read == 0 && written == 0 {
+                if end < self.input.len() {
+                    step *= 2;
+                    continue;
+                }
+                break;
+            }
+            step = ZSTD_STEP;
+        }
+        if finish && (self.started || self.frames == 0) {
+            return Err(anyhow!("Unexpected end of zstd data"));
+        }
+        Ok(())
+    }
+}
+
+enum Writer {
+    GzipEncoder(write::GzEncoder<Output>),
+    DeflateEncoder(write::ZlibEncoder<Output>),
+    DeflateRawEncoder(write::DeflateEncoder<Output>),
+    GzipDecoder(write::GzDecoder<Output>),
+    DeflateDecoder(write::ZlibDecoder<Output>),
+    DeflateRawDecoder(write::DeflateDecoder<Output>),
+    BrotliDecoder(Box<brotli_decompressor::DecompressorWriter<Output>>),
+    ZstdDecoder(Box<ZstdDecoder>),
+}
+
+/// Codec is the streaming compressor or decompressor of a CompressionStream or DecompressionStream,
+/// it is freed with its JS object, so a stream that is never finished does not keep it
+#[derive(Trace)]
+#[rquickjs::class]
+pub struct Codec {
+    // writer is None after the stream finished or failed
+    #[qjs(skip_trace)]
+    writer: Option<Writer>,
+    #[qjs(skip_trace)]
+    output: Output,
+}
+
+impl Codec {
+    fn create(format: &str, compress: bool) -> Result<Self> {
+        let output = if compress {
+            Output::default()
+        } else {
+            Output::limited(MAX_DECOMPRESSED)
+        };
+        let out = output.clone();
+        let writer = match (format, compress) {
+            ("gzip", true) => {
+                Writer::GzipEncoder(write::GzEncoder::new(out, Compression::default()))
+            }
+            ("deflate", true) => {
+                Writer::DeflateEncoder(write::ZlibEncoder::new(out, Compression::default()))
+            }
+            ("deflate-raw", true) => {
+                Writer::DeflateRawEncoder(write::DeflateEncoder::new(out, Compression::default()))
+            }
+            ("gzip", false) => Writer::GzipDecoder(write::GzDecoder::new(out)),
+            ("deflate", false) => Writer::DeflateDecoder(write::ZlibDecoder::new(out)),
+            ("deflate-raw", false) => Writer::DeflateRawDecoder(write::DeflateDecoder::new(out)),
+            ("br", false) | ("brotli", false) => Writer::BrotliDecoder(Box::new(
+                brotli_decompressor::DecompressorWriter::new(out, 4096),
+            )),
+            ("zstd", false) => Writer::ZstdDecoder(Box::new(ZstdDecoder::new())),
+            _ => {
+                return Err(anyhow!(
+                    "Unsupported {} format: {}",
+                    if compress {
+                        "compression"
+                    } else {
+                        "decompression"
+                    },
+                    format
+                ))
+            }
+        };
+        Ok(Self {
+            writer: Some(writer),
+            output,
+        })
+    }
+
+    fn write_chunk(&mut self, chunk: &[u8]) -> Result<Vec<u8>> {
+        let writer = self
+            .writer
+            .as_mut()
+            .ok_or_else(|| anyhow!("The compression stream is already finished"))?;
+        match writer {
+            Writer::GzipEncoder(w) => w.write_all(chunk)?,
+            Writer::DeflateEncoder(w) => w.write_all(chunk)?,
+            Writer::DeflateRawEncoder(w) => w.write_all(chunk)?,
+            Writer::GzipDecoder(w) => w.write_all(chunk)?,
+            Writer::DeflateDecoder(w) => w.write_all(chunk)?,
+            Writer::DeflateRawDecoder(w) => w.write_all(chunk)?,
+            Writer::BrotliDecoder(w) => w.write_all(chunk)?,
+            Writer::ZstdDecoder(decoder) => {
+                decoder.input.extend_from_slice(chunk);
+                decoder.decode(&mut self.output, false)?;
+            }
+        }
+        Ok(self.output.take())
+    }
+
+    fn finish_stream(&mut self) -> Result<Vec<u8>> {
+        let writer = self
+            .writer
+            .take()
+            .ok_or_else(|| anyhow!("The compression stream is already finished"))?;
+        match writer {
+            Writer::GzipEncoder(mut w) => w.try_finish()?,
+            Writer::DeflateEncoder(mut w) => w.try_finish()?,
+            Writer::DeflateRawEncoder(mut w) => w.try_finish()?,
+            Writer::GzipDecoder(mut w) => w.try_finish()?,
+            Writer::DeflateDecoder(mut w) => w.try_finish()?,
+            Writer::DeflateRawDecoder(mut w) => w.try_finish()?,
+            Writer::BrotliDecoder(mut w) => w.close()?,
+            Writer::ZstdDecoder(mut decoder) => decoder.decode(&mut self.output, true)?,
+        }
+        Ok(self.output.take())
+    }
+}
+
+#[rquickjs::methods]
+impl<'js> Codec {
+    /// new Codec(format, compress) creates a compressor or a decompressor of format
+    #[qjs(constructor)]
+    pub fn new(ctx: Ctx<'js>, format: String, compress: bool) -> rquickjs::Result<Self> {
+        Self::create(&format, compress).map_err(|e| to_js_error(ctx, e))
+    }
+
+    /// write(chunk) returns the output bytes of chunk as ArrayBuffer, the codec is dropped if it fails
+    pub fn write(
+        &mut self,
+        ctx: Ctx<'js>,
+        chunk: Value<'js>,
+    ) -> rquickjs::Result<ArrayBuffer<'js>> {
+        let chunk = arg_to_bytes(&chunk).map_err(|e| to_js_error(ctx.clone(), e))?;
+        let output = self.write_chunk(&chunk).map_err(|e| {
+            self.writer = None;
+            to_js_error(ctx.clone(), e)
+        })?;
+        ArrayBuffer::new(ctx, output)
+    }
+
+    /// finish() ends the codec and returns the remaining output bytes as ArrayBuffer
+    pub fn finish(&mut self, ctx: Ctx<'js>) -> rquickjs::Result<ArrayBuffer<'js>> {
+        let output = self
+            .finish_stream()
+            .map_err(|e| to_js_error(ctx.clone(), e))?;
+        ArrayBuffer::new(ctx, output)
+    }
+
+    /// close() drops the codec when the stream is cancelled
+    pub fn close(&mut self) {
+        self.writer = None;
+    }
+}
+
+/// build native compression object that used by CompressionStream and DecompressionStream
+pub fn build<'js>(ctx: Ctx<'js>) -> rquickjs::Result<Object> {
+    let compression = Object::new(ctx.clone())?;
+    Class::<Codec>::define(&compression)?;
+    Ok(compression)
+}
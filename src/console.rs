@@ -1,28 +1,542 @@
-use crate::hostcall::{get_args_as_str, to_js_error};
-use rquickjs::{prelude::MutFn, Ctx, Function, Object};
+use crate::bindings;
+use crate::hostcall::{arg_to_string, check_args, to_js_error};
+use anyhow::anyhow;
+use once_cell::sync::Lazy;
+use rquickjs::{prelude::Rest, Coerced, Ctx, FromJs, Function, Object, Value};
+use serde_json::{json, Value as JsonValue};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+// LOG_FORMAT_ENV selects the console output. "json" (default) writes a JSON line to stdout for every record,
+// "text" writes the message to stdout or stderr by level, "host" sends the records of a request
+// to the bindings service when the request is done.
+const LOG_FORMAT_ENV: &str = "LAND_LOG_FORMAT";
+
+// SCRIPT_ID_ENV is the id of the script in the records, it is set by the platform
+const SCRIPT_ID_ENV: &str = "LAND_SCRIPT_ID";
+
+/// REQUEST_ID_HEADER is the request id set by the platform, a random id is used if it is missing
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+// REQUEST_ID is the id of the running request, None in wizer.initialize
+static REQUEST_ID: Mutex<Option<String>> = Mutex::new(None);
+
+// PENDING keeps the records of the running request in the host format
+static PENDING: Mutex<Vec<JsonValue>> = Mutex::new(Vec::new());
+
+// GROUP_INDENT is the level of console.group, TIMERS and COUNTERS keep the labels of console.time and console.count,
+// they are reset for every request
+static GROUP_INDENT: Mutex<usize> = Mutex::new(0);
+static TIMERS: Lazy<Mutex<HashMap<String, Instant>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+static COUNTERS: Lazy<Mutex<HashMap<String, u64>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Level is the level of a record
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Level {
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl Level {
+    fn parse(value: &str) -> anyhow::Result<Self> {
+        match value {
+            "debug" => Ok(Level::Debug),
+            "info" => Ok(Level::Info),
+            "warn" => Ok(Level::Warn),
+            "error" => Ok(Level::Error),
+            _ => Err(anyhow!("Unknown log level '{}'", value)),
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            Level::Debug => "debug",
+            Level::Info => "info",
+            Level::Warn => "warn",
+            Level::Error => "error",
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Format {
+    Json,
+    Text,
+    Host,
+}
+
+fn format() -> Format {
+    match crate::env::var(LOG_FORMAT_ENV).as_deref() {
+        Some("text") => Format::Text,
+        Some("host") if bindings::endpoint().is_some() => Format::Host,
+        _ => Format::Json,
+    }
+}
+
+// timestamp returns the current time in RFC 3339 UTC with milliseconds
+fn timestamp() -> String {
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0);
+    let (days, millis_of_day) = (millis.div_euclid(86_400_000), millis.rem_euclid(86_400_000));
+    // civil date from days since 1970-01-01, http://howardhinnant.github.io/date_algorithms.html
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year,
+        month,
+        day,
+        millis_of_day / 3_600_000,
+        millis_of_day / 60_000 % 60,
+        millis_of_day / 1000 % 60,
+        millis_of_day % 1000
+    )
+}
+
+/// begin_request sets the request id of the records, it is the platform request id or a random id
+pub fn begin_request(request_id: Option<&str>) {
+    let request_id = request_id
+        .filter(|id| !id.is_empty())
+        .map(|id| id.to_string())
+        .unwrap_or_else(|| crate::random::hex_id(8));
+    *REQUEST_ID.lock().unwrap() = Some(request_id);
+    PENDING.lock().unwrap().clear();
+    *GROUP_INDENT.lock().unwrap() = 0;
+    TIMERS.lock().unwrap().clear();
+    COUNTERS.lock().unwrap().clear();
+}
+
+/// end_request sends the records of the request to the bindings service in the host format
+pub fn end_request() {
+    let records: Vec<JsonValue> = PENDING.lock().unwrap().drain(..).collect();
+    *REQUEST_ID.lock().unwrap() = None;
+    if records.is_empty() {
+        return;
+    }
+    let body = json!({ "records": records }).to_string().into_bytes();
+    let headers = vec![("content-type".to_string(), "application/json".to_string())];
+    if let Err(err) = bindings::call("POST", "/logs", &headers, Some(body)) {
+        eprintln!("send log records error: {:?}", err);
+    }
+}
+
+/// log writes a record of message at level, the secret values in message are redacted
+pub fn log(level: Level, message: &str) {
+    let message = crate::env::redact(message);
+    crate::debug::record_log(level.as_str(), &message);
+    let format = format();
+    if format == Format::Text {
+        match level {
+            Level::Debug | Level::Info => println!("{}", message),
+            Level::Warn | Level::Error => eprintln!("{}", message),
+        }
+        return;
+    }
+    let record = json!({
+        "timestamp": timestamp(),
+        "level": level.as_str(),
+        "request_id": REQUEST_ID.lock().unwrap().clone(),
+        "script_id": crate::env::var(SCRIPT_ID_ENV),
+        "message": message,
+    });
+    // the records out of a request, such as in wizer.initialize, can not be sent later
+    if format == Format::Host && REQUEST_ID.lock().unwrap().is_some() {
+        PENDING.lock().unwrap().push(record);
+        return;
+    }
+    println!("{}", record);
+}
+
+// write_group writes a record of message indented by the console.group level
+fn write_group(level: Level, message: &str) {
+    let indent = "  ".repeat(*GROUP_INDENT.lock().unwrap());
+    if indent.is_empty() {
+        return log(level, message);
+    }
+    let message = message
+        .lines()
+        .map(|line| format!("{}{}", indent, line))
+        .collect::<Vec<_>>()
+        .join("\n");
+    log(level, &message);
+}
+
+// label returns the label arg of console.time and console.count, "default" if it is undefined
+fn label<'js>(cx: &Ctx<'js>, args: &[Value<'js>]) -> rquickjs::Result<String> {
+    match args.first() {
+        Some(label) if !label.is_undefined() => {
+            Ok(Coerced::<String>::from_js(cx, label.clone())?.0)
+        }
+        _ => Ok("default".to_string()),
+    }
+}
+
+// elapsed returns the duration like console.timeEnd of Node.js, such as "1.234ms" or "1.234s"
+fn elapsed(start: Instant) -> String {
+    let millis = start.elapsed().as_secs_f64() * 1000.0;
+    if millis < 1000.0 {
+        format!("{:.3}ms", millis)
+    } else {
+        format!("{:.3}s", millis / 1000.0)
+    }
+}
+
+// table returns the rows of data as a box-drawing table like console.table,
+// the object rows have a column for every key and the primitive rows have the "Values" column
+fn table<'js>(
+    cx: &Ctx<'js>,
+    data: &Object<'js>,
+    filter: Option<Vec<String>>,
+) -> rquickjs::Result<String> {
+    const INDEX: &str = "(index)";
+    const VALUES: &str = "Values";
+    let mut columns: Vec<String> = Vec::new();
+    let mut rows: Vec<(String, HashMap<String, String>)> = Vec::new();
+    let mut has_values = false;
+    for key in data.keys::<String>() {
+        let key = key?;
+        let row: Value = data.get(key.as_str())?;
+        let mut cells = HashMap::new();
+        match row.as_object() {
+            Some(object) if !row.is_function() => {
+                for column in object.keys::<String>() {
+                    let column = column?;
+                    let value: Value = object.get(column.as_str())?;
+                    if !columns.contains(&column) {
+                        columns.push(column.clone());
+                    }
+                    cells.insert(column, crate::inspect::inspect(cx, &value, 0)?);
+                }
+            }
+            _ => {
+                has_values = true;
+                cells.insert(VALUES.to_string(), crate::inspect::inspect(cx, &row, 0)?);
+            }
+        }
+        rows.push((key, cells));
+    }
+    if let Some(filter) = filter {
+        columns = filter;
+    }
+    if has_values {
+        columns.push(VALUES.to_string());
+    }
+
+    let header: Vec<String> = std::iter::once(INDEX.to_string())
+        .chain(columns.iter().cloned())
+        .collect();
+    let body: Vec<Vec<String>> = rows
+        .into_iter()
+        .map(|(index, mut cells)| {
+            std::iter::once(index)
+                .chain(
+                    columns
+                        .iter()
+                        .map(|column| cells.remove(column).unwrap_or_default()),
+                )
+                .collect()
+        })
+        .collect();
+    let widths: Vec<usize> = (0..header.len())
+        .map(|i| {
+            body.iter()
+                .map(|row| row[i].chars().count())
+                .chain(std::iter::once(header[i].chars().count()))
+                .max()
+                .unwrap_or_default()
+                + 2
+        })
+        .collect();
+    let border = |left: &str, middle: &str, right: &str| {
+        let lines: Vec<String> = widths.iter().map(|width| "─".repeat(*width)).collect();
+        format!("{}{}{}", left, lines.join(middle), right)
+    };
+    let line = |row: &[String]| {
+        let cells: Vec<String> = row
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| {
+                format!(" {}{}", cell, " ".repeat(width - 1 - cell.chars().count()))
+            })
+            .collect();
+        format!("│{}│", cells.join("│"))
+    };
+    let mut lines = vec![border("┌", "┬", "┐"), line(&header), border("├", "┼", "┤")];
+    lines.extend(body.iter().map(|row| line(row)));
+    lines.push(border("└", "┴", "┘"));
+    Ok(lines.join("\n"))
+}
+
+// stack returns the QuickJS stack of the caller, the frame of the native function is skipped
+fn stack(cx: &Ctx) -> rquickjs::Result<String> {
+    let error = rquickjs::Exception::from_message(cx.clone(), "")?;
+    let stack: Value = error.as_object().get("stack")?;
+    let stack = stack
+        .as_string()
+        .map(|stack| stack.to_string())
+        .transpose()?
+        .unwrap_or_default();
+    Ok(stack
+        .lines()
+        .filter(|line| !line.contains("<native>") && !line.contains("(native)"))
+        .collect::<Vec<_>>()
+        .join("\n"))
+}
+
+// level_function returns a console function that writes the formatted args at level
+fn level_function<'js>(ctx: &Ctx<'js>, level: Level) -> rquickjs::Result<Function<'js>> {
+    Function::new(
+        ctx.clone(),
+        move |cx: Ctx<'js>, args: Rest<Value<'js>>| -> Result<(), rquickjs::Error> {
+            write_group(level, &crate::inspect::format(&cx, &args)?);
+            Ok(())
+        },
+    )
+}
 
 /// build console object that used export to globalThis
-pub fn build(ctx: Ctx) -> rquickjs::Result<Object> {
+pub fn build<'js>(ctx: Ctx<'js>) -> rquickjs::Result<Object<'js>> {
     let console = Object::new(ctx.clone())?;
-    let console_info_callback = Function::new(
-        ctx.clone(),
-        MutFn::new(move |cx, args| {
-            let statement = get_args_as_str(&args).map_err(|e| to_js_error(cx, e))?;
-            println!("{}", statement);
-            Ok::<_, rquickjs::Error>(())
-        }),
-    )?;
-    console.set("print", console_info_callback.clone())?;
-    console.set(
-        "print_error",
-        Function::new(
-            ctx.clone(),
-            MutFn::new(move |cx, args| {
-                let statement = get_args_as_str(&args).map_err(|e| to_js_error(cx, e))?;
-                eprintln!("{}", statement);
-                Ok::<_, rquickjs::Error>(())
-            }),
-        ),
+    // write(level, ...args) writes a record of the args formatted like util.format
+    let write_callback = Function::new(
+        ctx.clone(),
+        |cx: Ctx<'js>, args: Rest<Value<'js>>| -> Result<(), rquickjs::Error> {
+            check_args(&args, 1)?;
+            let level = arg_to_string(&args[0])
+                .and_then(|level| Level::parse(&level))
+                .map_err(|e| to_js_error(cx.clone(), e))?;
+            let message = crate::inspect::format(&cx, &args[1..])?;
+            write_group(level, &message);
+            Ok(())
+        },
+    )?;
+
+    // time(label) starts a timer, it warns if the timer exists
+    let time_callback = Function::new(
+        ctx.clone(),
+        |cx: Ctx<'js>, args: Rest<Value<'js>>| -> Result<(), rquickjs::Error> {
+            let label = label(&cx, &args)?;
+            let mut timers = TIMERS.lock().unwrap();
+            if timers.contains_key(&label) {
+                drop(timers);
+                write_group(Level::Warn, &format!("Timer '{}' already exists", label));
+                return Ok(());
+            }
+            timers.insert(label, Instant::now());
+            Ok(())
+        },
+    )?;
+
+    // timeLog(label, ...data) writes the elapsed time of the timer and data
+    let time_log_callback = Function::new(
+        ctx.clone(),
+        |cx: Ctx<'js>, args: Rest<Value<'js>>| -> Result<(), rquickjs::Error> {
+            let label = label(&cx, &args)?;
+            let start = TIMERS.lock().unwrap().get(&label).copied();
+            let start = match start {
+                Some(start) => start,
+                None => {
+                    write_group(Level::Warn, &format!("Timer '{}' does not exist", label));
+                    return Ok(());
+                }
+            };
+            let mut message = format!("{}: {}", label, elapsed(start));
+            if args.len() > 1 {
+                message.push(' ');
+                message.push_str(&crate::inspect::format(&cx, &args[1..])?);
+            }
+            write_group(Level::Info, &message);
+            Ok(())
+        },
+    )?;
+
+    // timeEnd(label) writes the elapsed time of the timer and removes it
+    let time_end_callback = Function::new(
+        ctx.clone(),
+        |cx: Ctx<'js>, args: Rest<Value<'js>>| -> Result<(), rquickjs::Error> {
+            let label = label(&cx, &args)?;
+            let start = TIMERS.lock().unwrap().remove(&label);
+            match start {
+                Some(start) => write_group(Level::Info, &format!("{}: {}", label, elapsed(start))),
+                None => write_group(Level::Warn, &format!("Timer '{}' does not exist", label)),
+            }
+            Ok(())
+        },
+    )?;
+
+    // count(label) writes the number of the calls with label
+    let count_callback = Function::new(
+        ctx.clone(),
+        |cx: Ctx<'js>, args: Rest<Value<'js>>| -> Result<(), rquickjs::Error> {
+            let label = label(&cx, &args)?;
+            let count = {
+                let mut counters = COUNTERS.lock().unwrap();
+                let count = counters.entry(label.clone()).or_insert(0);
+                *count += 1;
+                *count
+            };
+            write_group(Level::Info, &format!("{}: {}", label, count));
+            Ok(())
+        },
+    )?;
+
+    // countReset(label) resets the counter, it warns if the counter does not exist
+    let count_reset_callback = Function::new(
+        ctx.clone(),
+        |cx: Ctx<'js>, args: Rest<Value<'js>>| -> Result<(), rquickjs::Error> {
+            let label = label(&cx, &args)?;
+            let reset = COUNTERS
+                .lock()
+                .unwrap()
+                .get_mut(&label)
+                .map(|count| *count = 0)
+                .is_some();
+            if !reset {
+                write_group(
+                    Level::Warn,
+                    &format!("Count for '{}' does not exist", label),
+                );
+            }
+            Ok(())
+        },
     )?;
+
+    // assert(condition, ...data) writes an error record if condition is falsy
+    let assert_callback = Function::new(
+        ctx.clone(),
+        |cx: Ctx<'js>, args: Rest<Value<'js>>| -> Result<(), rquickjs::Error> {
+            let condition = match args.first() {
+                Some(condition) => Coerced::<bool>::from_js(&cx, condition.clone())?.0,
+                None => false,
+            };
+            if condition {
+                return Ok(());
+            }
+            let data = args.get(1..).unwrap_or_default();
+            let message = match data.first() {
+                None => "Assertion failed".to_string(),
+                Some(first) if first.is_string() => {
+                    format!("Assertion failed: {}", crate::inspect::format(&cx, data)?)
+                }
+                Some(_) => format!("Assertion failed {}", crate::inspect::format(&cx, data)?),
+            };
+            write_group(Level::Error, &message);
+            Ok(())
+        },
+    )?;
+
+    // table(data, columns) writes the rows of data as a table, other values are written like log
+    let table_callback = Function::new(
+        ctx.clone(),
+        |cx: Ctx<'js>, args: Rest<Value<'js>>| -> Result<(), rquickjs::Error> {
+            let data = match args.first().and_then(|data| data.as_object()) {
+                Some(data) if !args[0].is_function() => data.clone(),
+                _ => {
+                    write_group(Level::Info, &crate::inspect::format(&cx, &args)?);
+                    return Ok(());
+                }
+            };
+            let filter = match args.get(1) {
+                Some(columns) if columns.is_array() => Some(
+                    Vec::<Coerced<String>>::from_js(&cx, columns.clone())?
+                        .into_iter()
+                        .map(|column| column.0)
+                        .collect(),
+                ),
+                _ => None,
+            };
+            write_group(Level::Info, &table(&cx, &data, filter)?);
+            Ok(())
+        },
+    )?;
+
+    // trace(...data) writes the data and the stack of the caller
+    let trace_callback = Function::new(
+        ctx.clone(),
+        |cx: Ctx<'js>, args: Rest<Value<'js>>| -> Result<(), rquickjs::Error> {
+            let mut message = "Trace".to_string();
+            if !args.is_empty() {
+                message.push_str(": ");
+                message.push_str(&crate::inspect::format(&cx, &args)?);
+            }
+            let stack = stack(&cx)?;
+            if !stack.is_empty() {
+                message.push('\n');
+                message.push_str(&stack);
+            }
+            write_group(Level::Debug, &message);
+            Ok(())
+        },
+    )?;
+
+    // group(...label) writes the label and indents the next records, groupEnd() outdents them
+    let group_callback = Function::new(
+        ctx.clone(),
+        |cx: Ctx<'js>, args: Rest<Value<'js>>| -> Result<(), rquickjs::Error> {
+            if !args.is_empty() {
+                write_group(Level::Info, &crate::inspect::format(&cx, &args)?);
+            }
+            *GROUP_INDENT.lock().unwrap() += 1;
+            Ok(())
+        },
+    )?;
+    let group_end_callback = Function::new(ctx.clone(), || {
+        let mut indent = GROUP_INDENT.lock().unwrap();
+        *indent = indent.saturating_sub(1);
+    })?;
+
+    // dir(value, options) writes the inspected value, options.depth is the depth of the objects
+    let dir_callback = Function::new(
+        ctx.clone(),
+        |cx: Ctx<'js>, args: Rest<Value<'js>>| -> Result<(), rquickjs::Error> {
+            let value = args
+                .first()
+                .cloned()
+                .unwrap_or_else(|| Value::new_undefined(cx.clone()));
+            let depth = match args.get(1).and_then(|options| options.as_object()) {
+                Some(options) => options.get::<_, Value>("depth")?,
+                None => Value::new_undefined(cx.clone()),
+            };
+            let depth = match depth.as_number() {
+                _ if depth.is_null() => usize::MAX,
+                Some(depth) if depth < 0.0 || depth.is_infinite() => usize::MAX,
+                Some(depth) => depth as usize,
+                None => crate::inspect::DEFAULT_DEPTH,
+            };
+            write_group(Level::Info, &crate::inspect::inspect(&cx, &value, depth)?);
+            Ok(())
+        },
+    )?;
+
+    console.set("write", write_callback)?;
+    console.set("debug", level_function(&ctx, Level::Debug)?)?;
+    console.set("log", level_function(&ctx, Level::Info)?)?;
+    console.set("info", level_function(&ctx, Level::Info)?)?;
+    console.set("warn", level_function(&ctx, Level::Warn)?)?;
+    console.set("error", level_function(&ctx, Level::Error)?)?;
+    console.set("time", time_callback)?;
+    console.set("timeLog", time_log_callback)?;
+    console.set("timeEnd", time_end_callback)?;
+    console.set("count", count_callback)?;
+    console.set("countReset", count_reset_callback)?;
+    console.set("assert", assert_callback)?;
+    console.set("table", table_callback)?;
+    console.set("trace", trace_callback)?;
+    console.set("group", group_callback.clone())?;
+    console.set("groupCollapsed", group_callback)?;
+    console.set("groupEnd", group_end_callback)?;
+    console.set("dir", dir_callback)?;
     Ok(console)
 }
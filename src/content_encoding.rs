@@ -0,0 +1,196 @@
+use crate::entity::JsHttpObject;
+use anyhow::Result;
+use flate2::{write::GzEncoder, Compression};
+use rquickjs::{Ctx, Object};
+use std::io::Write;
+
+// COMPRESSION_ENV enables automatic response compression for all handlers when it is "auto",
+// a handler enables it with `export default { fetch, compression: "auto" }`
+const COMPRESSION_ENV: &str = "LAND_COMPRESSION";
+
+// MIN_COMPRESS_SIZE is the smallest body worth compressing, smaller bodies grow with the headers
+const MIN_COMPRESS_SIZE: usize = 1024;
+
+const COMPRESSIBLE_TYPES: [&str; 7] = [
+    "application/javascript",
+    "application/json",
+    "application/manifest+json",
+    "application/wasm",
+    "application/xml",
+    "application/xhtml+xml",
+    "image/svg+xml",
+];
+
+/// ContentEncoding is the encoding selected from Accept-Encoding
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ContentEncoding {
+    Brotli,
+    Gzip,
+}
+
+impl ContentEncoding {
+    fn name(&self) -> &'static str {
+        match self {
+            ContentEncoding::Brotli => "br",
+            ContentEncoding::Gzip => "gzip",
+        }
+    }
+
+    fn encode(&self, body: &[u8]) -> Result<Vec<u8>> {
+        match self {
+            ContentEncoding::Brotli => {
+                // quality 5 keeps the CPU cost close to gzip with a better ratio
+                let mut writer = brotli::CompressorWriter::new(Vec::new(), 4096, 5, 22);
+                writer.write_all(body)?;
+                Ok(writer.into_inner())
+            }
+            ContentEncoding::Gzip => {
+                let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+                encoder.write_all(body)?;
+                Ok(encoder.finish()?)
+            }
+        }
+    }
+}
+
+/// enabled returns whether automatic response compression is turned on by env or the handler
+pub fn enabled(ctx: &Ctx) -> bool {
+    let is_auto = |value: &str| value.eq_ignore_ascii_case("auto");
+    if crate::env::var(COMPRESSION_ENV).is_some_and(|value| is_auto(&value)) {
+        return true;
+    }
+    let handler: rquickjs::Result<Object> = ctx.globals().get("handler");
+    handler
+        .and_then(|handler| handler.get::<_, String>("compression"))
+        .map(|value| is_auto(&value))
+        .unwrap_or(false)
+}
+
+/// negotiate selects the encoding with the highest q-value in Accept-Encoding, brotli wins a tie
+pub fn negotiate(accept_encoding: &str) -> Option<ContentEncoding> {
+    let mut brotli = None;
+    let mut gzip = None;
+    let mut wildcard = None;
+    for item in accept_encoding.split(',') {
+        let mut parts = item.split(';');
+        let coding = parts.next().unwrap_or_default().trim().to_ascii_lowercase();
+        let mut quality = 1.0;
+        for param in parts {
+            if let Some((key, value)) = param.split_once('=') {
+                if key.trim().eq_ignore_ascii_case("q") {
+                    quality = value.trim().parse::<f32>().unwrap_or(0.0);
+                }
+            }
+        }
+        match coding.as_str() {
+            "br" => brotli = Some(quality),
+            "gzip" | "x-gzip" => gzip = Some(quality),
+            "*" => wildcard = Some(quality),
+            _ => {}
+        }
+    }
+    let brotli = brotli.or(wildcard).unwrap_or(0.0);
+    let gzip = gzip.or(wildcard).unwrap_or(0.0);
+    if brotli <= 0.0 && gzip <= 0.0 {
+        return None;
+    }
+    if brotli >= gzip {
+        Some(ContentEncoding::Brotli)
+    } else {
+        Some(ContentEncoding::Gzip)
+    }
+}
+
+fn is_compressible(content_type: &str) -> bool {
+    let mime = content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    mime.starts_with("text/")
+        || mime.ends_with("+json")
+        || mime.ends_with("+xml")
+        || COMPRESSIBLE_TYPES.contains(&mime.as_str())
+}
+
+fn header<'a>(response: &'a JsHttpObject, name: &str) -> Option<&'a String> {
+    response
+        .headers
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        .map(|(_, value)| value)
+}
+
+fn remove_header(response: &mut JsHttpObject, name: &str) {
+    response
+        .headers
+        .retain(|key, _| !key.eq_ignore_ascii_case(name));
+}
+
+// add_vary appends Accept-Encoding to Vary, so caches keep the encoded and plain responses apart
+fn add_vary(response: &mut JsHttpObject) {
+    let vary = header(response, "vary").cloned().unwrap_or_default();
+    if vary.split(',').any(|v| {
+        let v = v.trim();
+        v == "*" || v.eq_ignore_ascii_case("accept-encoding")
+    }) {
+        return;
+    }
+    remove_header(response, "vary");
+    let vary = if vary.trim().is_empty() {
+        "Accept-Encoding".to_string()
+    } else {
+        format!("{}, Accept-Encoding", vary)
+    };
+    response.headers.insert("vary".to_string(), vary);
+}
+
+/// apply compresses the buffered body of response with the encoding accepted by the request.
+/// Passthrough body_handle responses, encoded bodies and no-transform responses are not changed.
+pub fn apply(accept_encoding: Option<&str>, response: &mut JsHttpObject) -> Result<()> {
+    if response.body_handle != 0 || matches!(response.status, 204 | 206 | 304) {
+        return Ok(());
+    }
+    if header(response, "content-encoding").is_some() {
+        return Ok(());
+    }
+    if header(response, "cache-control")
+        .map(|value| value.to_ascii_lowercase().contains("no-transform"))
+        .unwrap_or(false)
+    {
+        return Ok(());
+    }
+    if !header(response, "content-type")
+        .map(|value| is_compressible(value))
+        .unwrap_or(false)
+    {
+        return Ok(());
+    }
+    let body_size = response.body.as_ref().map(|body| body.len()).unwrap_or(0);
+    if body_size < MIN_COMPRESS_SIZE {
+        return Ok(());
+    }
+    // the response depends on Accept-Encoding even when this request gets the plain body
+    add_vary(response);
+    let encoding = match accept_encoding.and_then(negotiate) {
+        Some(encoding) => encoding,
+        None => return Ok(()),
+    };
+    let encoded = encoding.encode(response.body.as_ref().unwrap())?;
+    remove_header(response, "content-length");
+    response
+        .headers
+        .insert("content-encoding".to_string(), encoding.name().to_string());
+    response.body = Some(encoded);
+    // the encoded body is not byte-identical any more, so a strong ETag becomes weak
+    if let Some(etag) = header(response, "etag").cloned() {
+        if etag.starts_with('"') {
+            remove_header(response, "etag");
+            response
+                .headers
+                .insert("etag".to_string(), format!("W/{}", etag));
+        }
+    }
+    Ok(())
+}
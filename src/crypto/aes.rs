@@ -0,0 +1,294 @@
+use aes::cipher::{
+    block_padding::Pkcs7, generic_array::GenericArray, BlockDecryptMut, BlockEncrypt,
+    BlockEncryptMut, KeyInit, KeyIvInit,
+};
+use aes::{Aes128, Aes192, Aes256};
+use aes_gcm::{
+    aead::{
+        consts::{U12, U13, U14, U15, U16},
+        generic_array::typenum::Unsigned,
+        Aead, AeadCore, Nonce, Payload,
+    },
+    AesGcm,
+};
+use aes_kw::Kek;
+use anyhow::{anyhow, Result};
+use rquickjs::{ArrayBuffer, Ctx, FromJs, Value};
+
+/// AesParams is the algorithm parameters passed from SubtleCrypto encrypt/decrypt
+#[derive(Debug, Default)]
+pub struct AesParams {
+    pub name: String,
+    pub iv: Vec<u8>,
+    pub additional_data: Vec<u8>,
+    pub tag_length: u32,
+    pub counter: Vec<u8>,
+    pub length: u32,
+}
+
+impl<'js> FromJs<'js> for AesParams {
+    fn from_js(ctx: &Ctx<'js>, value: Value<'js>) -> rquickjs::Result<Self> {
+        if !value.is_object() {
+            return Err(ctx.throw(
+                rquickjs::String::from_str(ctx.clone(), "AesParams need from an object")?
+                    .into_value(),
+            ));
+        }
+        let params_object = value.as_object().unwrap();
+        let name_value: Value = params_object.get("name")?;
+        let name = if name_value.is_string() {
+            name_value.into_string().unwrap().to_string()?
+        } else {
+            "".to_string()
+        };
+        let buffer_field = |key: &str| -> rquickjs::Result<Vec<u8>> {
+            let value: Value = params_object.get(key)?;
+            Ok(ArrayBuffer::from_value(value)
+                .and_then(|buffer| buffer.as_bytes().map(|bytes| bytes.to_vec()))
+                .unwrap_or_default())
+        };
+        let tag_length_value: Value = params_object.get("tagLength")?;
+        let tag_length = tag_length_value.as_number().unwrap_or(128.0) as u32;
+        let length_value: Value = params_object.get("length")?;
+        let length = length_value.as_number().unwrap_or(0.0) as u32;
+        Ok(Self {
+            name,
+            iv: buffer_field("iv")?,
+            additional_data: buffer_field("additionalData")?,
+            tag_length,
+            counter: buffer_field("counter")?,
+            length,
+        })
+    }
+}
+
+/// encrypt data with AES-GCM, AES-CBC or AES-CTR
+pub fn encrypt(params: &AesParams, key: &[u8], data: &[u8]) -> Result<Vec<u8>> {
+    match params.name.as_str() {
+        "AES-GCM" => gcm(key, params, data, true),
+        "AES-CBC" => cbc_encrypt(key, &params.iv, data),
+        "AES-CTR" => ctr(key, &params.counter, params.length, data),
+        _ => Err(anyhow!("Unrecognized AES algorithm: {}", params.name)),
+    }
+}
+
+/// decrypt data with AES-GCM, AES-CBC or AES-CTR
+pub fn decrypt(params: &AesParams, key: &[u8], data: &[u8]) -> Result<Vec<u8>> {
+    match params.name.as_str() {
+        "AES-GCM" => gcm(key, params, data, false),
+        "AES-CBC" => cbc_decrypt(key, &params.iv, data),
+        "AES-CTR" => ctr(key, &params.counter, params.length, data),
+        _ => Err(anyhow!("Unrecognized AES algorithm: {}", params.name)),
+    }
+}
+
+fn gcm_apply<C: KeyInit + Aead>(
+    key: &[u8],
+    iv: &[u8],
+    aad: &[u8],
+    data: &[u8],
+    encrypt: bool,
+) -> Result<Vec<u8>> {
+    if iv.len() != <C as AeadCore>::NonceSize::USIZE {
+        return Err(anyhow!(
+            "AES-GCM iv must be {} bytes",
+            <C as AeadCore>::NonceSize::USIZE
+        ));
+    }
+    let cipher = C::new_from_slice(key).map_err(key_error)?;
+    let nonce = Nonce::<C>::from_slice(iv);
+    let payload = Payload { msg: data, aad };
+    if encrypt {
+        cipher
+            .encrypt(nonce, payload)
+            .map_err(|_| anyhow!("AES-GCM encryption failed"))
+    } else {
+        // the error message is fixed to avoid leaking why the tag check failed
+        cipher
+            .decrypt(nonce, payload)
+            .map_err(|_| anyhow!("The operation failed for an operation-specific reason"))
+    }
+}
+
+fn gcm(key: &[u8], params: &AesParams, data: &[u8], encrypt: bool) -> Result<Vec<u8>> {
+    macro_rules! gcm_with_tag {
+        ($cipher:ty) => {
+            match params.tag_length {
+                96 => gcm_apply::<AesGcm<$cipher, U12, U12>>(
+                    key,
+                    &params.iv,
+                    &params.additional_data,
+                    data,
+                    encrypt,
+                ),
+                104 => gcm_apply::<AesGcm<$cipher, U12, U13>>(
+                    key,
+                    &params.iv,
+                    &params.additional_data,
+                    data,
+                    encrypt,
+                ),
+                112 => gcm_apply::<AesGcm<$cipher, U12, U14>>(
+                    key,
+                    &params.iv,
+                    &params.additional_data,
+                    data,
+                    encrypt,
+                ),
+                120 => gcm_apply::<AesGcm<$cipher, U12, U15>>(
+                    key,
+                    &params.iv,
+                    &params.additional_data,
+                    data,
+                    encrypt,
+                ),
+                128 => gcm_apply::<AesGcm<$cipher, U12, U16>>(
+                    key,
+                    &params.iv,
+                    &params.additional_data,
+                    data,
+                    encrypt,
+                ),
+                _ => Err(anyhow!(
+                    "AES-GCM tagLength {} is not supported",
+                    params.tag_length
+                )),
+            }
+        };
+    }
+    match key.len() {
+        16 => gcm_with_tag!(Aes128),
+        24 => gcm_with_tag!(Aes192),
+        32 => gcm_with_tag!(Aes256),
+        _ => Err(anyhow!("Invalid AES key length")),
+    }
+}
+
+fn cbc_encrypt(key: &[u8], iv: &[u8], data: &[u8]) -> Result<Vec<u8>> {
+    if iv.len() != 16 {
+        return Err(anyhow!("AES-CBC iv must be 16 bytes"));
+    }
+    let encrypted = match key.len() {
+        16 => cbc::Encryptor::<Aes128>::new_from_slices(key, iv)
+            .map_err(key_error)?
+            .encrypt_padded_vec_mut::<Pkcs7>(data),
+        24 => cbc::Encryptor::<Aes192>::new_from_slices(key, iv)
+            .map_err(key_error)?
+            .encrypt_padded_vec_mut::<Pkcs7>(data),
+        32 => cbc::Encryptor::<Aes256>::new_from_slices(key, iv)
+            .map_err(key_error)?
+            .encrypt_padded_vec_mut::<Pkcs7>(data),
+        _ => return Err(anyhow!("Invalid AES key length")),
+    };
+    Ok(encrypted)
+}
+
+fn cbc_decrypt(key: &[u8], iv: &[u8], data: &[u8]) -> Result<Vec<u8>> {
+    if iv.len() != 16 {
+        return Err(anyhow!("AES-CBC iv must be 16 bytes"));
+    }
+    let decrypted = match key.len() {
+        16 => cbc::Decryptor::<Aes128>::new_from_slices(key, iv)
+            .map_err(key_error)?
+            .decrypt_padded_vec_mut::<Pkcs7>(data),
+        24 => cbc::Decryptor::<Aes192>::new_from_slices(key, iv)
+            .map_err(key_error)?
+            .decrypt_padded_vec_mut::<Pkcs7>(data),
+        32 => cbc::Decryptor::<Aes256>::new_from_slices(key, iv)
+            .map_err(key_error)?
+            .decrypt_padded_vec_mut::<Pkcs7>(data),
+        _ => return Err(anyhow!("Invalid AES key length")),
+    };
+    decrypted.map_err(|_| anyhow!("The operation failed for an operation-specific reason"))
+}
+
+// ctr follows WebCrypto semantics: only the rightmost `length` bits of the
+// counter block are incremented and they wrap without touching the nonce part.
+fn ctr(key: &[u8], counter: &[u8], length: u32, data: &[u8]) -> Result<Vec<u8>> {
+    if counter.len() != 16 {
+        return Err(anyhow!("AES-CTR counter must be 16 bytes"));
+    }
+    if length == 0 || length > 128 {
+        return Err(anyhow!("AES-CTR length must be between 1 and 128"));
+    }
+    let blocks = data.len().div_ceil(16) as u128;
+    if length < 128 && blocks > (1u128 << length) {
+        return Err(anyhow!("AES-CTR counter would wrap around"));
+    }
+    match key.len() {
+        16 => Ok(ctr_apply(
+            &Aes128::new_from_slice(key).map_err(key_error)?,
+            counter,
+            length,
+            data,
+        )),
+        24 => Ok(ctr_apply(
+            &Aes192::new_from_slice(key).map_err(key_error)?,
+            counter,
+            length,
+            data,
+        )),
+        32 => Ok(ctr_apply(
+            &Aes256::new_from_slice(key).map_err(key_error)?,
+            counter,
+            length,
+            data,
+        )),
+        _ => Err(anyhow!("Invalid AES key length")),
+    }
+}
+
+fn ctr_apply<C: BlockEncrypt>(cipher: &C, counter: &[u8], length: u32, data: &[u8]) -> Vec<u8> {
+    let mask = if length == 128 {
+        u128::MAX
+    } else {
+        (1u128 << length) - 1
+    };
+    let mut block_value = u128::from_be_bytes(counter.try_into().unwrap());
+    let mut output = Vec::with_capacity(data.len());
+    for chunk in data.chunks(16) {
+        let mut block = GenericArray::clone_from_slice(&block_value.to_be_bytes());
+        cipher.encrypt_block(&mut block);
+        output.extend(chunk.iter().zip(block.iter()).map(|(a, b)| a ^ b));
+        block_value = (block_value & !mask) | (block_value.wrapping_add(1) & mask);
+    }
+    output
+}
+
+fn key_error<E>(_: E) -> anyhow::Error {
+    anyhow!("Invalid AES key length")
+}
+
+/// wrap key bytes with AES-KW (RFC 3394)
+pub fn wrap_key(kek: &[u8], data: &[u8]) -> Result<Vec<u8>> {
+    let wrapped = match kek.len() {
+        16 => Kek::<Aes128>::try_from(kek)
+            .map_err(key_error)?
+            .wrap_vec(data),
+        24 => Kek::<Aes192>::try_from(kek)
+            .map_err(key_error)?
+            .wrap_vec(data),
+        32 => Kek::<Aes256>::try_from(kek)
+            .map_err(key_error)?
+            .wrap_vec(data),
+        _ => return Err(anyhow!("Invalid AES key length")),
+    };
+    wrapped.map_err(|_| anyhow!("AES-KW data must be a multiple of 8 bytes"))
+}
+
+/// unwrap key bytes with AES-KW (RFC 3394)
+pub fn unwrap_key(kek: &[u8], data: &[u8]) -> Result<Vec<u8>> {
+    let unwrapped = match kek.len() {
+        16 => Kek::<Aes128>::try_from(kek)
+            .map_err(key_error)?
+            .unwrap_vec(data),
+        24 => Kek::<Aes192>::try_from(kek)
+            .map_err(key_error)?
+            .unwrap_vec(data),
+        32 => Kek::<Aes256>::try_from(kek)
+            .map_err(key_error)?
+            .unwrap_vec(data),
+        _ => return Err(anyhow!("Invalid AES key length")),
+    };
+    unwrapped.map_err(|_| anyhow!("The operation failed for an operation-specific reason"))
+}
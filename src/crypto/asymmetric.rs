@@ -0,0 +1,556 @@
+use anyhow::{anyhow, Result};
+use ed25519_dalek::{Signer, Verifier};
+use p256::ecdsa::signature::hazmat::{PrehashSigner, PrehashVerifier};
+use p256::elliptic_curve::sec1::{EncodedPoint, FromEncodedPoint, ToEncodedPoint};
+use p256::pkcs8::{DecodePrivateKey, DecodePublicKey, EncodePrivateKey, EncodePublicKey};
+use rand_core::OsRng;
+use rquickjs::{ArrayBuffer, Ctx, FromJs, Value};
+use rsa::traits::{PrivateKeyParts, PublicKeyParts};
+use rsa::{BigUint, RsaPrivateKey, RsaPublicKey};
+use sha2::Digest;
+use std::collections::HashMap;
+
+/// SignParams is the algorithm parameters passed from SubtleCrypto sign/verify
+#[derive(Debug, Default)]
+pub struct SignParams {
+    pub name: String,
+    pub hash: String,
+    pub salt_length: usize,
+}
+
+impl<'js> FromJs<'js> for SignParams {
+    fn from_js(ctx: &Ctx<'js>, value: Value<'js>) -> rquickjs::Result<Self> {
+        if !value.is_object() {
+            return Err(ctx.throw(
+                rquickjs::String::from_str(ctx.clone(), "SignParams need from an object")?
+                    .into_value(),
+            ));
+        }
+        let params_object = value.as_object().unwrap();
+        let string_field = |key: &str| -> rquickjs::Result<String> {
+            let value: Value = params_object.get(key)?;
+            if value.is_string() {
+                return value.into_string().unwrap().to_string();
+            }
+            Ok("".to_string())
+        };
+        let salt_length_value: Value = params_object.get("saltLength")?;
+        let salt_length = salt_length_value.as_number().unwrap_or(0.0) as usize;
+        Ok(Self {
+            name: string_field("name")?,
+            hash: string_field("hash")?,
+            salt_length,
+        })
+    }
+}
+
+/// JwkParams holds the decoded base64url members of a JSON Web Key
+#[derive(Debug, Default)]
+pub struct JwkParams {
+    pub members: HashMap<String, Vec<u8>>,
+}
+
+impl JwkParams {
+    fn get(&self, name: &str) -> Result<&[u8]> {
+        self.members
+            .get(name)
+            .map(|value| value.as_slice())
+            .ok_or_else(|| anyhow!("JWK member '{}' is missing", name))
+    }
+
+    fn has(&self, name: &str) -> bool {
+        self.members.contains_key(name)
+    }
+}
+
+impl<'js> FromJs<'js> for JwkParams {
+    fn from_js(ctx: &Ctx<'js>, value: Value<'js>) -> rquickjs::Result<Self> {
+        if !value.is_object() {
+            return Err(ctx.throw(
+                rquickjs::String::from_str(ctx.clone(), "JwkParams need from an object")?
+                    .into_value(),
+            ));
+        }
+        let mut members = HashMap::new();
+        for item in value.as_object().unwrap().clone().into_iter() {
+            let (key, value) = item?;
+            if let Some(buffer) = ArrayBuffer::from_value(value) {
+                members.insert(
+                    key.to_string()?,
+                    buffer.as_bytes().unwrap_or_default().to_vec(),
+                );
+            }
+        }
+        Ok(Self { members })
+    }
+}
+
+/// AsymmetricKey is a parsed public or private key of the supported algorithms,
+/// keys travel between js and rust as pkcs8 (private) or spki (public) der bytes
+pub enum AsymmetricKey {
+    RsaPrivate(RsaPrivateKey),
+    RsaPublic(RsaPublicKey),
+    P256Private(p256::SecretKey),
+    P256Public(p256::PublicKey),
+    P384Private(p384::SecretKey),
+    P384Public(p384::PublicKey),
+    Ed25519Private(ed25519_dalek::SigningKey),
+    Ed25519Public(ed25519_dalek::VerifyingKey),
+    X25519Private(x25519_dalek::StaticSecret),
+    X25519Public(x25519_dalek::PublicKey),
+}
+
+// X25519 keys have fixed size der encodings, the pkcs8 and spki crates
+// have no support for them so the der prefixes are used directly
+const X25519_PKCS8_PREFIX: [u8; 16] = [
+    0x30, 0x2e, 0x02, 0x01, 0x00, 0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x6e, 0x04, 0x22, 0x04, 0x20,
+];
+const X25519_SPKI_PREFIX: [u8; 12] = [
+    0x30, 0x2a, 0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x6e, 0x03, 0x21, 0x00,
+];
+
+fn x25519_bytes(der: &[u8], prefix: &[u8]) -> Result<[u8; 32]> {
+    if der.len() != prefix.len() + 32 || !der.starts_with(prefix) {
+        return Err(anyhow!("Invalid X25519 key data"));
+    }
+    Ok(der[prefix.len()..].try_into().unwrap())
+}
+
+impl AsymmetricKey {
+    /// from_der parses pkcs8 (private) or spki (public) der bytes of the kind of key
+    pub fn from_der(kind: &str, private: bool, der: &[u8]) -> Result<Self> {
+        let key = match (kind, private) {
+            ("RSA", true) => Self::RsaPrivate(RsaPrivateKey::from_pkcs8_der(der)?),
+            ("RSA", false) => Self::RsaPublic(RsaPublicKey::from_public_key_der(der)?),
+            ("P-256", true) => Self::P256Private(p256::SecretKey::from_pkcs8_der(der)?),
+            ("P-256", false) => Self::P256Public(p256::PublicKey::from_public_key_der(der)?),
+            ("P-384", true) => Self::P384Private(p384::SecretKey::from_pkcs8_der(der)?),
+            ("P-384", false) => Self::P384Public(p384::PublicKey::from_public_key_der(der)?),
+            ("Ed25519", true) => {
+                Self::Ed25519Private(ed25519_dalek::SigningKey::from_pkcs8_der(der)?)
+            }
+            ("Ed25519", false) => {
+                Self::Ed25519Public(ed25519_dalek::VerifyingKey::from_public_key_der(der)?)
+            }
+            ("X25519", true) => Self::X25519Private(x25519_dalek::StaticSecret::from(
+                x25519_bytes(der, &X25519_PKCS8_PREFIX)?,
+            )),
+            ("X25519", false) => Self::X25519Public(x25519_dalek::PublicKey::from(x25519_bytes(
+                der,
+                &X25519_SPKI_PREFIX,
+            )?)),
+            _ => return Err(anyhow!("Unsupported key kind: {}", kind)),
+        };
+        Ok(key)
+    }
+
+    /// from_raw parses the raw public key format of elliptic curve keys
+    pub fn from_raw(kind: &str, raw: &[u8]) -> Result<Self> {
+        let key = match kind {
+            "P-256" => Self::P256Public(p256::PublicKey::from_sec1_bytes(raw)?),
+            "P-384" => Self::P384Public(p384::PublicKey::from_sec1_bytes(raw)?),
+            "Ed25519" => Self::Ed25519Public(ed25519_dalek::VerifyingKey::from_bytes(
+                raw.try_into()
+                    .map_err(|_| anyhow!("Ed25519 public key must be 32 bytes"))?,
+            )?),
+            "X25519" => Self::X25519Public(x25519_dalek::PublicKey::from(
+                <[u8; 32]>::try_from(raw)
+                    .map_err(|_| anyhow!("X25519 public key must be 32 bytes"))?,
+            )),
+            _ => return Err(anyhow!("Raw format is not supported for {}", kind)),
+        };
+        Ok(key)
+    }
+
+    /// from_jwk builds the key from decoded JWK members, it is private if 'd' exists
+    pub fn from_jwk(kind: &str, jwk: &JwkParams) -> Result<Self> {
+        let private = jwk.has("d");
+        let key = match kind {
+            "RSA" => {
+                let n = BigUint::from_bytes_be(jwk.get("n")?);
+                let e = BigUint::from_bytes_be(jwk.get("e")?);
+                if private {
+                    let d = BigUint::from_bytes_be(jwk.get("d")?);
+                    let primes = if jwk.has("p") && jwk.has("q") {
+                        vec![
+                            BigUint::from_bytes_be(jwk.get("p")?),
+                            BigUint::from_bytes_be(jwk.get("q")?),
+                        ]
+                    } else {
+                        vec![]
+                    };
+                    let mut key = RsaPrivateKey::from_components(n, e, d, primes)?;
+                    key.validate()?;
+                    key.precompute()?;
+                    Self::RsaPrivate(key)
+                } else {
+                    Self::RsaPublic(RsaPublicKey::new(n, e)?)
+                }
+            }
+            "P-256" => {
+                if private {
+                    Self::P256Private(p256::SecretKey::from_slice(jwk.get("d")?)?)
+                } else {
+                    let (x, y) = (jwk.get("x")?, jwk.get("y")?);
+                    if x.len() != 32 || y.len() != 32 {
+                        return Err(anyhow!("Invalid P-256 public key"));
+                    }
+                    let point = EncodedPoint::<p256::NistP256>::from_affine_coordinates(
+                        p256::FieldBytes::from_slice(x),
+                        p256::FieldBytes::from_slice(y),
+                        false,
+                    );
+                    Self::P256Public(
+                        Option::from(p256::PublicKey::from_encoded_point(&point))
+                            .ok_or_else(|| anyhow!("Invalid P-256 public key"))?,
+                    )
+                }
+            }
+            "P-384" => {
+                if private {
+                    Self::P384Private(p384::SecretKey::from_slice(jwk.get("d")?)?)
+                } else {
+                    let (x, y) = (jwk.get("x")?, jwk.get("y")?);
+                    if x.len() != 48 || y.len() != 48 {
+                        return Err(anyhow!("Invalid P-384 public key"));
+                    }
+                    let point = EncodedPoint::<p384::NistP384>::from_affine_coordinates(
+                        p384::FieldBytes::from_slice(x),
+                        p384::FieldBytes::from_slice(y),
+                        false,
+                    );
+                    Self::P384Public(
+                        Option::from(p384::PublicKey::from_encoded_point(&point))
+                            .ok_or_else(|| anyhow!("Invalid P-384 public key"))?,
+                    )
+                }
+            }
+            "Ed25519" => {
+                if private {
+                    Self::Ed25519Private(ed25519_dalek::SigningKey::from_bytes(
+                        jwk.get("d")?
+                            .try_into()
+                            .map_err(|_| anyhow!("Ed25519 private key must be 32 bytes"))?,
+                    ))
+                } else {
+                    Self::from_raw(kind, jwk.get("x")?)?
+                }
+            }
+            "X25519" => {
+                if private {
+                    Self::X25519Private(x25519_dalek::StaticSecret::from(
+                        <[u8; 32]>::try_from(jwk.get("d")?)
+                            .map_err(|_| anyhow!("X25519 private key must be 32 bytes"))?,
+                    ))
+                } else {
+                    Self::from_raw(kind, jwk.get("x")?)?
+                }
+            }
+            _ => return Err(anyhow!("Unsupported key kind: {}", kind)),
+        };
+        Ok(key)
+    }
+
+    /// generate creates a new private key of the kind
+    pub fn generate(kind: &str) -> Result<Self> {
+        let key = match kind {
+            "P-256" => Self::P256Private(p256::SecretKey::random(&mut OsRng)),
+            "P-384" => Self::P384Private(p384::SecretKey::random(&mut OsRng)),
+            "Ed25519" => Self::Ed25519Private(ed25519_dalek::SigningKey::generate(&mut OsRng)),
+            "X25519" => Self::X25519Private(x25519_dalek::StaticSecret::random_from_rng(OsRng)),
+            _ => return Err(anyhow!("generateKey is not supported for {}", kind)),
+        };
+        Ok(key)
+    }
+
+    pub fn is_private(&self) -> bool {
+        matches!(
+            self,
+            Self::RsaPrivate(_)
+                | Self::P256Private(_)
+                | Self::P384Private(_)
+                | Self::Ed25519Private(_)
+                | Self::X25519Private(_)
+        )
+    }
+
+    /// public_key returns the public part of a private key
+    pub fn public_key(&self) -> Self {
+        match self {
+            Self::RsaPrivate(key) => Self::RsaPublic(key.to_public_key()),
+            Self::P256Private(key) => Self::P256Public(key.public_key()),
+            Self::P384Private(key) => Self::P384Public(key.public_key()),
+            Self::Ed25519Private(key) => Self::Ed25519Public(key.verifying_key()),
+            Self::X25519Private(key) => Self::X25519Public(x25519_dalek::PublicKey::from(key)),
+            Self::RsaPublic(key) => Self::RsaPublic(key.clone()),
+            Self::P256Public(key) => Self::P256Public(*key),
+            Self::P384Public(key) => Self::P384Public(*key),
+            Self::Ed25519Public(key) => Self::Ed25519Public(*key),
+            Self::X25519Public(key) => Self::X25519Public(*key),
+        }
+    }
+
+    /// to_der exports pkcs8 der for private keys and spki der for public keys
+    pub fn to_der(&self) -> Result<Vec<u8>> {
+        let der = match self {
+            Self::RsaPrivate(key) => key.to_pkcs8_der()?.as_bytes().to_vec(),
+            Self::RsaPublic(key) => key.to_public_key_der()?.as_bytes().to_vec(),
+            Self::P256Private(key) => key.to_pkcs8_der()?.as_bytes().to_vec(),
+            Self::P256Public(key) => key.to_public_key_der()?.as_bytes().to_vec(),
+            Self::P384Private(key) => key.to_pkcs8_der()?.as_bytes().to_vec(),
+            Self::P384Public(key) => key.to_public_key_der()?.as_bytes().to_vec(),
+            // export pkcs8 v1 without the public key like browsers do
+            Self::Ed25519Private(key) => ed25519_dalek::pkcs8::KeypairBytes {
+                secret_key: key.to_bytes(),
+                public_key: None,
+            }
+            .to_pkcs8_der()?
+            .as_bytes()
+            .to_vec(),
+            Self::Ed25519Public(key) => key.to_public_key_der()?.as_bytes().to_vec(),
+            Self::X25519Private(key) => [&X25519_PKCS8_PREFIX[..], key.as_bytes()].concat(),
+            Self::X25519Public(key) => [&X25519_SPKI_PREFIX[..], key.as_bytes()].concat(),
+        };
+        Ok(der)
+    }
+
+    /// to_raw exports the raw format of elliptic curve public keys
+    pub fn to_raw(&self) -> Result<Vec<u8>> {
+        let raw = match self {
+            Self::P256Public(key) => key.to_encoded_point(false).as_bytes().to_vec(),
+            Self::P384Public(key) => key.to_encoded_point(false).as_bytes().to_vec(),
+            Self::Ed25519Public(key) => key.to_bytes().to_vec(),
+            Self::X25519Public(key) => key.as_bytes().to_vec(),
+            _ => return Err(anyhow!("Raw format is only supported for public EC keys")),
+        };
+        Ok(raw)
+    }
+
+    /// to_jwk exports the binary JWK members, js side encodes them as base64url
+    pub fn to_jwk(&self) -> Result<Vec<(&'static str, Vec<u8>)>> {
+        let mut members = match self.public_key() {
+            Self::RsaPublic(key) => {
+                vec![("n", key.n().to_bytes_be()), ("e", key.e().to_bytes_be())]
+            }
+            Self::P256Public(key) => {
+                let point = key.to_encoded_point(false);
+                vec![
+                    ("x", point.x().unwrap().to_vec()),
+                    ("y", point.y().unwrap().to_vec()),
+                ]
+            }
+            Self::P384Public(key) => {
+                let point = key.to_encoded_point(false);
+                vec![
+                    ("x", point.x().unwrap().to_vec()),
+                    ("y", point.y().unwrap().to_vec()),
+                ]
+            }
+            Self::Ed25519Public(key) => vec![("x", key.to_bytes().to_vec())],
+            Self::X25519Public(key) => vec![("x", key.as_bytes().to_vec())],
+            _ => unreachable!(),
+        };
+        match self {
+            Self::RsaPrivate(key) => {
+                let primes = key.primes();
+                if primes.len() != 2 {
+                    return Err(anyhow!(
+                        "JWK export of multi-prime RSA keys is not supported"
+                    ));
+                }
+                members.push(("d", key.d().to_bytes_be()));
+                members.push(("p", primes[0].to_bytes_be()));
+                members.push(("q", primes[1].to_bytes_be()));
+                if let (Some(dp), Some(dq)) = (key.dp(), key.dq()) {
+                    members.push(("dp", dp.to_bytes_be()));
+                    members.push(("dq", dq.to_bytes_be()));
+                }
+                if let Some(qi) = key.crt_coefficient() {
+                    members.push(("qi", qi.to_bytes_be()));
+                }
+            }
+            Self::P256Private(key) => members.push(("d", key.to_bytes().to_vec())),
+            Self::P384Private(key) => members.push(("d", key.to_bytes().to_vec())),
+            Self::Ed25519Private(key) => members.push(("d", key.to_bytes().to_vec())),
+            Self::X25519Private(key) => members.push(("d", key.as_bytes().to_vec())),
+            _ => {}
+        }
+        Ok(members)
+    }
+
+    /// rsa_info returns modulus length in bits and public exponent bytes of RSA keys
+    pub fn rsa_info(&self) -> Option<(usize, Vec<u8>)> {
+        match self.public_key() {
+            Self::RsaPublic(key) => Some((key.n().bits(), key.e().to_bytes_be())),
+            _ => None,
+        }
+    }
+
+    /// sign data with the private key
+    pub fn sign(&self, params: &SignParams, data: &[u8]) -> Result<Vec<u8>> {
+        let signature = match (params.name.as_str(), self) {
+            ("RSASSA-PKCS1-v1_5", Self::RsaPrivate(key)) => {
+                rsa_sign_pkcs1v15(key, &params.hash, data)?
+            }
+            ("RSA-PSS", Self::RsaPrivate(key)) => {
+                rsa_sign_pss(key, &params.hash, params.salt_length, data)?
+            }
+            ("ECDSA", Self::P256Private(key)) => {
+                let signing_key = p256::ecdsa::SigningKey::from(key);
+                let signature: p256::ecdsa::Signature =
+                    signing_key.sign_prehash(&digest(&params.hash, data)?)?;
+                signature.to_bytes().to_vec()
+            }
+            ("ECDSA", Self::P384Private(key)) => {
+                let signing_key = p384::ecdsa::SigningKey::from(key);
+                let signature: p384::ecdsa::Signature =
+                    signing_key.sign_prehash(&digest(&params.hash, data)?)?;
+                signature.to_bytes().to_vec()
+            }
+            ("Ed25519", Self::Ed25519Private(key)) => key.sign(data).to_bytes().to_vec(),
+            _ => {
+                return Err(anyhow!(
+                    "The key can not be used to sign with {}",
+                    params.name
+                ))
+            }
+        };
+        Ok(signature)
+    }
+
+    /// verify signature of data with the public key
+    pub fn verify(&self, params: &SignParams, signature: &[u8], data: &[u8]) -> Result<bool> {
+        let valid = match (params.name.as_str(), self) {
+            ("RSASSA-PKCS1-v1_5", Self::RsaPublic(key)) => {
+                rsa_verify_pkcs1v15(key, &params.hash, signature, data)?
+            }
+            ("RSA-PSS", Self::RsaPublic(key)) => {
+                rsa_verify_pss(key, &params.hash, params.salt_length, signature, data)?
+            }
+            ("ECDSA", Self::P256Public(key)) => {
+                let verifying_key = p256::ecdsa::VerifyingKey::from(key);
+                match p256::ecdsa::Signature::from_slice(signature) {
+                    Ok(signature) => verifying_key
+                        .verify_prehash(&digest(&params.hash, data)?, &signature)
+                        .is_ok(),
+                    Err(_) => false,
+                }
+            }
+            ("ECDSA", Self::P384Public(key)) => {
+                let verifying_key = p384::ecdsa::VerifyingKey::from(key);
+                match p384::ecdsa::Signature::from_slice(signature) {
+                    Ok(signature) => verifying_key
+                        .verify_prehash(&digest(&params.hash, data)?, &signature)
+                        .is_ok(),
+                    Err(_) => false,
+                }
+            }
+            ("Ed25519", Self::Ed25519Public(key)) => {
+                match ed25519_dalek::Signature::from_slice(signature) {
+                    Ok(signature) => key.verify(data, &signature).is_ok(),
+                    Err(_) => false,
+                }
+            }
+            _ => {
+                return Err(anyhow!(
+                    "The key can not be used to verify with {}",
+                    params.name
+                ))
+            }
+        };
+        Ok(valid)
+    }
+
+    /// derive_bits computes the ECDH shared secret with the public key of the other party
+    pub fn derive_bits(&self, public: &AsymmetricKey) -> Result<Vec<u8>> {
+        let secret = match (self, public) {
+            (Self::P256Private(key), Self::P256Public(public)) => {
+                p256::ecdh::diffie_hellman(key.to_nonzero_scalar(), public.as_affine())
+                    .raw_secret_bytes()
+                    .to_vec()
+            }
+            (Self::P384Private(key), Self::P384Public(public)) => {
+                p384::ecdh::diffie_hellman(key.to_nonzero_scalar(), public.as_affine())
+                    .raw_secret_bytes()
+                    .to_vec()
+            }
+            (Self::X25519Private(key), Self::X25519Public(public)) => {
+                let shared = key.diffie_hellman(public);
+                if !shared.was_contributory() {
+                    return Err(anyhow!("X25519 shared secret is all zero"));
+                }
+                shared.as_bytes().to_vec()
+            }
+            _ => return Err(anyhow!("The keys can not be used to derive bits")),
+        };
+        Ok(secret)
+    }
+}
+
+/// digest hashes data with SHA-1 or SHA-2 family named as in WebCrypto
+pub fn digest(hash: &str, data: &[u8]) -> Result<Vec<u8>> {
+    let output = match hash {
+        "SHA-1" => sha1::Sha1::digest(data).to_vec(),
+        "SHA-256" => sha2::Sha256::digest(data).to_vec(),
+        "SHA-384" => sha2::Sha384::digest(data).to_vec(),
+        "SHA-512" => sha2::Sha512::digest(data).to_vec(),
+        _ => return Err(anyhow!("Unrecognized hash algorithm: {}", hash)),
+    };
+    Ok(output)
+}
+
+fn rsa_sign_pkcs1v15(key: &RsaPrivateKey, hash: &str, data: &[u8]) -> Result<Vec<u8>> {
+    use rsa::signature::{SignatureEncoding, Signer};
+    let signature = with_hash!(hash, H => rsa::pkcs1v15::SigningKey::<H>::new(key.clone())
+        .try_sign(data)?
+        .to_vec());
+    Ok(signature)
+}
+
+fn rsa_verify_pkcs1v15(
+    key: &RsaPublicKey,
+    hash: &str,
+    signature: &[u8],
+    data: &[u8],
+) -> Result<bool> {
+    use rsa::signature::Verifier;
+    let signature = match rsa::pkcs1v15::Signature::try_from(signature) {
+        Ok(signature) => signature,
+        Err(_) => return Ok(false),
+    };
+    let valid = with_hash!(hash, H => rsa::pkcs1v15::VerifyingKey::<H>::new(key.clone())
+        .verify(data, &signature)
+        .is_ok());
+    Ok(valid)
+}
+
+fn rsa_sign_pss(
+    key: &RsaPrivateKey,
+    hash: &str,
+    salt_length: usize,
+    data: &[u8],
+) -> Result<Vec<u8>> {
+    use rsa::signature::{RandomizedSigner, SignatureEncoding};
+    let signature = with_hash!(hash, H => rsa::pss::BlindedSigningKey::<H>::new_with_salt_len(key.clone(), salt_length)
+        .try_sign_with_rng(&mut OsRng, data)?
+        .to_vec());
+    Ok(signature)
+}
+
+fn rsa_verify_pss(
+    key: &RsaPublicKey,
+    hash: &str,
+    salt_length: usize,
+    signature: &[u8],
+    data: &[u8],
+) -> Result<bool> {
+    use rsa::signature::Verifier;
+    let signature = match rsa::pss::Signature::try_from(signature) {
+        Ok(signature) => signature,
+        Err(_) => return Ok(false),
+    };
+    let valid = with_hash!(hash, H => rsa::pss::VerifyingKey::<H>::new_with_salt_len(key.clone(), salt_length)
+        .verify(data, &signature)
+        .is_ok());
+    Ok(valid)
+}
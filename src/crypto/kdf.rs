@@ -0,0 +1,53 @@
+use anyhow::{anyhow, Result};
+use hkdf::Hkdf;
+
+// The limits bound the CPU time and memory of a PBKDF2 call, the iterations cover the
+// recommended counts of SHA-1 and SHA-256 and the length covers a few keys.
+const PBKDF2_MAX_ITERATIONS: u32 = 1 << 20;
+const PBKDF2_MAX_LENGTH: usize = 4096 * 8;
+
+/// pbkdf2 derives length bits from password with PBKDF2-HMAC
+pub fn pbkdf2(
+    hash: &str,
+    password: &[u8],
+    salt: &[u8],
+    iterations: u32,
+    length: usize,
+) -> Result<Vec<u8>> {
+    if iterations == 0 {
+        return Err(anyhow!("PBKDF2 iterations must be greater than 0"));
+    }
+    if iterations > PBKDF2_MAX_ITERATIONS {
+        return Err(anyhow!(
+            "PBKDF2 iterations {} exceeds {}",
+            iterations,
+            PBKDF2_MAX_ITERATIONS
+        ));
+    }
+    if length > PBKDF2_MAX_LENGTH {
+        return Err(anyhow!(
+            "PBKDF2 length {} exceeds {} bits",
+            length,
+            PBKDF2_MAX_LENGTH
+        ));
+    }
+    let mut output = vec![0u8; bits_to_bytes(length)?];
+    with_hash!(hash, H => pbkdf2::pbkdf2_hmac::<H>(password, salt, iterations, &mut output));
+    Ok(output)
+}
+
+/// hkdf derives length bits from key material with HKDF extract and expand
+pub fn hkdf(hash: &str, key: &[u8], salt: &[u8], info: &[u8], length: usize) -> Result<Vec<u8>> {
+    let mut output = vec![0u8; bits_to_bytes(length)?];
+    with_hash!(hash, H => Hkdf::<H>::new(Some(salt), key)
+        .expand(info, &mut output)
+        .map_err(|_| anyhow!("HKDF length is too large"))?);
+    Ok(output)
+}
+
+fn bits_to_bytes(length: usize) -> Result<usize> {
+    if length == 0 || length % 8 != 0 {
+        return Err(anyhow!("length must be a multiple of 8 and greater than 0"));
+    }
+    Ok(length / 8)
+}
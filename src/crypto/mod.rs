@@ -0,0 +1,300 @@
+use crate::hostcall::{arg_to_bytes, arg_to_string, check_args, to_js_error};
+use anyhow::anyhow;
+use asymmetric::{AsymmetricKey, JwkParams, SignParams};
+use rquickjs::{prelude::Rest, ArrayBuffer, Ctx, FromJs, Function, Object, Value};
+
+// with_hash expands the body once for each supported hash, binding the hash type to $hash,
+// it is defined before the submodules so they can use it
+macro_rules! with_hash {
+    ($name:expr, $hash:ident => $body:expr) => {
+        match $name {
+            "SHA-1" => {
+                type $hash = sha1::Sha1;
+                $body
+            }
+            "SHA-256" => {
+                type $hash = sha2::Sha256;
+                $body
+            }
+            "SHA-384" => {
+                type $hash = sha2::Sha384;
+                $body
+            }
+            "SHA-512" => {
+                type $hash = sha2::Sha512;
+                $body
+            }
+            _ => return Err(anyhow::anyhow!("Unrecognized hash algorithm: {}", $name)),
+        }
+    };
+}
+
+mod aes;
+mod asymmetric;
+mod kdf;
+
+/// build native crypto object that used by the WebCrypto APIs of the crypto global
+pub fn build<'js>(ctx: Ctx<'js>) -> rquickjs::Result<Object> {
+    let crypto = Object::new(ctx.clone())?;
+
+    // random_bytes returns an ArrayBuffer filled with random bytes from the host
+    let random_bytes = Function::new(
+        ctx.clone(),
+        |cx: Ctx<'js>, args: Rest<Value<'js>>| -> Result<Value<'js>, rquickjs::Error> {
+            check_args(&args, 1)?;
+            let length = args.first().unwrap().as_number().unwrap_or(0.0) as usize;
+            if length > 65536 {
+                return Err(to_js_error(
+                    cx.clone(),
+                    anyhow!("random_bytes length {} exceeds 65536", length),
+                ));
+            }
+            let mut buffer = vec![0u8; length];
+            getrandom::getrandom(&mut buffer)
+                .map_err(|e| to_js_error(cx.clone(), anyhow!("{}", e)))?;
+            Ok::<_, rquickjs::Error>(ArrayBuffer::new(cx.clone(), buffer)?.into_value())
+        },
+    )?;
+
+    // aes_encrypt(params, key, data) encrypts data with AES-GCM, AES-CBC or AES-CTR
+    let aes_encrypt = Function::new(
+        ctx.clone(),
+        |cx: Ctx<'js>, args: Rest<Value<'js>>| -> Result<Value<'js>, rquickjs::Error> {
+            check_args(&args, 3)?;
+            let params = aes::AesParams::from_js(&cx, args[0].clone())?;
+            let key = arg_to_bytes(&args[1]).map_err(|e| to_js_error(cx.clone(), e))?;
+            let data = arg_to_bytes(&args[2]).map_err(|e| to_js_error(cx.clone(), e))?;
+            let output =
+                aes::encrypt(&params, &key, &data).map_err(|e| to_js_error(cx.clone(), e))?;
+            Ok::<_, rquickjs::Error>(ArrayBuffer::new(cx.clone(), output)?.into_value())
+        },
+    )?;
+
+    // aes_decrypt(params, key, data) decrypts data with AES-GCM, AES-CBC or AES-CTR
+    let aes_decrypt = Function::new(
+        ctx.clone(),
+        |cx: Ctx<'js>, args: Rest<Value<'js>>| -> Result<Value<'js>, rquickjs::Error> {
+            check_args(&args, 3)?;
+            let params = aes::AesParams::from_js(&cx, args[0].clone())?;
+            let key = arg_to_bytes(&args[1]).map_err(|e| to_js_error(cx.clone(), e))?;
+            let data = arg_to_bytes(&args[2]).map_err(|e| to_js_error(cx.clone(), e))?;
+            let output =
+                aes::decrypt(&params, &key, &data).map_err(|e| to_js_error(cx.clone(), e))?;
+            Ok::<_, rquickjs::Error>(ArrayBuffer::new(cx.clone(), output)?.into_value())
+        },
+    )?;
+
+    // aes_wrap_key(kek, data) wraps key bytes with AES-KW
+    let aes_wrap_key = Function::new(
+        ctx.clone(),
+        |cx: Ctx<'js>, args: Rest<Value<'js>>| -> Result<Value<'js>, rquickjs::Error> {
+            check_args(&args, 2)?;
+            let kek = arg_to_bytes(&args[0]).map_err(|e| to_js_error(cx.clone(), e))?;
+            let data = arg_to_bytes(&args[1]).map_err(|e| to_js_error(cx.clone(), e))?;
+            let output = aes::wrap_key(&kek, &data).map_err(|e| to_js_error(cx.clone(), e))?;
+            Ok::<_, rquickjs::Error>(ArrayBuffer::new(cx.clone(), output)?.into_value())
+        },
+    )?;
+
+    // aes_unwrap_key(kek, data) unwraps key bytes with AES-KW
+    let aes_unwrap_key = Function::new(
+        ctx.clone(),
+        |cx: Ctx<'js>, args: Rest<Value<'js>>| -> Result<Value<'js>, rquickjs::Error> {
+            check_args(&args, 2)?;
+            let kek = arg_to_bytes(&args[0]).map_err(|e| to_js_error(cx.clone(), e))?;
+            let data = arg_to_bytes(&args[1]).map_err(|e| to_js_error(cx.clone(), e))?;
+            let output = aes::unwrap_key(&kek, &data).map_err(|e| to_js_error(cx.clone(), e))?;
+            Ok::<_, rquickjs::Error>(ArrayBuffer::new(cx.clone(), output)?.into_value())
+        },
+    )?;
+
+    // key_import(kind, format, data) parses spki, pkcs8, raw or jwk key data,
+    // returns {private, der} with der as pkcs8 for private keys and spki for public keys
+    let key_import = Function::new(
+        ctx.clone(),
+        |cx: Ctx<'js>, args: Rest<Value<'js>>| -> Result<Value<'js>, rquickjs::Error> {
+            check_args(&args, 3)?;
+            let kind = arg_to_string(&args[0]).map_err(|e| to_js_error(cx.clone(), e))?;
+            let format = arg_to_string(&args[1]).map_err(|e| to_js_error(cx.clone(), e))?;
+            let key = match format.as_str() {
+                "jwk" => {
+                    let jwk = JwkParams::from_js(&cx, args[2].clone())?;
+                    AsymmetricKey::from_jwk(&kind, &jwk)
+                }
+                _ => {
+                    let data = arg_to_bytes(&args[2]).map_err(|e| to_js_error(cx.clone(), e))?;
+                    match format.as_str() {
+                        "spki" => AsymmetricKey::from_der(&kind, false, &data),
+                        "pkcs8" => AsymmetricKey::from_der(&kind, true, &data),
+                        "raw" => AsymmetricKey::from_raw(&kind, &data),
+                        _ => Err(anyhow!("Unsupported key format: {}", format)),
+                    }
+                }
+            }
+            .map_err(|e| to_js_error(cx.clone(), e))?;
+            key_to_js(&cx, &key)
+        },
+    )?;
+
+    // key_export(kind, private, format, der) exports the key as spki, pkcs8, raw or jwk members
+    let key_export = Function::new(
+        ctx.clone(),
+        |cx: Ctx<'js>, args: Rest<Value<'js>>| -> Result<Value<'js>, rquickjs::Error> {
+            check_args(&args, 4)?;
+            let kind = arg_to_string(&args[0]).map_err(|e| to_js_error(cx.clone(), e))?;
+            let private = args[1].as_bool().unwrap_or(false);
+            let format = arg_to_string(&args[2]).map_err(|e| to_js_error(cx.clone(), e))?;
+            let der = arg_to_bytes(&args[3]).map_err(|e| to_js_error(cx.clone(), e))?;
+            let key = AsymmetricKey::from_der(&kind, private, &der)
+                .map_err(|e| to_js_error(cx.clone(), e))?;
+            if format == "jwk" {
+                let members = key.to_jwk().map_err(|e| to_js_error(cx.clone(), e))?;
+                let jwk_object = Object::new(cx.clone())?;
+                for (name, value) in members {
+                    jwk_object.set(name, ArrayBuffer::new(cx.clone(), value)?)?;
+                }
+                return Ok::<_, rquickjs::Error>(Value::from_object(jwk_object));
+            }
+            let output = match (format.as_str(), private) {
+                ("spki", false) | ("pkcs8", true) => Ok(der),
+                ("raw", false) => key.to_raw(),
+                _ => Err(anyhow!("Unsupported key format: {}", format)),
+            }
+            .map_err(|e| to_js_error(cx.clone(), e))?;
+            Ok::<_, rquickjs::Error>(ArrayBuffer::new(cx.clone(), output)?.into_value())
+        },
+    )?;
+
+    // key_generate(kind) creates a key pair, returns {private_key, public_key} as der
+    let key_generate = Function::new(
+        ctx.clone(),
+        |cx: Ctx<'js>, args: Rest<Value<'js>>| -> Result<Value<'js>, rquickjs::Error> {
+            check_args(&args, 1)?;
+            let kind = arg_to_string(&args[0]).map_err(|e| to_js_error(cx.clone(), e))?;
+            let key = AsymmetricKey::generate(&kind).map_err(|e| to_js_error(cx.clone(), e))?;
+            let private_der = key.to_der().map_err(|e| to_js_error(cx.clone(), e))?;
+            let public_der = key
+                .public_key()
+                .to_der()
+                .map_err(|e| to_js_error(cx.clone(), e))?;
+            let pair_object = Object::new(cx.clone())?;
+            pair_object.set("private_key", ArrayBuffer::new(cx.clone(), private_der)?)?;
+            pair_object.set("public_key", ArrayBuffer::new(cx.clone(), public_der)?)?;
+            Ok::<_, rquickjs::Error>(Value::from_object(pair_object))
+        },
+    )?;
+
+    // sign(params, kind, der, data) signs data with a pkcs8 private key
+    let sign = Function::new(
+        ctx.clone(),
+        |cx: Ctx<'js>, args: Rest<Value<'js>>| -> Result<Value<'js>, rquickjs::Error> {
+            check_args(&args, 4)?;
+            let params = SignParams::from_js(&cx, args[0].clone())?;
+            let kind = arg_to_string(&args[1]).map_err(|e| to_js_error(cx.clone(), e))?;
+            let der = arg_to_bytes(&args[2]).map_err(|e| to_js_error(cx.clone(), e))?;
+            let data = arg_to_bytes(&args[3]).map_err(|e| to_js_error(cx.clone(), e))?;
+            let signature = AsymmetricKey::from_der(&kind, true, &der)
+                .and_then(|key| key.sign(&params, &data))
+                .map_err(|e| to_js_error(cx.clone(), e))?;
+            Ok::<_, rquickjs::Error>(ArrayBuffer::new(cx.clone(), signature)?.into_value())
+        },
+    )?;
+
+    // verify(params, kind, der, signature, data) verifies signature with a spki public key
+    let verify = Function::new(
+        ctx.clone(),
+        |cx: Ctx<'js>, args: Rest<Value<'js>>| -> Result<Value<'js>, rquickjs::Error> {
+            check_args(&args, 5)?;
+            let params = SignParams::from_js(&cx, args[0].clone())?;
+            let kind = arg_to_string(&args[1]).map_err(|e| to_js_error(cx.clone(), e))?;
+            let der = arg_to_bytes(&args[2]).map_err(|e| to_js_error(cx.clone(), e))?;
+            let signature = arg_to_bytes(&args[3]).map_err(|e| to_js_error(cx.clone(), e))?;
+            let data = arg_to_bytes(&args[4]).map_err(|e| to_js_error(cx.clone(), e))?;
+            let valid = AsymmetricKey::from_der(&kind, false, &der)
+                .and_then(|key| key.verify(&params, &signature, &data))
+                .map_err(|e| to_js_error(cx.clone(), e))?;
+            Ok::<_, rquickjs::Error>(Value::new_bool(cx.clone(), valid))
+        },
+    )?;
+
+    // pbkdf2(hash, password, salt, iterations, length) derives length bits with PBKDF2
+    let pbkdf2 = Function::new(
+        ctx.clone(),
+        |cx: Ctx<'js>, args: Rest<Value<'js>>| -> Result<Value<'js>, rquickjs::Error> {
+            check_args(&args, 5)?;
+            let hash = arg_to_string(&args[0]).map_err(|e| to_js_error(cx.clone(), e))?;
+            let password = arg_to_bytes(&args[1]).map_err(|e| to_js_error(cx.clone(), e))?;
+            let salt = arg_to_bytes(&args[2]).map_err(|e| to_js_error(cx.clone(), e))?;
+            let iterations = args[3].as_number().unwrap_or(0.0) as u32;
+            let length = args[4].as_number().unwrap_or(0.0) as usize;
+            let output = kdf::pbkdf2(&hash, &password, &salt, iterations, length)
+                .map_err(|e| to_js_error(cx.clone(), e))?;
+            Ok::<_, rquickjs::Error>(ArrayBuffer::new(cx.clone(), output)?.into_value())
+        },
+    )?;
+
+    // hkdf(hash, key, salt, info, length) derives length bits with HKDF
+    let hkdf = Function::new(
+        ctx.clone(),
+        |cx: Ctx<'js>, args: Rest<Value<'js>>| -> Result<Value<'js>, rquickjs::Error> {
+            check_args(&args, 5)?;
+            let hash = arg_to_string(&args[0]).map_err(|e| to_js_error(cx.clone(), e))?;
+            let key = arg_to_bytes(&args[1]).map_err(|e| to_js_error(cx.clone(), e))?;
+            let salt = arg_to_bytes(&args[2]).map_err(|e| to_js_error(cx.clone(), e))?;
+            let info = arg_to_bytes(&args[3]).map_err(|e| to_js_error(cx.clone(), e))?;
+            let length = args[4].as_number().unwrap_or(0.0) as usize;
+            let output = kdf::hkdf(&hash, &key, &salt, &info, length)
+                .map_err(|e| to_js_error(cx.clone(), e))?;
+            Ok::<_, rquickjs::Error>(ArrayBuffer::new(cx.clone(), output)?.into_value())
+        },
+    )?;
+
+    // ecdh(kind, private_der, public_der) computes the ECDH shared secret
+    let ecdh = Function::new(
+        ctx.clone(),
+        |cx: Ctx<'js>, args: Rest<Value<'js>>| -> Result<Value<'js>, rquickjs::Error> {
+            check_args(&args, 3)?;
+            let kind = arg_to_string(&args[0]).map_err(|e| to_js_error(cx.clone(), e))?;
+            let private_der = arg_to_bytes(&args[1]).map_err(|e| to_js_error(cx.clone(), e))?;
+            let public_der = arg_to_bytes(&args[2]).map_err(|e| to_js_error(cx.clone(), e))?;
+            let secret = AsymmetricKey::from_der(&kind, true, &private_der)
+                .and_then(|key| {
+                    let public = AsymmetricKey::from_der(&kind, false, &public_der)?;
+                    key.derive_bits(&public)
+                })
+                .map_err(|e| to_js_error(cx.clone(), e))?;
+            Ok::<_, rquickjs::Error>(ArrayBuffer::new(cx.clone(), secret)?.into_value())
+        },
+    )?;
+
+    crypto.set("random_bytes", random_bytes)?;
+    crypto.set("aes_encrypt", aes_encrypt)?;
+    crypto.set("aes_decrypt", aes_decrypt)?;
+    crypto.set("aes_wrap_key", aes_wrap_key)?;
+    crypto.set("aes_unwrap_key", aes_unwrap_key)?;
+    crypto.set("key_import", key_import)?;
+    crypto.set("key_export", key_export)?;
+    crypto.set("key_generate", key_generate)?;
+    crypto.set("sign", sign)?;
+    crypto.set("verify", verify)?;
+    crypto.set("pbkdf2", pbkdf2)?;
+    crypto.set("hkdf", hkdf)?;
+    crypto.set("ecdh", ecdh)?;
+    Ok(crypto)
+}
+
+// key_to_js converts imported key to {private, der, modulus_length, public_exponent}
+fn key_to_js<'js>(cx: &Ctx<'js>, key: &AsymmetricKey) -> rquickjs::Result<Value<'js>> {
+    let der = key.to_der().map_err(|e| to_js_error(cx.clone(), e))?;
+    let key_object = Object::new(cx.clone())?;
+    key_object.set("private", key.is_private())?;
+    key_object.set("der", ArrayBuffer::new(cx.clone(), der)?)?;
+    if let Some((modulus_length, public_exponent)) = key.rsa_info() {
+        key_object.set("modulus_length", modulus_length as u32)?;
+        key_object.set(
+            "public_exponent",
+            ArrayBuffer::new(cx.clone(), public_exponent)?,
+        )?;
+    }
+    Ok(Value::from_object(key_object))
+}
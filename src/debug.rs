@@ -0,0 +1,182 @@
+use hmac::{Hmac, Mac};
+use http::{HeaderName, HeaderValue};
+use land_sdk::http::Response;
+use serde_json::{json, Value as JsonValue};
+use sha2::Sha256;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+// DEBUG_ENV enables the debug headers for all requests when it is "on", it should not be set in production
+const DEBUG_ENV: &str = "LAND_DEBUG";
+
+// DEBUG_SECRET_ENV is the key of the signed debug request header
+const DEBUG_SECRET_ENV: &str = "LAND_DEBUG_SECRET";
+
+/// DEBUG_HEADER enables the debug headers of a request, it is "{unix seconds}.{signature}",
+/// the signature is the hex HMAC-SHA256 of "{unix seconds}\n{method}\n{path}" with LAND_DEBUG_SECRET,
+/// so a captured header only enables the debug headers of the same method and path
+pub const DEBUG_HEADER: &str = "x-land-debug";
+
+// SIGNATURE_TTL is how long a signed debug header is valid, it can not be replayed after it
+const SIGNATURE_TTL: u64 = 300;
+
+// MAX_HEADER_SIZE limits the size of a debug header, the records that do not fit are dropped
+const MAX_HEADER_SIZE: usize = 8 * 1024;
+
+// Debug is the captured output of the running request
+#[derive(Default)]
+struct Debug {
+    logs: Vec<JsonValue>,
+    timings: Vec<JsonValue>,
+    error: Option<String>,
+}
+
+// DEBUG is None if the debug mode is not enabled for the running request
+static DEBUG: Mutex<Option<Debug>> = Mutex::new(None);
+
+// verify checks the signed debug header value against the method and the path of the request
+fn verify(value: &str, method: &str, path: &str) -> bool {
+    let secret = match crate::env::var(DEBUG_SECRET_ENV).filter(|secret| !secret.is_empty()) {
+        Some(secret) => secret,
+        None => return false,
+    };
+    let (timestamp, signature) = match value.trim().split_once('.') {
+        Some(parts) => parts,
+        None => return false,
+    };
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    match timestamp.parse::<u64>() {
+        Ok(timestamp) if now.abs_diff(timestamp) <= SIGNATURE_TTL => {}
+        _ => return false,
+    }
+    let signature = match crate::bindings::decode_hex(signature) {
+        Ok(signature) => signature,
+        Err(_) => return false,
+    };
+    let mut mac = match Hmac::<Sha256>::new_from_slice(secret.as_bytes()) {
+        Ok(mac) => mac,
+        Err(_) => return false,
+    };
+    mac.update(format!("{}\n{}\n{}", timestamp, method, path).as_bytes());
+    mac.verify_slice(&signature).is_ok()
+}
+
+/// begin_request enables the debug mode if the env enables it or the request has a valid signed debug header
+pub fn begin_request(header: Option<&str>, method: &str, path: &str) {
+    let enabled = crate::env::var(DEBUG_ENV).is_some_and(|value| value.eq_ignore_ascii_case("on"))
+        || header.is_some_and(|header| verify(header, method, path));
+    *DEBUG.lock().unwrap() = if enabled {
+        Some(Debug::default())
+    } else {
+        None
+    };
+}
+
+/// enabled returns true if the debug headers of the running request are enabled
+pub fn enabled() -> bool {
+    DEBUG.lock().unwrap().is_some()
+}
+
+fn update(f: impl FnOnce(&mut Debug)) {
+    if let Some(debug) = DEBUG.lock().unwrap().as_mut() {
+        f(debug);
+    }
+}
+
+/// record_log captures a console record, the message is already redacted
+pub fn record_log(level: &str, message: &str) {
+    update(|debug| {
+        debug
+            .logs
+            .push(json!({ "level": level, "message": message }))
+    });
+}
+
+/// record_timing captures the duration of a hostcall, such as a fetch or a bindings call
+pub fn record_timing(name: &str, duration: Duration) {
+    update(|debug| {
+        let ms = (duration.as_secs_f64() * 1000.0 * 1000.0).round() / 1000.0;
+        debug.timings.push(json!({ "name": name, "ms": ms }))
+    });
+}
+
+/// record_error captures the error of the request, such as the exception stack of the handler
+pub fn record_error(message: &str) {
+    update(|debug| debug.error = Some(crate::env::redact(message).into_owned()));
+}
+
+// ascii escapes the non-ASCII characters of the JSON text, the header values must be visible ASCII
+fn ascii(json: &str) -> String {
+    let mut output = String::with_capacity(json.len());
+    for c in json.chars() {
+        if c.is_ascii() {
+            output.push(c);
+            continue;
+        }
+        let mut units = [0; 2];
+        for unit in c.encode_utf16(&mut units) {
+            output.push_str(&format!("\\u{:04x}", unit));
+        }
+    }
+    output
+}
+
+// fit returns the JSON array of the items that fit in a header, and a note of the dropped items
+fn fit(items: Vec<JsonValue>) -> String {
+    let total = items.len();
+    let mut values: Vec<String> = Vec::new();
+    let mut size = 2;
+    for item in items {
+        let value = ascii(&item.to_string());
+        // the reserved bytes are for the note of the dropped items
+        if size + value.len() + 1 > MAX_HEADER_SIZE - 64 {
+            break;
+        }
+        size += value.len() + 1;
+        values.push(value);
+    }
+    if values.len() < total {
+        let note = json!({ "dropped": total - values.len() });
+        values.push(note.to_string());
+    }
+    format!("[{}]", values.join(","))
+}
+
+fn insert(resp: &mut Response, name: &'static str, value: String) {
+    if let Ok(value) = HeaderValue::from_str(&value) {
+        resp.headers_mut()
+            .insert(HeaderName::from_static(name), value);
+    }
+}
+
+/// end_request adds the x-land-debug-* headers of the captured output to the response
+pub fn end_request(resp: &mut Response) {
+    let debug = match DEBUG.lock().unwrap().take() {
+        Some(debug) => debug,
+        None => return,
+    };
+    insert(resp, "x-land-debug-logs", fit(debug.logs));
+    insert(resp, "x-land-debug-timings", fit(debug.timings));
+    if let Some(memory) = crate::metrics::snapshot() {
+        insert(resp, "x-land-debug-memory", ascii(&memory.to_string()));
+    }
+    if let Some(mut error) = debug.error {
+        // the long stacks are cut to fit in the header
+        loop {
+            let value = ascii(&JsonValue::String(error.clone()).to_string());
+            if value.len() <= MAX_HEADER_SIZE {
+                insert(resp, "x-land-debug-error", value);
+                break;
+            }
+            let keep = error.chars().count() * MAX_HEADER_SIZE / value.len();
+            error = error
+                .chars()
+                .take(keep.saturating_sub(3))
+                .collect::<String>()
+                + "...";
+        }
+    }
+}
@@ -0,0 +1,171 @@
+use crate::hostcall::{arg_to_bytes, arg_to_string, check_args, to_js_error};
+use anyhow::{anyhow, Result};
+use encoding_rs::{CoderResult, Decoder, DecoderResult, Encoding};
+use rquickjs::{class::Trace, prelude::Rest, ArrayBuffer, Class, Ctx, Function, Object, Value};
+
+/// encoding_for_label returns the WHATWG encoding for label, the replacement encoding is not allowed in TextDecoder
+pub fn encoding_for_label(label: &str) -> Option<&'static Encoding> {
+    Encoding::for_label_no_replacement(label.trim().as_bytes())
+}
+
+/// encoding_name returns the lowercase name used by TextDecoder.encoding
+pub fn encoding_name(encoding: &'static Encoding) -> String {
+    encoding.name().to_ascii_lowercase()
+}
+
+/// StreamDecoder is the decoder state of a TextDecoder, it keeps the incomplete sequence of a
+/// decode call with {stream: true} and is freed with its TextDecoder
+#[derive(Trace)]
+#[rquickjs::class]
+pub struct StreamDecoder {
+    #[qjs(skip_trace)]
+    encoding: &'static Encoding,
+    #[qjs(skip_trace)]
+    fatal: bool,
+    #[qjs(skip_trace)]
+    ignore_bom: bool,
+    // decoder is the decoder of the running stream, None if the last call was not streaming
+    #[qjs(skip_trace)]
+    decoder: Option<Decoder>,
+}
+
+impl StreamDecoder {
+    // decode_bytes decodes bytes, the decoder is kept if stream is true
+    fn decode_bytes(&mut self, bytes: &[u8], stream: bool) -> Result<String> {
+        // the decoder is dropped on error, the next call starts a new stream
+        let mut decoder = match self.decoder.take() {
+            Some(decoder) => decoder,
+            None if self.ignore_bom => self.encoding.new_decoder_without_bom_handling(),
+            None => self.encoding.new_decoder_with_bom_removal(),
+        };
+        let last = !stream;
+        let capacity = decoder
+            .max_utf8_buffer_length(bytes.len())
+            .ok_or_else(|| anyhow!("Decoded text is too large"))?;
+        let mut text = String::with_capacity(capacity);
+        if self.fatal {
+            let (result, _read) =
+                decoder.decode_to_string_without_replacement(bytes, &mut text, last);
+            match result {
+                DecoderResult::InputEmpty => {}
+                DecoderResult::Malformed(_, _) => {
+                    return Err(anyhow!(
+                        "The encoded data was not valid for encoding {}",
+                        encoding_name(decoder.encoding())
+                    ))
+                }
+                DecoderResult::OutputFull => return Err(anyhow!("Decoded text is too large")),
+            }
+        } else {
+            let (result, _read, _replaced) = decoder.decode_to_string(bytes, &mut text, last);
+            if result == CoderResult::OutputFull {
+                return Err(anyhow!("Decoded text is too large"));
+            }
+        }
+        if stream {
+            self.decoder = Some(decoder);
+        }
+        Ok(text)
+    }
+}
+
+#[rquickjs::methods]
+impl<'js> StreamDecoder {
+    /// new StreamDecoder(label, fatal, ignoreBOM) creates the decoder state of a TextDecoder
+    #[qjs(constructor)]
+    pub fn new(
+        ctx: Ctx<'js>,
+        label: String,
+        fatal: bool,
+        ignore_bom: bool,
+    ) -> rquickjs::Result<Self> {
+        let encoding = encoding_for_label(&label)
+            .ok_or_else(|| anyhow!("The encoding label provided ('{}') is invalid", label))
+            .map_err(|e| to_js_error(ctx, e))?;
+        Ok(Self {
+            encoding,
+            fatal,
+            ignore_bom,
+            decoder: None,
+        })
+    }
+
+    /// decode(bytes, stream) returns the text of bytes, the incomplete sequence is kept if stream is true
+    pub fn decode(
+        &mut self,
+        ctx: Ctx<'js>,
+        bytes: Value<'js>,
+        stream: bool,
+    ) -> rquickjs::Result<String> {
+        let bytes = arg_to_bytes(&bytes).map_err(|e| to_js_error(ctx.clone(), e))?;
+        self.decode_bytes(&bytes, stream)
+            .map_err(|e| to_js_error(ctx, e))
+    }
+}
+
+/// encode_into encodes text as UTF-8 up to available bytes,
+/// returns the bytes and the UTF-16 code units read as TextEncoder.encodeInto does
+pub fn encode_into(text: &str, available: usize) -> (Vec<u8>, usize) {
+    let mut written = 0;
+    let mut read = 0;
+    for c in text.chars() {
+        if written + c.len_utf8() > available {
+            break;
+        }
+        written += c.len_utf8();
+        read += c.len_utf16();
+    }
+    (text.as_bytes()[..written].to_vec(), read)
+}
+
+/// build native encoding object that used by TextEncoder and TextDecoder
+pub fn build<'js>(ctx: Ctx<'js>) -> rquickjs::Result<Object> {
+    let encoding = Object::new(ctx.clone())?;
+
+    // encoding_name(label) returns the encoding name for label, or null if label is invalid
+    let encoding_name_callback = Function::new(
+        ctx.clone(),
+        |cx: Ctx<'js>, args: Rest<Value<'js>>| -> Result<Value<'js>, rquickjs::Error> {
+            check_args(&args, 1)?;
+            let label = arg_to_string(&args[0]).map_err(|e| to_js_error(cx.clone(), e))?;
+            match encoding_for_label(&label) {
+                Some(encoding) => {
+                    let name = rquickjs::String::from_str(cx.clone(), &encoding_name(encoding))?;
+                    Ok::<_, rquickjs::Error>(Value::from_string(name))
+                }
+                None => Ok(Value::new_null(cx.clone())),
+            }
+        },
+    )?;
+
+    // encode(text) returns the UTF-8 bytes of text
+    let encode_callback = Function::new(
+        ctx.clone(),
+        |cx: Ctx<'js>, args: Rest<Value<'js>>| -> Result<Value<'js>, rquickjs::Error> {
+            check_args(&args, 1)?;
+            let text = arg_to_string(&args[0]).map_err(|e| to_js_error(cx.clone(), e))?;
+            Ok::<_, rquickjs::Error>(ArrayBuffer::new(cx.clone(), text.into_bytes())?.into_value())
+        },
+    )?;
+
+    // encode_into(text, available) returns {bytes, read}
+    let encode_into_callback = Function::new(
+        ctx.clone(),
+        |cx: Ctx<'js>, args: Rest<Value<'js>>| -> Result<Value<'js>, rquickjs::Error> {
+            check_args(&args, 2)?;
+            let text = arg_to_string(&args[0]).map_err(|e| to_js_error(cx.clone(), e))?;
+            let available = args[1].as_number().unwrap_or(0.0) as usize;
+            let (bytes, read) = encode_into(&text, available);
+            let result = Object::new(cx.clone())?;
+            result.set("bytes", ArrayBuffer::new(cx.clone(), bytes)?)?;
+            result.set("read", read as u32)?;
+            Ok::<_, rquickjs::Error>(Value::from_object(result))
+        },
+    )?;
+
+    encoding.set("encoding_name", encoding_name_callback)?;
+    encoding.set("encode", encode_callback)?;
+    encoding.set("encode_into", encode_into_callback)?;
+    Class::<StreamDecoder>::define(&encoding)?;
+    Ok(encoding)
+}
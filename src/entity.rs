@@ -81,6 +81,9 @@ impl JsHttpObject {
     }
 
     pub fn into_response(self) -> Response {
+        if self.body_handle == 0 {
+            crate::metrics::record_response_body(self.body.as_ref().map_or(0, |body| body.len()));
+        }
         let mut response_builder = http::Response::builder().status(self.status);
         if let Some(headers) = response_builder.headers_mut() {
             for (header_name, header_value) in self.headers.iter() {
@@ -101,7 +104,7 @@ impl JsHttpObject {
         // if body_handle is 0, try read body from js_response.body
         // it should be an arraybuffer
         if self.body_handle == 0 {
-            let body = Body::from(self.body.unwrap());
+            let body = Body::from(self.body.unwrap_or_default());
             let response = response_builder.body(body).unwrap();
             return response;
         }
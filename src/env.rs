@@ -0,0 +1,128 @@
+use crate::hostcall::{arg_to_string, check_args, to_js_error};
+use anyhow::anyhow;
+use rquickjs::{prelude::Rest, Array, Ctx, Function, IntoJs, Object, Value};
+use std::borrow::Cow;
+
+// PLATFORM_PREFIX is the prefix of the platform configuration, such as LAND_BINDINGS_URL,
+// it can be read but it is not listed as the vars of the script
+const PLATFORM_PREFIX: &str = "LAND_";
+
+// JSON_VARS_ENV declares the vars with JSON values, such as "APP_CONFIG,FEATURE_FLAGS"
+const JSON_VARS_ENV: &str = "LAND_JSON_VARS";
+
+// SECRETS_ENV declares the secret vars, such as "API_TOKEN", their values are redacted in the console output
+const SECRETS_ENV: &str = "LAND_SECRETS";
+
+const REDACTED: &str = "[REDACTED]";
+
+// DECLARATION_VARS are the platform vars read by the bindings of js-vendor with read_env,
+// they only declare the binding names
+const DECLARATION_VARS: [&str; 4] = [
+    "LAND_KV_BINDINGS",
+    "LAND_BUCKET_BINDINGS",
+    "LAND_SQL_BINDINGS",
+    "LAND_QUEUE_BINDINGS",
+];
+
+/// MODULE is the source of the `land:env` module, the env can be imported by any module of the script
+pub static MODULE: &str = include_str!("../js-vendor/src/modules/env.js");
+
+fn declared(key: &str) -> Vec<String> {
+    var(key)
+        .unwrap_or_default()
+        .split(',')
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .collect()
+}
+
+/// var returns the value of env key, None if it is not set. It is for the engine,
+/// the script reads the vars with script_var, the platform configuration is hidden from it.
+pub fn var(key: &str) -> Option<String> {
+    std::env::var_os(key).map(|value| value.to_string_lossy().into_owned())
+}
+
+// script_var returns the value of env key for the script, None for the platform configuration,
+// such as LAND_DEBUG_SECRET and LAND_SECRETS, except the declarations allowed
+fn script_var(key: &str, allowed: &[&str]) -> Option<String> {
+    if key.to_ascii_uppercase().starts_with(PLATFORM_PREFIX) && !allowed.contains(&key) {
+        return None;
+    }
+    var(key)
+}
+
+/// keys returns the names of the vars of the script, sorted, the platform configuration is not listed
+pub fn keys() -> Vec<String> {
+    let mut keys: Vec<String> = std::env::vars_os()
+        .filter_map(|(key, _)| key.into_string().ok())
+        .filter(|key| !key.starts_with(PLATFORM_PREFIX) && *key == key.to_uppercase())
+        .collect();
+    keys.sort();
+    keys
+}
+
+/// redact replaces the values of the secret vars in text
+pub fn redact(text: &str) -> Cow<str> {
+    let mut text = Cow::Borrowed(text);
+    for secret in declared(SECRETS_ENV) {
+        if let Some(value) = var(&secret).filter(|value| !value.is_empty()) {
+            if text.contains(&value) {
+                text = Cow::Owned(text.replace(&value, REDACTED));
+            }
+        }
+    }
+    text
+}
+
+/// build the env hostcalls, they are set on the hostcall object
+pub fn build<'js>(ctx: Ctx<'js>, hostcall: &Object<'js>) -> rquickjs::Result<()> {
+    // read_env(key) returns the string value of env key or null, the platform configuration
+    // is null except the binding declarations
+    let read_env = Function::new(
+        ctx.clone(),
+        |cx: Ctx<'js>, args: Rest<Value<'js>>| -> Result<Value<'js>, rquickjs::Error> {
+            check_args(&args, 1)?;
+            let key = arg_to_string(&args[0]).map_err(|e| to_js_error(cx.clone(), e))?;
+            match script_var(&key, &DECLARATION_VARS) {
+                Some(value) => value.into_js(&cx),
+                None => Ok(Value::new_null(cx.clone())),
+            }
+        },
+    )?;
+
+    // env_var(key) returns the value of var key, the JSON vars are parsed,
+    // null if it is not set or it is platform configuration
+    let env_var = Function::new(
+        ctx.clone(),
+        |cx: Ctx<'js>, args: Rest<Value<'js>>| -> Result<Value<'js>, rquickjs::Error> {
+            check_args(&args, 1)?;
+            let key = arg_to_string(&args[0]).map_err(|e| to_js_error(cx.clone(), e))?;
+            let value = match script_var(&key, &[]) {
+                Some(value) => value,
+                None => return Ok(Value::new_null(cx.clone())),
+            };
+            if !declared(JSON_VARS_ENV).contains(&key) {
+                return value.into_js(&cx);
+            }
+            cx.json_parse(value)
+                .map_err(|_| to_js_error(cx.clone(), anyhow!("Env var {} is not valid JSON", key)))
+        },
+    )?;
+
+    // env_keys() returns the names of the vars
+    let env_keys = Function::new(
+        ctx.clone(),
+        |cx: Ctx<'js>| -> Result<Array<'js>, rquickjs::Error> {
+            let array = Array::new(cx.clone())?;
+            for (index, key) in keys().into_iter().enumerate() {
+                array.set(index, key)?;
+            }
+            Ok(array)
+        },
+    )?;
+
+    hostcall.set("read_env", read_env)?;
+    hostcall.set("env_var", env_var)?;
+    hostcall.set("env_keys", env_keys)?;
+    Ok(())
+}
@@ -0,0 +1,367 @@
+use crate::console::{self, Level};
+use crate::source_map::SourceMap;
+use land_sdk::http::{Body, Response};
+use once_cell::sync::OnceCell;
+use rquickjs::{Array, Context, Ctx, Value};
+
+// MODE_ENV is "development" to render the error details, the other modes return a generic body
+// with the error id of the logs, so the stacks and the sources are not exposed in production
+const MODE_ENV: &str = "LAND_MODE";
+
+/// ERROR_ID_HEADER is the id of the error in the logs
+pub const ERROR_ID_HEADER: &str = "x-land-error-id";
+
+// USER_MODULE is the module name of the user script in the stack frames
+const USER_MODULE: &str = "user.js";
+
+// MAX_DEPTH limits the nested causes and errors, a cause can refer to an error of the chain
+const MAX_DEPTH: usize = 8;
+
+// MAX_ERRORS limits the described errors of an AggregateError
+const MAX_ERRORS: usize = 16;
+
+// SNIPPET_FRAMES and SNIPPET_LINES are the frames with a code snippet and the lines around the failing line
+const SNIPPET_FRAMES: usize = 3;
+const SNIPPET_LINES: u32 = 3;
+
+// SOURCE is the user script loaded in wizer.initialize, the source map is decoded at the first error
+static SOURCE: OnceCell<String> = OnceCell::new();
+static SOURCE_MAP: OnceCell<Option<SourceMap>> = OnceCell::new();
+
+/// init keeps the user script for the code snippets of the error pages
+pub fn init(script: &str) {
+    let _ = SOURCE.set(script.to_string());
+}
+
+fn development() -> bool {
+    crate::env::var(MODE_ENV).is_some_and(|mode| mode.eq_ignore_ascii_case("development"))
+}
+
+// Frame is a stack frame, the location is mapped to the original source if the script has a source map.
+// The content is the script or the original source, it is shared with SOURCE and SOURCE_MAP.
+struct Frame {
+    function: String,
+    file: String,
+    line: Option<u32>,
+    column: Option<u32>,
+    content: Option<&'static str>,
+}
+
+impl Frame {
+    // parse parses a QuickJS stack line, such as "    at handleRequest (user.js:10:15)"
+    fn parse(line: &str) -> Option<Self> {
+        let line = line.trim().strip_prefix("at ")?;
+        let (function, location) = match line.rsplit_once(" (") {
+            Some((function, location)) => (function, location.trim_end_matches(')')),
+            None => ("<anonymous>", line),
+        };
+        let mut parts = location.rsplitn(3, ':');
+        let (column, line, file) = match (parts.next(), parts.next(), parts.next()) {
+            (Some(column), Some(line), Some(file)) if column.parse::<u32>().is_ok() => {
+                (column.parse().ok(), line.parse().ok(), file)
+            }
+            _ => match location.rsplit_once(':') {
+                Some((file, line)) => (None, line.parse().ok(), file),
+                None => (None, None, location),
+            },
+        };
+        let mut frame = Self {
+            function: function.to_string(),
+            file: file.to_string(),
+            line,
+            column,
+            content: None,
+        };
+        if frame.file == USER_MODULE {
+            frame.map();
+        }
+        Some(frame)
+    }
+
+    // map maps the frame of the user script to the original source
+    fn map(&mut self) {
+        let source = match SOURCE.get() {
+            Some(source) => source,
+            None => return,
+        };
+        let map = SOURCE_MAP.get_or_init(|| SourceMap::parse_inline(source));
+        let location = self
+            .line
+            .and_then(|line| map.as_ref()?.lookup(line, self.column));
+        match location {
+            Some(location) => {
+                self.file = location.source.to_string();
+                self.line = Some(location.line);
+                self.column = Some(location.column);
+                self.content = location.content;
+            }
+            None => self.content = Some(source.as_str()),
+        }
+    }
+
+    fn location(&self) -> String {
+        match (self.line, self.column) {
+            (Some(line), Some(column)) => format!("{}:{}:{}", self.file, line, column),
+            (Some(line), None) => format!("{}:{}", self.file, line),
+            _ => self.file.clone(),
+        }
+    }
+
+    // snippet returns the numbered lines around the failing line, the failing line is marked with ">"
+    fn snippet(&self) -> Option<Vec<(u32, &'static str, bool)>> {
+        let line = self.line?;
+        let content = self.content?;
+        let start = line.saturating_sub(SNIPPET_LINES).max(1);
+        let lines: Vec<(u32, &'static str, bool)> = content
+            .lines()
+            .enumerate()
+            .skip(start as usize - 1)
+            .take((line - start + SNIPPET_LINES + 1) as usize)
+            .map(|(i, text)| (i as u32 + 1, text, i as u32 + 1 == line))
+            .collect();
+        if lines.is_empty() {
+            None
+        } else {
+            Some(lines)
+        }
+    }
+}
+
+/// Failure is an uncaught error of the handler, it is rendered as the 500 response.
+/// The cause chain and the errors of an AggregateError are described recursively.
+pub struct Failure {
+    name: String,
+    message: String,
+    stack: String,
+    // is_error is false for the thrown values that are not errors, the message is the inspected value
+    is_error: bool,
+    cause: Option<Box<Failure>>,
+    errors: Vec<Failure>,
+}
+
+impl Failure {
+    /// from_js describes a thrown value, its cause chain and the errors of an AggregateError
+    pub fn from_js<'js>(ctx: &Ctx<'js>, value: Value<'js>) -> Self {
+        Self::describe(ctx, &value, 0)
+    }
+
+    fn describe<'js>(ctx: &Ctx<'js>, value: &Value<'js>, depth: usize) -> Self {
+        let exception = match value.as_exception() {
+            Some(exception) => exception,
+            None => {
+                let message = crate::inspect::inspect(ctx, value, crate::inspect::DEFAULT_DEPTH)
+                    .unwrap_or_else(|_| "unknown error".to_string());
+                return Self {
+                    name: "Uncaught".to_string(),
+                    message,
+                    stack: String::new(),
+                    is_error: false,
+                    cause: None,
+                    errors: Vec::new(),
+                };
+            }
+        };
+        let object = exception.as_object();
+        let mut failure = Self {
+            name: object
+                .get::<_, Option<String>>("name")
+                .ok()
+                .flatten()
+                .unwrap_or_else(|| "Error".to_string()),
+            message: exception.message().unwrap_or_default(),
+            stack: exception.stack().unwrap_or_default(),
+            is_error: true,
+            cause: None,
+            errors: Vec::new(),
+        };
+        if depth >= MAX_DEPTH {
+            return failure;
+        }
+        if object.contains_key("cause").unwrap_or(false) {
+            if let Ok(cause) = object.get::<_, Value>("cause") {
+                failure.cause = Some(Box::new(Self::describe(ctx, &cause, depth + 1)));
+            }
+        }
+        if let Ok(errors) = object.get::<_, Array>("errors") {
+            failure.errors = errors
+                .iter::<Value>()
+                .take(MAX_ERRORS)
+                .filter_map(|error| error.ok())
+                .map(|error| Self::describe(ctx, &error, depth + 1))
+                .collect();
+        }
+        failure
+    }
+
+    /// from_exception describes the pending exception of a failed call into the context
+    pub fn from_exception(context: &Context, err: rquickjs::Error) -> Self {
+        if !err.is_exception() {
+            return Self::from_error(&err.into());
+        }
+        context.with(|ctx| {
+            let exception = ctx.catch();
+            Self::from_js(&ctx, exception)
+        })
+    }
+
+    /// from_error describes an error of the host, such as a failed body read, the contexts are the causes
+    pub fn from_error(err: &anyhow::Error) -> Self {
+        let messages: Vec<String> = err.chain().map(|cause| cause.to_string()).collect();
+        let mut failure: Option<Failure> = None;
+        for message in messages.into_iter().rev() {
+            failure = Some(Self {
+                name: "Error".to_string(),
+                message,
+                stack: String::new(),
+                is_error: true,
+                cause: failure.map(Box::new),
+                errors: Vec::new(),
+            });
+        }
+        failure.unwrap()
+    }
+
+    fn frames(&self) -> Vec<Frame> {
+        self.stack.lines().filter_map(Frame::parse).collect()
+    }
+
+    fn headline(&self) -> String {
+        if self.is_error {
+            format!("{}: {}", self.name, self.message)
+        } else {
+            self.message.clone()
+        }
+    }
+
+    /// text renders the error as plain text, the frames of the user script are mapped
+    pub fn text(&self) -> String {
+        let mut text = String::new();
+        self.write_text(&mut text, "", "");
+        text
+    }
+
+    fn write_text(&self, text: &mut String, indent: &str, label: &str) {
+        text.push_str(&format!("{}{}{}\n", indent, label, self.headline()));
+        for frame in self.frames() {
+            text.push_str(&format!(
+                "{}    at {} ({})\n",
+                indent,
+                frame.function,
+                frame.location()
+            ));
+        }
+        let nested = format!("{}  ", indent);
+        for (i, error) in self.errors.iter().enumerate() {
+            error.write_text(text, &nested, &format!("[errors[{}]] ", i));
+        }
+        if let Some(cause) = &self.cause {
+            cause.write_text(text, indent, "Caused by: ");
+        }
+    }
+
+    fn html(&self, id: &str) -> String {
+        let (name, message) = (escape(&self.name), escape(&self.message));
+        let mut html = format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+             <title>500 {}: {}</title>\n<style>{}</style>\n</head>\n<body>\n\
+             <h1>{}</h1>\n<p class=\"message\">{}</p>\n",
+            name, message, STYLE, name, message
+        );
+        let mut cause = self.cause.as_deref();
+        if cause.is_some() {
+            html.push_str("<h2>Caused by</h2>\n<ol class=\"causes\">\n");
+            while let Some(failure) = cause {
+                html.push_str(&format!("<li>{}</li>\n", escape(&failure.headline())));
+                cause = failure.cause.as_deref();
+            }
+            html.push_str("</ol>\n");
+        }
+        if !self.errors.is_empty() {
+            html.push_str("<h2>Errors</h2>\n<ol class=\"errors\">\n");
+            for error in self.errors.iter() {
+                html.push_str(&format!("<li><pre>{}</pre></li>\n", escape(&error.text())));
+            }
+            html.push_str("</ol>\n");
+        }
+        html.push_str("<h2>Stack</h2>\n<ol class=\"stack\">\n");
+        let mut snippets = 0;
+        for frame in self.frames() {
+            html.push_str(&format!(
+                "<li><code>{}</code> <span class=\"location\">{}</span>\n",
+                escape(&frame.function),
+                escape(&frame.location())
+            ));
+            let snippet = if snippets < SNIPPET_FRAMES {
+                frame.snippet()
+            } else {
+                None
+            };
+            if let Some(lines) = snippet {
+                snippets += 1;
+                html.push_str("<pre class=\"snippet\">");
+                for (number, text, failing) in lines {
+                    html.push_str(&format!(
+                        "<span{}>{} {:>4} | {}</span>\n",
+                        if failing { " class=\"failing\"" } else { "" },
+                        if failing { "&gt;" } else { " " },
+                        number,
+                        escape(text)
+                    ));
+                }
+                html.push_str("</pre>\n");
+            }
+            html.push_str("</li>\n");
+        }
+        html.push_str(&format!(
+            "</ol>\n<footer>Error ID {} &middot; runtime-land-js {}</footer>\n</body>\n</html>\n",
+            escape(id),
+            crate::PKG_VERSION
+        ));
+        html
+    }
+
+    /// into_response logs the error with a new error id and returns the 500 response,
+    /// the details are rendered in development mode, as HTML if the client accepts it
+    pub fn into_response(self, accept: Option<&str>) -> Response {
+        let id = crate::random::hex_id(8);
+        let text = self.text();
+        console::log(Level::Error, &format!("Uncaught {}Error ID: {}", text, id));
+        crate::debug::record_error(&text);
+        let (content_type, body) = if !development() {
+            (
+                "text/plain; charset=utf-8",
+                format!("Internal Server Error\nError ID: {}\n", id),
+            )
+        } else if accept.is_some_and(|accept| accept.contains("text/html")) {
+            ("text/html; charset=utf-8", self.html(&id))
+        } else {
+            (
+                "text/plain; charset=utf-8",
+                format!("{}\nError ID: {}\n", text, id),
+            )
+        };
+        // the secret values are redacted as in the logs
+        let body = crate::env::redact(&body).into_owned();
+        http::Response::builder()
+            .status(500)
+            .header("content-type", content_type)
+            .header(ERROR_ID_HEADER, id)
+            .body(Body::from(body.into_bytes()))
+            .unwrap()
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+const STYLE: &str = "body{font-family:system-ui,sans-serif;margin:2rem;color:#222}\
+h1{color:#b00020;margin-bottom:0}\
+.message{font-size:1.2rem}\
+.location{color:#666}\
+.snippet{background:#f6f6f6;padding:.5rem;overflow:auto}\
+.failing{background:#ffe0e0}\
+footer{margin-top:2rem;color:#666}";
@@ -1,7 +1,7 @@
 use crate::entity::{JsFetchOptions, JsHttpObject};
-use anyhow::Context;
 use land_sdk::http::{Body, RedirectPolicy};
 use rquickjs::{prelude::Rest, ArrayBuffer, Ctx, FromJs, Function, IntoJs, Object, Value};
+use std::time::Instant;
 
 /// build hostcall object that used export to globalThis
 pub fn build<'js>(ctx: Ctx<'js>) -> rquickjs::Result<Object> {
@@ -26,7 +26,16 @@ pub fn build<'js>(ctx: Ctx<'js>) -> rquickjs::Result<Object> {
                 ));
             }
             let body = Body::from_handle(body_handle);
-            let (value, ok) = body.read(0).map_err(|err| to_js_error(cx.clone(), err))?;
+            let mut span = crate::trace::start("read_body", crate::trace::Kind::Internal);
+            let start = Instant::now();
+            let result = body.read(0);
+            crate::performance::record_body_read(start.elapsed());
+            if let Err(err) = &result {
+                span.fail(err);
+            }
+            span.end();
+            let (value, ok) = result.map_err(|err| to_js_error(cx.clone(), err))?;
+            crate::metrics::record_body_read(value.len());
             let chunk_object = Object::new(cx.clone())?;
             chunk_object.set("done", Value::new_bool(cx.clone(), ok))?;
             let chunk_buffer = ArrayBuffer::new(cx.clone(), value)?;
@@ -48,12 +57,42 @@ pub fn build<'js>(ctx: Ctx<'js>) -> rquickjs::Result<Object> {
             }
             let options_value = args.pop().unwrap();
             let req_value = args.pop().unwrap();
-            let js_request = JsHttpObject::from_js(&cx, req_value)?;
+            let mut js_request = JsHttpObject::from_js(&cx, req_value)?;
             let js_request_options = JsFetchOptions::from_js(&cx, options_value)?;
             /*println!(
                 "---fetch begin,js_request: {:?}, js_request_options: {:?}",
                 js_request, js_request_options
             );*/
+            // the Server-Timing description has the host only, the path and query can be private
+            let description = format!(
+                "{} {}",
+                js_request.method,
+                js_request
+                    .uri
+                    .parse::<http::Uri>()
+                    .ok()
+                    .and_then(|uri| uri.host().map(|host| host.to_string()))
+                    .unwrap_or_default()
+            );
+            let mut span = crate::trace::start("fetch", crate::trace::Kind::Client);
+            span.attribute("http.request.method", js_request.method.as_str());
+            span.url_attributes(js_request.uri.as_str());
+            // the trace continues in the upstream, unless the script sets its own traceparent
+            if !js_request
+                .headers
+                .contains_key(crate::trace::TRACEPARENT_HEADER)
+            {
+                if let Some(traceparent) = span.traceparent() {
+                    js_request
+                        .headers
+                        .insert(crate::trace::TRACEPARENT_HEADER.to_string(), traceparent);
+                    if let Some(tracestate) = crate::trace::tracestate() {
+                        js_request
+                            .headers
+                            .insert(crate::trace::TRACESTATE_HEADER.to_string(), tracestate);
+                    }
+                }
+            }
             let http_request = js_request.into_request();
             let http_request_options = land_sdk::http::RequestOptions {
                 timeout: js_request_options.timeout,
@@ -65,8 +104,22 @@ pub fn build<'js>(ctx: Ctx<'js>) -> rquickjs::Result<Object> {
                 },
             };
 
-            let response = land_sdk::http::fetch(http_request, http_request_options)
-                .map_err(|e| to_js_error(cx.clone(), e.into()))?;
+            let start = Instant::now();
+            let response = land_sdk::http::fetch(http_request, http_request_options);
+            crate::performance::record("fetch", start.elapsed(), Some(description));
+            crate::metrics::record_fetch(start.elapsed());
+            let response = match response {
+                Ok(response) => {
+                    span.attribute("http.response.status_code", response.status().as_u16());
+                    span.end();
+                    response
+                }
+                Err(err) => {
+                    span.fail(&err);
+                    span.end();
+                    return Err(to_js_error(cx.clone(), err.into()));
+                }
+            };
             let js_response = JsHttpObject::from_response(response);
             // println!("------fetch_response_js_value: {:?}", js_response);
             let js_response_value = js_response.into_js(&cx)?;
@@ -74,32 +127,16 @@ pub fn build<'js>(ctx: Ctx<'js>) -> rquickjs::Result<Object> {
         },
     )?;
 
-    let read_env = Function::new(
-        ctx.clone(),
-        |cx: Ctx<'js>, args: Rest<Value<'js>>| -> Result<Value<'js>, rquickjs::Error> {
-            if args.len() < 1 {
-                let err = rquickjs::Error::MissingArgs {
-                    expected: 1,
-                    given: args.len(),
-                };
-                return Err(err);
-            }
-            let env_key =
-                arg_to_string(args.first().unwrap()).map_err(|e| to_js_error(cx.clone(), e))?;
-            let env_value = std::env::var_os(env_key);
-            if env_value.is_none() {
-                return Ok(Value::new_null(cx.clone()));
-            }
-            let env_value = env_value.unwrap();
-            let env_value = env_value.to_str().unwrap();
-            let env_value_js = rquickjs::String::from_str(cx.clone(), env_value)?;
-            Ok::<_, rquickjs::Error>(Value::from_string(env_value_js))
-        },
-    )?;
-
     hostcall.set("read_body", read_body_callback)?;
     hostcall.set("fetch_request", fetch_request_callback)?;
-    hostcall.set("read_env", read_env)?;
+    crate::env::build(ctx.clone(), &hostcall)?;
+    crate::cache::build(ctx.clone(), &hostcall)?;
+    crate::kv::build(ctx.clone(), &hostcall)?;
+    crate::bucket::build(ctx.clone(), &hostcall)?;
+    crate::sql::build(ctx.clone(), &hostcall)?;
+    crate::queue::build(ctx.clone(), &hostcall)?;
+    crate::performance::build(ctx.clone(), &hostcall)?;
+    crate::metrics::build(ctx.clone(), &hostcall)?;
     Ok(hostcall)
 }
 
@@ -113,12 +150,25 @@ pub fn arg_to_string(arg: &Value) -> anyhow::Result<String> {
     Err(anyhow::anyhow!("Failed to convert arg to string"))
 }
 
-pub fn get_args_as_str(args: &Rest<Value>) -> anyhow::Result<String> {
-    args.iter()
-        .map(|arg| arg_to_string(arg))
-        .collect::<Result<Vec<String>, _>>()
-        .map(|vec| vec.join(" "))
-        .context("Failed to convert args to string")
+pub fn arg_to_bytes(arg: &Value) -> anyhow::Result<Vec<u8>> {
+    if let Some(buffer) = ArrayBuffer::from_value(arg.clone()) {
+        return Ok(buffer.as_bytes().unwrap_or_default().to_vec());
+    }
+    if let Some(str) = arg.as_string() {
+        return Ok(str.to_string()?.into_bytes());
+    }
+    Err(anyhow::anyhow!("Failed to convert arg to bytes"))
+}
+
+/// check_args returns MissingArgs error if args are fewer than expected
+pub fn check_args(args: &Rest<Value>, expected: usize) -> rquickjs::Result<()> {
+    if args.len() < expected {
+        return Err(rquickjs::Error::MissingArgs {
+            expected,
+            given: args.len(),
+        });
+    }
+    Ok(())
 }
 
 pub fn to_js_error(cx: Ctx, e: anyhow::Error) -> rquickjs::Error {
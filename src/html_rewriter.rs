@@ -0,0 +1,391 @@
+use crate::hostcall::{arg_to_bytes, to_js_error};
+use anyhow::{anyhow, Result};
+use lol_html::html_content::{Comment, ContentType, Doctype, DocumentEnd, Element, TextChunk};
+use lol_html::{
+    DocumentContentHandlers, ElementContentHandlers, HtmlRewriter, OutputSink, Selector, Settings,
+};
+use rquickjs::{class::Trace, Array, ArrayBuffer, Class, Ctx, FromJs, Function, Object, Value};
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Output collects the rewritten bytes, they are taken after each chunk
+#[derive(Clone, Default)]
+struct Output(Rc<RefCell<Vec<u8>>>);
+
+impl OutputSink for Output {
+    fn handle_chunk(&mut self, chunk: &[u8]) {
+        self.0.borrow_mut().extend_from_slice(chunk);
+    }
+}
+
+impl Output {
+    fn take(&self) -> Vec<u8> {
+        std::mem::take(&mut *self.0.borrow_mut())
+    }
+}
+
+/// HandlerSpec is the handler kinds registered by HTMLRewriter.on(selector, handlers)
+#[derive(Debug, Default)]
+pub struct HandlerSpec {
+    pub selector: String,
+    pub element: bool,
+    pub comments: bool,
+    pub text: bool,
+}
+
+/// RewriterSpec is the handlers of an HTMLRewriter passed to create
+#[derive(Debug, Default)]
+pub struct RewriterSpec {
+    pub elements: Vec<HandlerSpec>,
+    pub doctype: bool,
+    pub comments: bool,
+    pub text: bool,
+    pub end: bool,
+}
+
+fn get_bool(object: &Object, key: &str) -> rquickjs::Result<bool> {
+    let value: Value = object.get(key)?;
+    Ok(value.as_bool().unwrap_or(false))
+}
+
+impl<'js> FromJs<'js> for RewriterSpec {
+    fn from_js(ctx: &Ctx<'js>, value: Value<'js>) -> rquickjs::Result<Self> {
+        if !value.is_object() {
+            return Err(ctx.throw(
+                rquickjs::String::from_str(ctx.clone(), "RewriterSpec need from an object")?
+                    .into_value(),
+            ));
+        }
+        let spec_object = value.as_object().unwrap();
+        let elements: Array = spec_object.get("elements")?;
+        let mut handlers = vec![];
+        for item in elements.iter::<Object>() {
+            let item = item?;
+            handlers.push(HandlerSpec {
+                selector: item.get("selector")?,
+                element: get_bool(&item, "element")?,
+                comments: get_bool(&item, "comments")?,
+                text: get_bool(&item, "text")?,
+            });
+        }
+        Ok(Self {
+            elements: handlers,
+            doctype: get_bool(spec_object, "doctype")?,
+            comments: get_bool(spec_object, "comments")?,
+            text: get_bool(spec_object, "text")?,
+            end: get_bool(spec_object, "end")?,
+        })
+    }
+}
+
+/// Mutation is an operation recorded by the JS proxy of an element, comment, text chunk or document end
+#[derive(Debug)]
+struct Mutation {
+    op: String,
+    args: Vec<String>,
+    html: bool,
+}
+
+impl Mutation {
+    fn arg(&self, index: usize) -> &str {
+        self.args.get(index).map(|arg| arg.as_str()).unwrap_or("")
+    }
+
+    fn content_type(&self) -> ContentType {
+        if self.html {
+            ContentType::Html
+        } else {
+            ContentType::Text
+        }
+    }
+}
+
+/// Dispatcher calls the JS dispatch(kind, index, snapshot) function of the running write or end call,
+/// lol_html calls the handlers synchronously inside that call, the function is not kept after it
+#[derive(Clone, Default)]
+struct Dispatcher<'js>(Rc<RefCell<Option<Function<'js>>>>);
+
+impl<'js> Dispatcher<'js> {
+    fn call<F>(&self, kind: &str, index: usize, snapshot: F) -> Result<Vec<Mutation>>
+    where
+        F: FnOnce(&Ctx<'js>) -> rquickjs::Result<Object<'js>>,
+    {
+        let dispatch = self
+            .0
+            .borrow()
+            .clone()
+            .ok_or_else(|| anyhow!("HTMLRewriter handler is called outside of a transform"))?;
+        let snapshot = snapshot(dispatch.ctx())?;
+        let result: Value = dispatch.call((kind, index as u32, snapshot))?;
+        // a handler error is kept by the JS side and thrown again after the native call fails
+        if result.is_null() {
+            return Err(anyhow!("HTMLRewriter handler failed"));
+        }
+        let mut mutations = vec![];
+        if let Some(array) = result.as_array() {
+            for item in array.iter::<Object>() {
+                let item = item?;
+                mutations.push(Mutation {
+                    op: item.get("op")?,
+                    args: item.get("args")?,
+                    html: get_bool(&item, "html")?,
+                });
+            }
+        }
+        Ok(mutations)
+    }
+
+    // with sets the dispatch function while f runs
+    fn with<T>(&self, dispatch: Function<'js>, f: impl FnOnce() -> T) -> T {
+        *self.0.borrow_mut() = Some(dispatch);
+        let result = f();
+        *self.0.borrow_mut() = None;
+        result
+    }
+}
+
+fn element_handler<'js>(
+    dispatcher: Dispatcher<'js>,
+    index: usize,
+) -> impl FnMut(&mut Element) -> lol_html::HandlerResult + 'js {
+    move |element: &mut Element| {
+        let mutations = dispatcher.call("element", index, |ctx| {
+            let snapshot = Object::new(ctx.clone())?;
+            snapshot.set("tagName", element.tag_name())?;
+            snapshot.set("namespaceURI", element.namespace_uri())?;
+            snapshot.set("selfClosing", element.is_self_closing())?;
+            snapshot.set("canHaveContent", element.can_have_content())?;
+            let attributes = Array::new(ctx.clone())?;
+            for (i, attribute) in element.attributes().iter().enumerate() {
+                attributes.set(i, vec![attribute.name(), attribute.value()])?;
+            }
+            snapshot.set("attributes", attributes)?;
+            Ok(snapshot)
+        })?;
+        for mutation in mutations {
+            match mutation.op.as_str() {
+                "setAttribute" => element.set_attribute(mutation.arg(0), mutation.arg(1))?,
+                "removeAttribute" => element.remove_attribute(mutation.arg(0)),
+                "setTagName" => element.set_tag_name(mutation.arg(0))?,
+                "before" => element.before(mutation.arg(0), mutation.content_type()),
+                "after" => element.after(mutation.arg(0), mutation.content_type()),
+                "prepend" => element.prepend(mutation.arg(0), mutation.content_type()),
+                "append" => element.append(mutation.arg(0), mutation.content_type()),
+                "setInnerContent" => {
+                    element.set_inner_content(mutation.arg(0), mutation.content_type())
+                }
+                "replace" => element.replace(mutation.arg(0), mutation.content_type()),
+                "remove" => element.remove(),
+                "removeAndKeepContent" => element.remove_and_keep_content(),
+                op => return Err(anyhow!("Unknown element operation: {}", op).into()),
+            }
+        }
+        Ok(())
+    }
+}
+
+fn comments_handler<'js>(
+    dispatcher: Dispatcher<'js>,
+    kind: &'static str,
+    index: usize,
+) -> impl FnMut(&mut Comment) -> lol_html::HandlerResult + 'js {
+    move |comment: &mut Comment| {
+        let mutations = dispatcher.call(kind, index, |ctx| {
+            let snapshot = Object::new(ctx.clone())?;
+            snapshot.set("text", comment.text())?;
+            Ok(snapshot)
+        })?;
+        for mutation in mutations {
+            match mutation.op.as_str() {
+                "setText" => comment.set_text(mutation.arg(0))?,
+                "before" => comment.before(mutation.arg(0), mutation.content_type()),
+                "after" => comment.after(mutation.arg(0), mutation.content_type()),
+                "replace" => comment.replace(mutation.arg(0), mutation.content_type()),
+                "remove" => comment.remove(),
+                op => return Err(anyhow!("Unknown comment operation: {}", op).into()),
+            }
+        }
+        Ok(())
+    }
+}
+
+fn text_handler<'js>(
+    dispatcher: Dispatcher<'js>,
+    kind: &'static str,
+    index: usize,
+) -> impl FnMut(&mut TextChunk) -> lol_html::HandlerResult + 'js {
+    move |chunk: &mut TextChunk| {
+        let mutations = dispatcher.call(kind, index, |ctx| {
+            let snapshot = Object::new(ctx.clone())?;
+            snapshot.set("text", chunk.as_str())?;
+            snapshot.set("lastInTextNode", chunk.last_in_text_node())?;
+            Ok(snapshot)
+        })?;
+        for mutation in mutations {
+            match mutation.op.as_str() {
+                "before" => chunk.before(mutation.arg(0), mutation.content_type()),
+                "after" => chunk.after(mutation.arg(0), mutation.content_type()),
+                "replace" => chunk.replace(mutation.arg(0), mutation.content_type()),
+                "remove" => chunk.remove(),
+                op => return Err(anyhow!("Unknown text operation: {}", op).into()),
+            }
+        }
+        Ok(())
+    }
+}
+
+fn doctype_handler<'js>(
+    dispatcher: Dispatcher<'js>,
+) -> impl FnMut(&mut Doctype) -> lol_html::HandlerResult + 'js {
+    move |doctype: &mut Doctype| {
+        dispatcher.call("doctype", 0, |ctx| {
+            let snapshot = Object::new(ctx.clone())?;
+            snapshot.set("name", doctype.name())?;
+            snapshot.set("publicId", doctype.public_id())?;
+            snapshot.set("systemId", doctype.system_id())?;
+            Ok(snapshot)
+        })?;
+        Ok(())
+    }
+}
+
+fn end_handler<'js>(
+    dispatcher: Dispatcher<'js>,
+) -> impl FnMut(&mut DocumentEnd) -> lol_html::HandlerResult + 'js {
+    move |end: &mut DocumentEnd| {
+        let mutations = dispatcher.call("end", 0, |ctx| Object::new(ctx.clone()))?;
+        for mutation in mutations {
+            match mutation.op.as_str() {
+                "append" => end.append(mutation.arg(0), mutation.content_type()),
+                op => return Err(anyhow!("Unknown document end operation: {}", op).into()),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Rewriter is the native rewriter of an HTMLRewriter transform, it is freed with its JS object,
+/// so a body that is never read to the end does not keep it
+#[derive(Trace)]
+#[rquickjs::class]
+pub struct Rewriter<'js> {
+    // the dispatch function is only set during a write or end call, it is not traced
+    #[qjs(skip_trace)]
+    dispatcher: Dispatcher<'js>,
+    #[qjs(skip_trace)]
+    rewriter: Option<HtmlRewriter<'js, Output>>,
+    #[qjs(skip_trace)]
+    output: Output,
+}
+
+// create builds the lol_html rewriter of spec, the handlers call the dispatch function of dispatcher
+fn create<'js>(
+    spec: RewriterSpec,
+    dispatcher: &Dispatcher<'js>,
+    output: Output,
+) -> Result<HtmlRewriter<'js, Output>> {
+    let mut element_content_handlers = vec![];
+    for (index, handler) in spec.elements.iter().enumerate() {
+        let selector: Selector = handler
+            .selector
+            .parse()
+            .map_err(|e| anyhow!("Invalid selector '{}': {}", handler.selector, e))?;
+        let mut handlers = ElementContentHandlers::default();
+        if handler.element {
+            handlers = handlers.element(element_handler(dispatcher.clone(), index));
+        }
+        if handler.comments {
+            handlers = handlers.comments(comments_handler(dispatcher.clone(), "comments", index));
+        }
+        if handler.text {
+            handlers = handlers.text(text_handler(dispatcher.clone(), "text", index));
+        }
+        element_content_handlers.push((Cow::Owned(selector), handlers));
+    }
+    let mut document_handlers = DocumentContentHandlers::default();
+    if spec.doctype {
+        document_handlers = document_handlers.doctype(doctype_handler(dispatcher.clone()));
+    }
+    if spec.comments {
+        document_handlers =
+            document_handlers.comments(comments_handler(dispatcher.clone(), "doc_comments", 0));
+    }
+    if spec.text {
+        document_handlers = document_handlers.text(text_handler(dispatcher.clone(), "doc_text", 0));
+    }
+    if spec.end {
+        document_handlers = document_handlers.end(end_handler(dispatcher.clone()));
+    }
+    Ok(HtmlRewriter::new(
+        Settings {
+            element_content_handlers,
+            document_content_handlers: vec![document_handlers],
+            ..Settings::default()
+        },
+        output,
+    ))
+}
+
+#[rquickjs::methods]
+impl<'js> Rewriter<'js> {
+    /// new Rewriter(spec) builds the rewriter of the handlers of an HTMLRewriter
+    #[qjs(constructor)]
+    pub fn new(ctx: Ctx<'js>, spec: RewriterSpec) -> rquickjs::Result<Self> {
+        let dispatcher = Dispatcher::default();
+        let output = Output::default();
+        let rewriter =
+            create(spec, &dispatcher, output.clone()).map_err(|e| to_js_error(ctx, e))?;
+        Ok(Self {
+            dispatcher,
+            rewriter: Some(rewriter),
+            output,
+        })
+    }
+
+    /// write(chunk, dispatch) returns the rewritten bytes of chunk as ArrayBuffer
+    pub fn write(
+        &mut self,
+        ctx: Ctx<'js>,
+        chunk: Value<'js>,
+        dispatch: Function<'js>,
+    ) -> rquickjs::Result<ArrayBuffer<'js>> {
+        let chunk = arg_to_bytes(&chunk).map_err(|e| to_js_error(ctx.clone(), e))?;
+        let rewriter = self
+            .rewriter
+            .as_mut()
+            .ok_or_else(|| to_js_error(ctx.clone(), anyhow!("HTMLRewriter is already ended")))?;
+        self.dispatcher
+            .with(dispatch, || rewriter.write(&chunk))
+            .map_err(|e| to_js_error(ctx.clone(), e.into()))?;
+        ArrayBuffer::new(ctx, self.output.take())
+    }
+
+    /// end(dispatch) finishes the rewriter and returns the remaining bytes as ArrayBuffer
+    pub fn end(
+        &mut self,
+        ctx: Ctx<'js>,
+        dispatch: Function<'js>,
+    ) -> rquickjs::Result<ArrayBuffer<'js>> {
+        let rewriter = self
+            .rewriter
+            .take()
+            .ok_or_else(|| to_js_error(ctx.clone(), anyhow!("HTMLRewriter is already ended")))?;
+        self.dispatcher
+            .with(dispatch, || rewriter.end())
+            .map_err(|e| to_js_error(ctx.clone(), e.into()))?;
+        ArrayBuffer::new(ctx, self.output.take())
+    }
+
+    /// close() drops the rewriter when the body stream is cancelled
+    pub fn close(&mut self) {
+        self.rewriter = None;
+    }
+}
+
+/// build native html_rewriter object that used by HTMLRewriter
+pub fn build<'js>(ctx: Ctx<'js>) -> rquickjs::Result<Object<'js>> {
+    let html_rewriter = Object::new(ctx.clone())?;
+    Class::<Rewriter>::define(&html_rewriter)?;
+    Ok(html_rewriter)
+}
@@ -0,0 +1,566 @@
+use crate::hostcall::check_args;
+use rquickjs::{
+    prelude::{Rest, This},
+    Array, ArrayBuffer, Ctx, Function, Object, Type, Value,
+};
+
+// The limits follow util.inspect of Node.js
+pub const DEFAULT_DEPTH: usize = 2;
+const OBJECT_FORMAT_DEPTH: usize = 4;
+const MAX_ARRAY_LENGTH: usize = 100;
+const MAX_STRING_LENGTH: usize = 10000;
+const BREAK_LENGTH: usize = 72;
+
+/// MODULE is the source of the `land:util` module, it wraps the native util object
+pub static MODULE: &str = include_str!("../js-vendor/src/modules/util.js");
+
+// Inspector renders values like util.inspect, it keeps the objects on the current path to find cycles
+struct Inspector<'js> {
+    ctx: Ctx<'js>,
+    depth: usize,
+    seen: Vec<Value<'js>>,
+    string: Function<'js>,
+    object_to_string: Function<'js>,
+    array_from: Function<'js>,
+}
+
+impl<'js> Inspector<'js> {
+    fn new(ctx: &Ctx<'js>, depth: usize) -> rquickjs::Result<Self> {
+        let global = ctx.globals();
+        let object: Object = global.get("Object")?;
+        let prototype: Object = object.get("prototype")?;
+        let array: Object = global.get("Array")?;
+        Ok(Self {
+            ctx: ctx.clone(),
+            depth,
+            seen: Vec::new(),
+            string: global.get("String")?,
+            object_to_string: prototype.get("toString")?,
+            array_from: array.get("from")?,
+        })
+    }
+
+    // string returns String(value)
+    fn string(&self, value: &Value<'js>) -> rquickjs::Result<String> {
+        self.string.call((value.clone(),))
+    }
+
+    // tag returns X of Object.prototype.toString, such as Map, Uint8Array or the Symbol.toStringTag
+    fn tag(&self, value: &Value<'js>) -> rquickjs::Result<String> {
+        let tag: String = self.object_to_string.call((This(value.clone()),))?;
+        Ok(tag
+            .trim_start_matches("[object ")
+            .trim_end_matches(']')
+            .to_string())
+    }
+
+    fn number(&self, value: &Value<'js>) -> rquickjs::Result<String> {
+        if let Some(int) = value.as_int() {
+            return Ok(int.to_string());
+        }
+        match value.as_float() {
+            Some(float) if float == 0.0 && float.is_sign_negative() => Ok("-0".to_string()),
+            _ => self.string(value),
+        }
+    }
+
+    fn inspect(&mut self, value: &Value<'js>, level: usize) -> rquickjs::Result<String> {
+        match value.type_of() {
+            Type::Undefined | Type::Uninitialized => Ok("undefined".to_string()),
+            Type::Null => Ok("null".to_string()),
+            Type::Bool => Ok(value.as_bool().unwrap_or_default().to_string()),
+            Type::Int | Type::Float => self.number(value),
+            Type::BigInt => Ok(format!("{}n", self.string(value)?)),
+            Type::String => Ok(quote(&self.string(value)?)),
+            Type::Symbol => self.string(value),
+            Type::Function | Type::Constructor => self.function(value),
+            _ => match value.as_object() {
+                Some(object) => self.object(value, object, level),
+                None => self.string(value),
+            },
+        }
+    }
+
+    fn function(&self, value: &Value<'js>) -> rquickjs::Result<String> {
+        let function = value.as_object().unwrap();
+        let name: Value = function.get("name")?;
+        let name = name
+            .as_string()
+            .map(|name| name.to_string())
+            .transpose()?
+            .unwrap_or_default();
+        if self.string(value)?.starts_with("class") {
+            if name.is_empty() {
+                return Ok("[class (anonymous)]".to_string());
+            }
+            return Ok(format!("[class {}]", name));
+        }
+        if name.is_empty() {
+            return Ok("[Function (anonymous)]".to_string());
+        }
+        Ok(format!("[Function: {}]", name))
+    }
+
+    // constructor_name returns the name of the constructor, None for the null prototype objects
+    fn constructor_name(&self, object: &Object<'js>) -> rquickjs::Result<Option<String>> {
+        let constructor: Value = object.get("constructor")?;
+        let constructor = match constructor.as_object() {
+            Some(constructor) => constructor,
+            None => return Ok(None),
+        };
+        let name: Value = constructor.get("name")?;
+        Ok(Some(
+            name.as_string()
+                .map(|name| name.to_string())
+                .transpose()?
+                .filter(|name| !name.is_empty())
+                .unwrap_or_else(|| "Object".to_string()),
+        ))
+    }
+
+    fn object(
+        &mut self,
+        value: &Value<'js>,
+        object: &Object<'js>,
+        level: usize,
+    ) -> rquickjs::Result<String> {
+        if self.seen.contains(value) {
+            return Ok("[Circular]".to_string());
+        }
+        let tag = self.tag(value)?;
+        match tag.as_str() {
+            "Error" => return self.error(value, object, level),
+            "Date" => return self.date(object),
+            "RegExp" => return self.string(value),
+            "Promise" => return Ok("Promise {}".to_string()),
+            "WeakMap" | "WeakSet" => return Ok(format!("{} {{ <items unknown> }}", tag)),
+            "ArrayBuffer" => return self.array_buffer(value),
+            _ => {}
+        }
+        let name = self.constructor_name(object)?;
+        if level > self.depth {
+            let name = match value.as_array() {
+                Some(_) => "Array".to_string(),
+                None => name.unwrap_or_else(|| "Object".to_string()),
+            };
+            return Ok(format!("[{}]", name));
+        }
+
+        self.seen.push(value.clone());
+        let result = if let Some(array) = value.as_array() {
+            self.array(array, level)
+        } else if tag == "Map" || tag == "Set" {
+            self.collection(value, &tag, level)
+        } else if tag.ends_with("Array") {
+            self.typed_array(object, &tag, level)
+        } else {
+            self.plain_object(object, name, &tag, level)
+        };
+        self.seen.pop();
+        result
+    }
+
+    fn array(&mut self, array: &Array<'js>, level: usize) -> rquickjs::Result<String> {
+        let mut entries = Vec::new();
+        for index in 0..array.len().min(MAX_ARRAY_LENGTH) {
+            let item: Value = array.get(index)?;
+            entries.push(self.inspect(&item, level + 1)?);
+        }
+        more_items(&mut entries, array.len());
+        Ok(group("", "[", "]", entries, level))
+    }
+
+    fn typed_array(
+        &mut self,
+        object: &Object<'js>,
+        tag: &str,
+        level: usize,
+    ) -> rquickjs::Result<String> {
+        let length: Value = object.get("length")?;
+        let length = length.as_number().unwrap_or_default() as usize;
+        let mut entries = Vec::new();
+        for index in 0..length.min(MAX_ARRAY_LENGTH) {
+            let item: Value = object.get(index as u32)?;
+            entries.push(self.inspect(&item, level + 1)?);
+        }
+        more_items(&mut entries, length);
+        Ok(group(
+            &format!("{}({}) ", tag, length),
+            "[",
+            "]",
+            entries,
+            level,
+        ))
+    }
+
+    // collection renders the entries of a Map or the values of a Set
+    fn collection(
+        &mut self,
+        value: &Value<'js>,
+        tag: &str,
+        level: usize,
+    ) -> rquickjs::Result<String> {
+        let items: Array = self.array_from.call((value.clone(),))?;
+        let mut entries = Vec::new();
+        for index in 0..items.len().min(MAX_ARRAY_LENGTH) {
+            let item: Value = items.get(index)?;
+            if tag == "Map" {
+                let pair = item.as_array().unwrap();
+                let key: Value = pair.get(0)?;
+                let value: Value = pair.get(1)?;
+                entries.push(format!(
+                    "{} => {}",
+                    self.inspect(&key, level + 1)?,
+                    self.inspect(&value, level + 1)?
+                ));
+            } else {
+                entries.push(self.inspect(&item, level + 1)?);
+            }
+        }
+        more_items(&mut entries, items.len());
+        Ok(group(
+            &format!("{}({}) ", tag, items.len()),
+            "{",
+            "}",
+            entries,
+            level,
+        ))
+    }
+
+    fn plain_object(
+        &mut self,
+        object: &Object<'js>,
+        name: Option<String>,
+        tag: &str,
+        level: usize,
+    ) -> rquickjs::Result<String> {
+        let entries = self.properties(object, &[], level)?;
+        let prefix = match name {
+            None => "[Object: null prototype] ".to_string(),
+            Some(name) if tag != "Object" && tag != name => format!("{} [{}] ", name, tag),
+            Some(name) if name != "Object" => format!("{} ", name),
+            Some(_) => String::new(),
+        };
+        Ok(group(&prefix, "{", "}", entries, level))
+    }
+
+    // properties renders the own enumerable string keys of object as "key: value"
+    fn properties(
+        &mut self,
+        object: &Object<'js>,
+        skip: &[&str],
+        level: usize,
+    ) -> rquickjs::Result<Vec<String>> {
+        let mut entries = Vec::new();
+        for key in object.keys::<String>() {
+            let key = key?;
+            if skip.contains(&key.as_str()) {
+                continue;
+            }
+            let value: Value = object.get(key.as_str())?;
+            entries.push(format!(
+                "{}: {}",
+                property_key(&key),
+                self.inspect(&value, level + 1)?
+            ));
+        }
+        Ok(entries)
+    }
+
+    // error renders the name, message and stack, the extra properties such as code follow it
+    fn error(
+        &mut self,
+        value: &Value<'js>,
+        object: &Object<'js>,
+        level: usize,
+    ) -> rquickjs::Result<String> {
+        let name: Value = object.get("name")?;
+        let name = if name.is_undefined() {
+            "Error".to_string()
+        } else {
+            self.string(&name)?
+        };
+        let message: Value = object.get("message")?;
+        let message = if message.is_undefined() {
+            String::new()
+        } else {
+            self.string(&message)?
+        };
+        let header = if message.is_empty() {
+            name.clone()
+        } else {
+            format!("{}: {}", name, message)
+        };
+        let stack: Value = object.get("stack")?;
+        let stack = match stack.as_string() {
+            Some(stack) => stack.to_string()?.trim_end().to_string(),
+            None => String::new(),
+        };
+        let mut text = if stack.is_empty() {
+            format!("[{}]", header)
+        } else if stack.starts_with(&name) {
+            stack
+        } else {
+            format!("{}\n{}", header, stack)
+        };
+        if level > 0 {
+            text = text.replace('\n', &format!("\n{}", "  ".repeat(level)));
+        }
+        if level > self.depth {
+            return Ok(text);
+        }
+        self.seen.push(value.clone());
+        let entries = self.properties(object, &["stack", "message"], level);
+        self.seen.pop();
+        let entries = entries?;
+        if entries.is_empty() {
+            return Ok(text);
+        }
+        Ok(group(&format!("{} ", text), "{", "}", entries, level))
+    }
+
+    fn date(&self, object: &Object<'js>) -> rquickjs::Result<String> {
+        let to_iso_string: Function = object.get("toISOString")?;
+        match to_iso_string.call::<_, String>((This(object.clone()),)) {
+            Ok(date) => Ok(date),
+            // toISOString throws RangeError for the invalid dates
+            Err(rquickjs::Error::Exception) => {
+                self.ctx.catch();
+                Ok("Invalid Date".to_string())
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    fn array_buffer(&self, value: &Value<'js>) -> rquickjs::Result<String> {
+        let bytes = ArrayBuffer::from_value(value.clone())
+            .and_then(|buffer| buffer.as_bytes().map(|bytes| bytes.to_vec()))
+            .unwrap_or_default();
+        let mut contents: Vec<String> = bytes
+            .iter()
+            .take(MAX_ARRAY_LENGTH)
+            .map(|byte| format!("{:02x}", byte))
+            .collect();
+        if bytes.len() > MAX_ARRAY_LENGTH {
+            contents.push(format!("... {} more bytes", bytes.len() - MAX_ARRAY_LENGTH));
+        }
+        Ok(format!(
+            "ArrayBuffer {{ [Uint8Contents]: <{}>, byteLength: {} }}",
+            contents.join(" "),
+            bytes.len()
+        ))
+    }
+}
+
+fn more_items(entries: &mut Vec<String>, length: usize) {
+    if length > MAX_ARRAY_LENGTH {
+        let more = length - MAX_ARRAY_LENGTH;
+        entries.push(format!(
+            "... {} more item{}",
+            more,
+            if more > 1 { "s" } else { "" }
+        ));
+    }
+}
+
+// group joins the entries in one line if they fit, or one entry per line indented by the level
+fn group(prefix: &str, open: &str, close: &str, entries: Vec<String>, level: usize) -> String {
+    if entries.is_empty() {
+        return format!("{}{}{}", prefix, open, close);
+    }
+    let width =
+        level * 2 + prefix.len() + entries.iter().map(|entry| entry.len() + 2).sum::<usize>() + 2;
+    if width <= BREAK_LENGTH && !entries.iter().any(|entry| entry.contains('\n')) {
+        return format!("{}{} {} {}", prefix, open, entries.join(", "), close);
+    }
+    let indent = "  ".repeat(level + 1);
+    format!(
+        "{}{}\n{}{}\n{}{}",
+        prefix,
+        open,
+        indent,
+        entries.join(&format!(",\n{}", indent)),
+        "  ".repeat(level),
+        close
+    )
+}
+
+// quote returns the string in single quotes, or double quotes if it has single quotes only
+fn quote(text: &str) -> String {
+    let quote = if text.contains('\'') && !text.contains('"') {
+        '"'
+    } else {
+        '\''
+    };
+    let length = text.chars().count();
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push(quote);
+    for c in text.chars().take(MAX_STRING_LENGTH) {
+        match c {
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            '\\' => quoted.push_str("\\\\"),
+            c if c == quote => {
+                quoted.push('\\');
+                quoted.push(c);
+            }
+            c if c.is_control() => quoted.push_str(&format!("\\x{:02X}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push(quote);
+    if length > MAX_STRING_LENGTH {
+        quoted.push_str(&format!(
+            "... {} more characters",
+            length - MAX_STRING_LENGTH
+        ));
+    }
+    quoted
+}
+
+// property_key returns the identifier keys as they are, other keys are quoted
+fn property_key(key: &str) -> String {
+    let mut chars = key.chars();
+    let identifier = chars
+        .next()
+        .map(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        .unwrap_or(false)
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+    if identifier {
+        key.to_string()
+    } else {
+        quote(key)
+    }
+}
+
+/// inspect returns the string representation of value, the objects deeper than depth are abbreviated
+pub fn inspect<'js>(ctx: &Ctx<'js>, value: &Value<'js>, depth: usize) -> rquickjs::Result<String> {
+    Inspector::new(ctx, depth)?.inspect(value, 0)
+}
+
+// spec_number converts value with the global function name for %d, %i and %f, symbols are NaN
+fn spec_number<'js>(
+    inspector: &Inspector<'js>,
+    value: &Value<'js>,
+    name: &str,
+) -> rquickjs::Result<String> {
+    if value.is_symbol() || (name == "Number" && value.is_object()) {
+        return Ok("NaN".to_string());
+    }
+    if value.type_of() == Type::BigInt {
+        return Ok(format!("{}n", inspector.string(value)?));
+    }
+    let convert: Function = inspector.ctx.globals().get(name)?;
+    let number: Value = convert.call((value.clone(),))?;
+    inspector.number(&number)
+}
+
+// format_spec returns the substitution of value for the format specifier
+fn format_spec<'js>(
+    inspector: &mut Inspector<'js>,
+    spec: char,
+    value: &Value<'js>,
+) -> rquickjs::Result<String> {
+    let ctx = inspector.ctx.clone();
+    match spec {
+        's' => match value.type_of() {
+            Type::String | Type::Symbol => inspector.string(value),
+            Type::BigInt => Ok(format!("{}n", inspector.string(value)?)),
+            Type::Int | Type::Float => inspector.number(value),
+            _ if value.is_object() && !value.is_function() => inspect(&ctx, value, 0),
+            _ => inspector.inspect(value, 0),
+        },
+        'd' => spec_number(inspector, value, "Number"),
+        'i' => spec_number(inspector, value, "parseInt"),
+        'f' => spec_number(inspector, value, "parseFloat"),
+        'j' => match ctx.json_stringify(value.clone()) {
+            Ok(Some(json)) => json.to_string(),
+            Ok(None) => Ok("undefined".to_string()),
+            // JSON.stringify throws TypeError for the cyclic objects
+            Err(rquickjs::Error::Exception) => {
+                ctx.catch();
+                Ok("[Circular]".to_string())
+            }
+            Err(e) => Err(e),
+        },
+        'o' => inspect(&ctx, value, OBJECT_FORMAT_DEPTH),
+        'O' => inspector.inspect(value, 0),
+        // %c is CSS in the browsers, it is consumed without output
+        _ => Ok(String::new()),
+    }
+}
+
+/// format returns the message of console args like util.format,
+/// the first string arg can have format specifiers %s %d %i %f %j %o %O %c and %%
+pub fn format<'js>(ctx: &Ctx<'js>, args: &[Value<'js>]) -> rquickjs::Result<String> {
+    let mut inspector = Inspector::new(ctx, DEFAULT_DEPTH)?;
+    let mut message = String::new();
+    let mut rest = 0;
+    if let Some(template) = args.first().and_then(|arg| arg.as_string()) {
+        let template = template.to_string()?;
+        rest = 1;
+        if args.len() == 1 {
+            return Ok(template);
+        }
+        let mut chars = template.chars().peekable();
+        while let Some(c) = chars.next() {
+            let spec = match (c, chars.peek()) {
+                ('%', Some(&'%')) => {
+                    chars.next();
+                    message.push('%');
+                    continue;
+                }
+                ('%', Some(&spec)) if "sdifjoOc".contains(spec) && rest < args.len() => spec,
+                _ => {
+                    message.push(c);
+                    continue;
+                }
+            };
+            chars.next();
+            message.push_str(&format_spec(&mut inspector, spec, &args[rest])?);
+            rest += 1;
+        }
+    }
+    for arg in &args[rest..] {
+        if !message.is_empty() || rest > 0 {
+            message.push(' ');
+        }
+        match arg.as_string() {
+            Some(text) => message.push_str(&text.to_string()?),
+            None => message.push_str(&inspector.inspect(arg, 0)?),
+        }
+    }
+    Ok(message)
+}
+
+/// build util object that used export to globalThis
+pub fn build<'js>(ctx: Ctx<'js>) -> rquickjs::Result<Object<'js>> {
+    let util = Object::new(ctx.clone())?;
+
+    // format(...args) returns the formatted message like console.log
+    let format_callback = Function::new(
+        ctx.clone(),
+        |cx: Ctx<'js>, args: Rest<Value<'js>>| -> Result<String, rquickjs::Error> {
+            format(&cx, &args)
+        },
+    )?;
+
+    // inspect(value, depth) returns the string representation of value, negative depth is unlimited
+    let inspect_callback = Function::new(
+        ctx.clone(),
+        |cx: Ctx<'js>, args: Rest<Value<'js>>| -> Result<String, rquickjs::Error> {
+            check_args(&args, 1)?;
+            let depth = match args.get(1).and_then(|depth| depth.as_number()) {
+                Some(depth) if depth < 0.0 || depth.is_infinite() => usize::MAX,
+                Some(depth) => depth as usize,
+                None => DEFAULT_DEPTH,
+            };
+            inspect(&cx, &args[0], depth)
+        },
+    )?;
+
+    util.set("format", format_callback)?;
+    util.set("inspect", inspect_callback)?;
+    Ok(util)
+}
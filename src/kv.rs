@@ -0,0 +1,297 @@
+use crate::bindings::{self, encode_segment};
+use crate::hostcall::{arg_to_bytes, arg_to_string, check_args, to_js_error};
+use anyhow::{anyhow, Result};
+use once_cell::sync::Lazy;
+use rquickjs::{prelude::Rest, Array, ArrayBuffer, Ctx, FromJs, Function, Object, Value};
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// The limits follow the KV namespace limits of the platform
+const MAX_KEY_SIZE: usize = 512;
+const MAX_VALUE_SIZE: usize = 25 * 1024 * 1024;
+const MAX_METADATA_SIZE: usize = 1024;
+const MAX_LIST_LIMIT: u32 = 1000;
+
+// METADATA_HEADER and EXPIRATION_HEADER carry the metadata and the expiration time of a value
+const METADATA_HEADER: &str = "x-land-kv-metadata";
+const EXPIRATION_HEADER: &str = "x-land-kv-expiration";
+
+// MEMORY is the in-instance KV used when the bindings service is not configured,
+// it is not shared by other instances
+static MEMORY: Lazy<Mutex<BTreeMap<(String, String), KvEntry>>> =
+    Lazy::new(|| Mutex::new(BTreeMap::new()));
+
+/// KvEntry is a stored value, metadata is the encoded JSON string from JS,
+/// expiration is the unix time in seconds, 0 means it never expires
+#[derive(Debug, Clone, Default)]
+pub struct KvEntry {
+    pub value: Vec<u8>,
+    pub metadata: Option<String>,
+    pub expiration: u64,
+}
+
+impl KvEntry {
+    fn is_expired(&self) -> bool {
+        self.expiration != 0 && self.expiration <= now_secs()
+    }
+}
+
+/// PutOptions is the options of kv_put
+#[derive(Debug, Default)]
+pub struct PutOptions {
+    pub metadata: Option<String>,
+    pub expiration: u64,
+}
+
+impl<'js> FromJs<'js> for PutOptions {
+    fn from_js(ctx: &Ctx<'js>, value: Value<'js>) -> rquickjs::Result<Self> {
+        if !value.is_object() {
+            return Err(ctx.throw(
+                rquickjs::String::from_str(ctx.clone(), "PutOptions need from an object")?
+                    .into_value(),
+            ));
+        }
+        let options_object = value.as_object().unwrap();
+        let metadata: Value = options_object.get("metadata")?;
+        let expiration: Value = options_object.get("expiration")?;
+        Ok(Self {
+            metadata: metadata.as_string().map(|m| m.to_string()).transpose()?,
+            expiration: expiration.as_number().unwrap_or(0.0).max(0.0) as u64,
+        })
+    }
+}
+
+/// ListOptions is the options of kv_list
+#[derive(Debug, Default)]
+pub struct ListOptions {
+    pub prefix: String,
+    pub limit: u32,
+    pub cursor: String,
+}
+
+impl<'js> FromJs<'js> for ListOptions {
+    fn from_js(ctx: &Ctx<'js>, value: Value<'js>) -> rquickjs::Result<Self> {
+        if !value.is_object() {
+            return Err(ctx.throw(
+                rquickjs::String::from_str(ctx.clone(), "ListOptions need from an object")?
+                    .into_value(),
+            ));
+        }
+        let options_object = value.as_object().unwrap();
+        let prefix: Value = options_object.get("prefix")?;
+        let limit: Value = options_object.get("limit")?;
+        let cursor: Value = options_object.get("cursor")?;
+        Ok(Self {
+            prefix: arg_to_string(&prefix).unwrap_or_default(),
+            limit: (limit.as_number().unwrap_or(MAX_LIST_LIMIT as f64) as u32)
+                .clamp(1, MAX_LIST_LIMIT),
+            cursor: arg_to_string(&cursor).unwrap_or_default(),
+        })
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn check_key(key: &str) -> Result<()> {
+    if key.is_empty() || key == "." || key == ".." {
+        return Err(anyhow!("KV key '{}' is invalid", key));
+    }
+    if key.len() > MAX_KEY_SIZE {
+        return Err(anyhow!("KV key is longer than {} bytes", MAX_KEY_SIZE));
+    }
+    Ok(())
+}
+
+fn kv_path(namespace: &str, key: &str) -> String {
+    format!("/kv/{}/{}", encode_segment(namespace), encode_segment(key))
+}
+
+/// get returns the value of key in namespace, None if it is missing or expired
+pub fn get(namespace: &str, key: &str) -> Result<Option<KvEntry>> {
+    check_key(key)?;
+    if bindings::endpoint().is_none() {
+        let memory = MEMORY.lock().unwrap();
+        let entry = memory.get(&(namespace.to_string(), key.to_string()));
+        return Ok(entry.filter(|entry| !entry.is_expired()).cloned());
+    }
+    let response = bindings::call("GET", &kv_path(namespace, key), &[], None)?;
+    if response.status == 404 {
+        return Ok(None);
+    }
+    Ok(Some(KvEntry {
+        metadata: response.header(METADATA_HEADER).map(|m| m.to_string()),
+        expiration: response
+            .header(EXPIRATION_HEADER)
+            .and_then(|e| e.parse().ok())
+            .unwrap_or(0),
+        value: response.body,
+    }))
+}
+
+/// put stores value as key in namespace
+pub fn put(namespace: &str, key: &str, value: Vec<u8>, options: PutOptions) -> Result<()> {
+    check_key(key)?;
+    if value.len() > MAX_VALUE_SIZE {
+        return Err(anyhow!("KV value is larger than {} bytes", MAX_VALUE_SIZE));
+    }
+    if options.metadata.as_ref().map(|m| m.len()).unwrap_or(0) > MAX_METADATA_SIZE {
+        return Err(anyhow!(
+            "KV metadata is larger than {} bytes",
+            MAX_METADATA_SIZE
+        ));
+    }
+    if bindings::endpoint().is_none() {
+        MEMORY.lock().unwrap().insert(
+            (namespace.to_string(), key.to_string()),
+            KvEntry {
+                value,
+                metadata: options.metadata,
+                expiration: options.expiration,
+            },
+        );
+        return Ok(());
+    }
+    let mut headers = vec![(
+        EXPIRATION_HEADER.to_string(),
+        options.expiration.to_string(),
+    )];
+    if let Some(metadata) = options.metadata {
+        headers.push((METADATA_HEADER.to_string(), metadata));
+    }
+    bindings::call("PUT", &kv_path(namespace, key), &headers, Some(value))?;
+    Ok(())
+}
+
+/// delete removes key from namespace
+pub fn delete(namespace: &str, key: &str) -> Result<()> {
+    check_key(key)?;
+    if bindings::endpoint().is_none() {
+        MEMORY
+            .lock()
+            .unwrap()
+            .remove(&(namespace.to_string(), key.to_string()));
+        return Ok(());
+    }
+    bindings::call("DELETE", &kv_path(namespace, key), &[], None)?;
+    Ok(())
+}
+
+/// list returns {keys: [{name, expiration, metadata}], list_complete, cursor} of namespace
+pub fn list<'js>(ctx: &Ctx<'js>, namespace: &str, options: ListOptions) -> Result<Value<'js>> {
+    if bindings::endpoint().is_some() {
+        let path = format!(
+            "/kv/{}?prefix={}&limit={}&cursor={}",
+            encode_segment(namespace),
+            encode_segment(&options.prefix),
+            options.limit,
+            encode_segment(&options.cursor)
+        );
+        let response = bindings::call("GET", &path, &[], None)?;
+        return Ok(ctx.json_parse(response.body)?);
+    }
+    let memory = MEMORY.lock().unwrap();
+    let keys = Array::new(ctx.clone())?;
+    let mut listed: Vec<&String> = vec![];
+    let mut list_complete = true;
+    let entries = memory
+        .range((namespace.to_string(), options.cursor.clone())..)
+        .take_while(|((ns, _), _)| ns == namespace)
+        .filter(|((_, key), entry)| {
+            key.starts_with(&options.prefix) && *key != options.cursor && !entry.is_expired()
+        });
+    for ((_, key), entry) in entries {
+        if listed.len() == options.limit as usize {
+            list_complete = false;
+            break;
+        }
+        let item = Object::new(ctx.clone())?;
+        item.set("name", key.as_str())?;
+        if entry.expiration != 0 {
+            item.set("expiration", entry.expiration)?;
+        }
+        if let Some(metadata) = &entry.metadata {
+            item.set("metadata", metadata.as_str())?;
+        }
+        keys.set(listed.len(), item)?;
+        listed.push(key);
+    }
+    let result = Object::new(ctx.clone())?;
+    result.set("keys", keys)?;
+    result.set("list_complete", list_complete)?;
+    // the cursor is the last listed key, the next page starts after it
+    if !list_complete {
+        result.set("cursor", listed.last().map(|key| key.as_str()))?;
+    }
+    Ok(Value::from_object(result))
+}
+
+/// build the KV hostcalls, they are set on the hostcall object
+pub fn build<'js>(ctx: Ctx<'js>, hostcall: &Object<'js>) -> rquickjs::Result<()> {
+    // kv_get(namespace, key) returns {value: ArrayBuffer, metadata, expiration} or null
+    let get_callback = Function::new(
+        ctx.clone(),
+        |cx: Ctx<'js>, args: Rest<Value<'js>>| -> Result<Value<'js>, rquickjs::Error> {
+            check_args(&args, 2)?;
+            let namespace = arg_to_string(&args[0]).map_err(|e| to_js_error(cx.clone(), e))?;
+            let key = arg_to_string(&args[1]).map_err(|e| to_js_error(cx.clone(), e))?;
+            let entry = match get(&namespace, &key).map_err(|e| to_js_error(cx.clone(), e))? {
+                Some(entry) => entry,
+                None => return Ok(Value::new_null(cx.clone())),
+            };
+            let result = Object::new(cx.clone())?;
+            result.set("value", ArrayBuffer::new(cx.clone(), entry.value)?)?;
+            result.set("metadata", entry.metadata)?;
+            if entry.expiration != 0 {
+                result.set("expiration", entry.expiration)?;
+            }
+            Ok::<_, rquickjs::Error>(Value::from_object(result))
+        },
+    )?;
+
+    // kv_put(namespace, key, value, {metadata, expiration}) stores value
+    let put_callback = Function::new(
+        ctx.clone(),
+        |cx: Ctx<'js>, args: Rest<Value<'js>>| -> Result<(), rquickjs::Error> {
+            check_args(&args, 4)?;
+            let namespace = arg_to_string(&args[0]).map_err(|e| to_js_error(cx.clone(), e))?;
+            let key = arg_to_string(&args[1]).map_err(|e| to_js_error(cx.clone(), e))?;
+            let value = arg_to_bytes(&args[2]).map_err(|e| to_js_error(cx.clone(), e))?;
+            let options = PutOptions::from_js(&cx, args[3].clone())?;
+            put(&namespace, &key, value, options).map_err(|e| to_js_error(cx.clone(), e))
+        },
+    )?;
+
+    // kv_delete(namespace, key) removes key
+    let delete_callback = Function::new(
+        ctx.clone(),
+        |cx: Ctx<'js>, args: Rest<Value<'js>>| -> Result<(), rquickjs::Error> {
+            check_args(&args, 2)?;
+            let namespace = arg_to_string(&args[0]).map_err(|e| to_js_error(cx.clone(), e))?;
+            let key = arg_to_string(&args[1]).map_err(|e| to_js_error(cx.clone(), e))?;
+            delete(&namespace, &key).map_err(|e| to_js_error(cx.clone(), e))
+        },
+    )?;
+
+    // kv_list(namespace, {prefix, limit, cursor}) returns {keys, list_complete, cursor}
+    let list_callback = Function::new(
+        ctx.clone(),
+        |cx: Ctx<'js>, args: Rest<Value<'js>>| -> Result<Value<'js>, rquickjs::Error> {
+            check_args(&args, 2)?;
+            let namespace = arg_to_string(&args[0]).map_err(|e| to_js_error(cx.clone(), e))?;
+            let options = ListOptions::from_js(&cx, args[1].clone())?;
+            list(&cx, &namespace, options).map_err(|e| to_js_error(cx.clone(), e))
+        },
+    )?;
+
+    hostcall.set("kv_get", get_callback)?;
+    hostcall.set("kv_put", put_callback)?;
+    hostcall.set("kv_delete", delete_callback)?;
+    hostcall.set("kv_list", list_callback)?;
+    Ok(())
+}
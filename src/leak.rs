@@ -0,0 +1,164 @@
+use crate::console;
+use rquickjs::{Context, Ctx, Value};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+
+// LEAK_CHECK_ENV enables the leak detector in development, it is the number of requests the retained heap
+// must keep growing to warn, or "on" for DEFAULT_REQUESTS. It runs the garbage collector after every request,
+// so it should not be enabled in production.
+const LEAK_CHECK_ENV: &str = "LAND_LEAK_CHECK";
+const DEFAULT_REQUESTS: usize = 5;
+
+/// LEAK_HEADER is the debug response header of a suspected leak
+pub const LEAK_HEADER: &str = "x-land-leak";
+
+// MAX_GLOBALS limits the global properties that are compared
+const MAX_GLOBALS: usize = 200;
+
+// Snapshot is the retained heap after the garbage collector at the end of a request
+struct Snapshot {
+    heap: i64,
+    objects: i64,
+    strings: i64,
+    properties: i64,
+    globals: HashMap<String, usize>,
+}
+
+// Detector keeps the snapshots of the latest requests, JS_CONTEXT persists across the requests
+// of an instance, so a closure or a global cache that is never freed grows the heap of every request
+struct Detector {
+    snapshots: VecDeque<Snapshot>,
+    warning: Option<String>,
+}
+
+static DETECTOR: Mutex<Detector> = Mutex::new(Detector {
+    snapshots: VecDeque::new(),
+    warning: None,
+});
+
+fn requests() -> Option<usize> {
+    match crate::env::var(LEAK_CHECK_ENV)?.trim() {
+        "on" => Some(DEFAULT_REQUESTS),
+        value => value.parse().ok().filter(|requests| *requests > 0),
+    }
+}
+
+// size returns the entries of a global value, the length of arrays and strings,
+// the size of collections such as Map and Set, or the own properties of objects
+fn size(value: &Value) -> usize {
+    if let Some(string) = value.as_string() {
+        return string.to_string().map(|s| s.len()).unwrap_or_default();
+    }
+    if let Some(array) = value.as_array() {
+        return array.len();
+    }
+    if value.is_function() {
+        return 0;
+    }
+    match value.as_object() {
+        Some(object) => match object.get::<_, Option<f64>>("size") {
+            Ok(Some(size)) if size >= 0.0 => size as usize,
+            _ => object.keys::<String>().count(),
+        },
+        None => 0,
+    }
+}
+
+fn global_sizes(ctx: &Ctx) -> HashMap<String, usize> {
+    let globals = ctx.globals();
+    globals
+        .keys::<String>()
+        .filter_map(|key| key.ok())
+        .take(MAX_GLOBALS)
+        .filter_map(|key| {
+            let value: Value = globals.get(key.as_str()).ok()?;
+            Some((key, size(&value)))
+        })
+        .collect()
+}
+
+// grew returns true if a global property never shrank and grew over the snapshots
+fn grew(snapshots: &[Snapshot], name: &str) -> bool {
+    let size = |snapshot: &Snapshot| snapshot.globals.get(name).copied().unwrap_or_default();
+    snapshots
+        .windows(2)
+        .all(|pair| size(&pair[1]) >= size(&pair[0]))
+        && size(&snapshots[snapshots.len() - 1]) > size(&snapshots[0])
+}
+
+/// check snapshots the retained heap of the request and warns if it kept growing over the latest requests.
+/// It is called out of the context when the response is ready, the runtime is locked in it.
+pub fn check(context: &Context) {
+    let requests = match requests() {
+        Some(requests) => requests,
+        None => return,
+    };
+    let runtime = context.runtime();
+    runtime.run_gc();
+    let usage = runtime.memory_usage();
+    let globals = context.with(|ctx| global_sizes(&ctx));
+
+    let mut detector = DETECTOR.lock().unwrap();
+    detector.snapshots.push_back(Snapshot {
+        heap: usage.memory_used_size,
+        objects: usage.obj_count,
+        strings: usage.str_count,
+        properties: usage.prop_count,
+        globals,
+    });
+    while detector.snapshots.len() > requests + 1 {
+        detector.snapshots.pop_front();
+    }
+    detector.warning = None;
+    if detector.snapshots.len() <= requests {
+        return;
+    }
+    let snapshots: &[Snapshot] = detector.snapshots.make_contiguous();
+    if !snapshots.windows(2).all(|pair| pair[1].heap > pair[0].heap) {
+        return;
+    }
+    let (first, last) = (&snapshots[0], &snapshots[snapshots.len() - 1]);
+    let mut grown: Vec<(String, usize)> = last
+        .globals
+        .iter()
+        .filter(|(name, _)| grew(snapshots, name))
+        .map(|(name, size)| (name.clone(), size - first.globals.get(name).unwrap_or(&0)))
+        .collect();
+    grown.sort();
+    let globals = grown
+        .iter()
+        .map(|(name, size)| format!("{} (+{})", name, size))
+        .collect::<Vec<_>>()
+        .join(", ");
+    console::log(
+        console::Level::Warn,
+        &format!(
+            "Memory leak suspected: the retained heap grew in {} requests from {} to {} bytes, \
+             objects {:+}, strings {:+}, properties {:+}, growing globals: {}",
+            requests,
+            first.heap,
+            last.heap,
+            last.objects - first.objects,
+            last.strings - first.strings,
+            last.properties - first.properties,
+            if globals.is_empty() { "none" } else { &globals },
+        ),
+    );
+    let mut warning = format!(
+        "heap={:+}, objects={:+}, requests={}",
+        last.heap - first.heap,
+        last.objects - first.objects,
+        requests
+    );
+    if !grown.is_empty() {
+        let names: Vec<&str> = grown.iter().map(|(name, _)| name.as_str()).collect();
+        warning.push_str(&format!(", globals={}", names.join(" ")));
+    }
+    detector.warning = Some(warning);
+}
+
+/// header returns the x-land-leak header value of the request if a leak is suspected,
+/// such as "heap=+40960, objects=+3, requests=5, globals=cache"
+pub fn header() -> Option<String> {
+    DETECTOR.lock().unwrap().warning.clone()
+}
@@ -5,13 +5,36 @@ use once_cell::sync::OnceCell;
 use rquickjs::{
     function::Args,
     loader::{BuiltinLoader, BuiltinResolver},
-    Context, FromJs, IntoJs, Module, Runtime, Undefined, Value,
+    Context, FromJs, IntoJs, Module, Object, Runtime, Undefined, Value,
 };
 use std::io::Read;
 
+mod bindings;
+mod bucket;
+mod cache;
+mod compression;
 mod console;
+mod content_encoding;
+mod crypto;
+mod debug;
+mod encoding;
 mod entity;
+mod env;
+mod error_page;
 mod hostcall;
+mod html_rewriter;
+mod inspect;
+mod kv;
+mod leak;
+mod metrics;
+mod password;
+mod performance;
+mod queue;
+mod random;
+mod rejection;
+mod source_map;
+mod sql;
+mod trace;
 
 static PKG_VERSION: &str = env!("CARGO_PKG_VERSION");
 
@@ -34,23 +57,17 @@ pub extern "C" fn init() {
 }
 
 fn export_js_error(context: Context, err: rquickjs::Error) -> anyhow::Error {
-    if err.is_exception() {
-        let message = context
-            .with(|ctx| {
-                let exception = ctx.catch();
-                let exception = exception.as_exception().unwrap();
-                let message = format!(
-                    "Exception: {}\n{}",
-                    exception.message().unwrap_or_default(),
-                    exception.stack().unwrap_or_default()
-                );
-                Ok::<String, rquickjs::Error>(message)
-            })
-            .unwrap();
-        return anyhow!(message);
+    let failure = error_page::Failure::from_exception(&context, err);
+    anyhow!("Exception: {}", failure.text().trim_end())
+}
+
+// execute_job runs a pending job, the exception of a job is logged instead of dropped
+fn execute_job(runtime: &Runtime) {
+    match runtime.execute_pending_job() {
+        Ok(true) => metrics::record_job(),
+        Ok(false) => {}
+        Err(err) => rejection::job_error(err.0),
     }
-    let message = format!("Error: {:?}", err);
-    anyhow!(message)
 }
 
 fn init_js_context() -> Result<()> {
@@ -59,23 +76,58 @@ fn init_js_context() -> Result<()> {
 
     let mut user_script = String::new();
     std::io::stdin().read_to_string(&mut user_script)?;
+    error_page::init(&user_script);
 
-    // 0. load user js code
-    let resolver = BuiltinResolver::default().with_module("user.js");
-    let loader = BuiltinLoader::default().with_module("user.js", user_script);
+    // 0. load user js code and builtin modules
+    let resolver = BuiltinResolver::default()
+        .with_module("user.js")
+        .with_module("land:password")
+        .with_module("land:env")
+        .with_module("land:util");
+    let loader = BuiltinLoader::default()
+        .with_module("user.js", user_script)
+        .with_module("land:password", password::MODULE)
+        .with_module("land:env", env::MODULE)
+        .with_module("land:util", inspect::MODULE);
     runtime.set_loader(resolver, loader);
 
+    random::init()?;
+    rejection::install(&runtime);
+
     // 1. load vendor js code
     let res = context.with(|ctx| {
         // add global modules
         let global = ctx.globals();
+        let text_encoding = encoding::build(ctx.clone())?;
+        global.set("textEncoding", text_encoding)?;
+        let compression = compression::build(ctx.clone())?;
+        global.set("compression", compression)?;
+        let html_rewriter = html_rewriter::build(ctx.clone())?;
+        global.set("htmlRewriter", html_rewriter)?;
         let console = console::build(ctx.clone())?;
         global.set("console", console)?;
         let hostcall = hostcall::build(ctx.clone())?;
         global.set("hostcall", hostcall)?;
+        let crypto = crypto::build(ctx.clone())?;
+        global.set("landCrypto", crypto)?;
+        let password = password::build(ctx.clone())?;
+        global.set("landPassword", password)?;
+        let util = inspect::build(ctx.clone())?;
+        global.set("landUtil", util)?;
+        let math: Object = global.get("Math")?;
+        math.set("random", random::build(ctx.clone())?)?;
 
         ctx.eval(JS_VENDOR)?;
 
+        // the builtin modules capture their native objects and remove them from globalThis
+        // before the user script runs, the user imports get the same module instances
+        Module::evaluate(
+            ctx.clone(),
+            "builtin",
+            "import 'land:password'; import 'land:env'; import 'land:util';",
+        )?
+        .finish()?;
+
         // import user js module and export to globalThis
         Module::evaluate(
             ctx.clone(),
@@ -94,8 +146,12 @@ fn init_js_context() -> Result<()> {
 
     // waiting pending tasks
     while runtime.is_job_pending() {
-        let _ = runtime.execute_pending_job();
+        execute_job(&runtime);
     }
+    rejection::report(&context);
+
+    // Math.random state used in init must not be shared by every instance restored from the snapshot
+    random::reset();
 
     JS_CONTEXT
         .set(context)
@@ -103,23 +159,91 @@ fn init_js_context() -> Result<()> {
     Ok(())
 }
 
-use land_sdk::http::{error_response, Body, Error, Request, Response};
+use land_sdk::http::{Body, Error, Request, Response};
 use land_sdk::http_main;
 
 #[http_main]
 pub fn handle_request(req: Request) -> Result<Response, Error> {
-    let resp = match handle_js_request(req) {
+    performance::begin_request();
+    password::begin_request();
+    let context = JS_CONTEXT.get().unwrap();
+    let runtime = context.runtime();
+    // the Server-Timing header is decided once, so the error pages and the queue deliveries have it too
+    context.with(|ctx| performance::enable(&ctx));
+    // the handler span continues the trace of the caller, or starts a new trace
+    let header = |name: &str| {
+        req.headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.to_string())
+    };
+    trace::begin_request(
+        header(trace::TRACEPARENT_HEADER).as_deref(),
+        header(trace::TRACESTATE_HEADER).as_deref(),
+        req.method().as_str(),
+        &req.uri().to_string(),
+    );
+    // the console records of the request are correlated by the request id
+    console::begin_request(
+        req.headers()
+            .get(console::REQUEST_ID_HEADER)
+            .and_then(|value| value.to_str().ok()),
+    );
+    // the debug headers are returned for the requests signed with the debug secret
+    debug::begin_request(
+        header(debug::DEBUG_HEADER).as_deref(),
+        req.method().as_str(),
+        req.uri().path(),
+    );
+    // the heap is sampled for the debug headers too, so the metrics begin after the debug mode is known
+    metrics::begin_request(runtime);
+    // the uncaught errors are rendered as HTML pages in development mode if the client accepts them
+    let accept = header("accept");
+    // queue deliveries are sent by the platform to the queue handler instead of the fetch handler
+    let result = if req.headers().contains_key(queue::DELIVERY_HEADER) {
+        handle_js_queue(req)
+    } else {
+        handle_js_request(req, accept.as_deref())
+    };
+    let mut resp = match result {
         Ok(response) => response,
-        Err(err) => {
-            println!("handle_js_request error: {:?}", err);
-            error_response(StatusCode::INTERNAL_SERVER_ERROR, err.to_string())
-        }
+        Err(err) => error_page::Failure::from_error(&err).into_response(accept.as_deref()),
     };
+    insert_header(
+        &mut resp,
+        performance::SERVER_TIMING_HEADER,
+        performance::server_timing(),
+    );
+    // the rejections that are still unhandled when the response is ready are reported
+    rejection::report(context);
+    // the heap is sampled once for every response, such as the error pages, out of the context,
+    // the runtime is locked in it. The leak detector runs the garbage collector after the sample.
+    metrics::finish_script(runtime);
+    leak::check(context);
+    insert_header(&mut resp, metrics::METRICS_HEADER, metrics::header());
+    insert_header(&mut resp, leak::LEAK_HEADER, leak::header());
+    debug::end_request(&mut resp);
+    trace::end_request(resp.status().as_u16());
+    metrics::end_request(resp.status().as_u16());
+    console::end_request();
     Ok(resp)
 }
 
-fn handle_js_request(req: Request) -> Result<Response, Error> {
+// insert_header adds a header of the runtime to the response, such as the metrics of the request
+fn insert_header(resp: &mut Response, name: &'static str, value: Option<String>) {
+    if let Some(value) = value.and_then(|value| http::HeaderValue::from_str(&value).ok()) {
+        resp.headers_mut()
+            .insert(http::HeaderName::from_static(name), value);
+    }
+}
+
+fn handle_js_request(req: Request, accept: Option<&str>) -> Result<Response, Error> {
     let context = JS_CONTEXT.get().unwrap();
+    let accept_encoding = req
+        .headers()
+        .get("accept-encoding")
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.to_string());
 
     let response_result = context.with(|ctx| {
         // 0. getCallHandler
@@ -144,14 +268,14 @@ fn handle_js_request(req: Request) -> Result<Response, Error> {
         Ok::<_, rquickjs::Error>(Undefined)
     });
     if let Err(err) = response_result {
-        return Err(err.into());
+        return Ok(error_page::Failure::from_exception(context, err).into_response(accept));
     }
 
     // 3. waiting pending tasks, waiting promises
     let runtime = context.runtime();
     while runtime.is_job_pending() {
         // println!("waiting pending tasks");
-        let _ = runtime.execute_pending_job();
+        execute_job(runtime);
         let res = context.with(|ctx| {
             let response_object: Value = ctx.globals().get("globalResponse")?;
 
@@ -160,17 +284,107 @@ fn handle_js_request(req: Request) -> Result<Response, Error> {
             if response_object.is_null() {
                 return Ok::<_, rquickjs::Error>(None);
             }
-            let js_response = JsHttpObject::from_js(&ctx, response_object)?;
-            let http_response = js_response.into_response();
-            Ok::<_, rquickjs::Error>(Some(http_response))
+            // the handler is rejected, the error is rendered as the 500 response
+            if let Some(object) = response_object.as_object() {
+                if object.contains_key("error")? {
+                    let error: Value = object.get("error")?;
+                    return Ok(Some(Err(error_page::Failure::from_js(&ctx, error))));
+                }
+            }
+            let mut js_response = JsHttpObject::from_js(&ctx, response_object)?;
+            if content_encoding::enabled(&ctx) {
+                if let Err(err) =
+                    content_encoding::apply(accept_encoding.as_deref(), &mut js_response)
+                {
+                    console::log(
+                        console::Level::Warn,
+                        &format!("compress response error: {:?}", err),
+                    );
+                }
+            }
+            Ok::<_, rquickjs::Error>(Some(Ok(js_response)))
         });
         if let Err(err) = res {
-            return Err(err.into());
+            return Ok(error_page::Failure::from_exception(context, err).into_response(accept));
         }
         // if response is not null, return response
-        if let Some(response) = res.unwrap() {
-            return Ok(response);
+        if let Some(outcome) = res.unwrap() {
+            return Ok(match outcome {
+                Ok(js_response) => js_response.into_response(),
+                Err(failure) => failure.into_response(accept),
+            });
         }
     }
     Err(anyhow!("handle_js_request no response"))
 }
+
+fn handle_js_queue(req: Request) -> Result<Response, Error> {
+    let context = JS_CONTEXT.get().unwrap();
+    let signature = req
+        .headers()
+        .get(queue::SIGNATURE_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.to_string());
+    let delivery = bindings::read_body(req.body().body_handle())?;
+    // the delivery header can be sent by any client, only the signed deliveries of the platform run the handler
+    if !queue::verify(signature.as_deref(), &delivery) {
+        console::log(console::Level::Warn, "queue delivery signature is invalid");
+        let response = http::Response::builder()
+            .status(StatusCode::FORBIDDEN)
+            .body(Body::from("Forbidden".as_bytes()))?;
+        return Ok(response);
+    }
+
+    let call_result = context.with(|ctx| {
+        // 0. getCallQueueHandler
+        let call_queue_handler: Value = ctx.globals().get("callQueueHandler")?;
+        if !call_queue_handler.is_function() {
+            let err = ctx.throw(
+                rquickjs::String::from_str(ctx.clone(), "queue handler is not a function")?
+                    .into_value(),
+            );
+            return Err(err);
+        }
+        let call_queue_handler = call_queue_handler.as_function().unwrap();
+
+        // 1. build batch object
+        let batch = match queue::batch_into_js(&ctx, &delivery) {
+            Ok(batch) => batch,
+            Err(err) => return Err(hostcall::to_js_error(ctx.clone(), err)),
+        };
+        let mut args = Args::new(ctx.clone(), 1);
+        args.push_arg(batch)?;
+        call_queue_handler.call_arg::<Value>(args)?;
+        Ok::<_, rquickjs::Error>(Undefined)
+    });
+    if let Err(err) = call_result {
+        return Err(export_js_error(context.clone(), err));
+    }
+
+    // 2. waiting pending tasks until the handler reports the outcome of every message
+    let runtime = context.runtime();
+    while runtime.is_job_pending() {
+        execute_job(runtime);
+        let res = context.with(|ctx| {
+            let result: Value = ctx.globals().get("globalQueueResult")?;
+            if result.is_null() {
+                return Ok::<_, rquickjs::Error>(None);
+            }
+            ctx.json_stringify(result)?
+                .map(|json| json.to_string())
+                .transpose()
+        });
+        match res {
+            Ok(Some(json)) => {
+                let response = http::Response::builder()
+                    .status(StatusCode::OK)
+                    .header("content-type", "application/json")
+                    .body(Body::from(json.as_bytes()))?;
+                return Ok(response);
+            }
+            Ok(None) => {}
+            Err(err) => return Err(err.into()),
+        }
+    }
+    Err(anyhow!("handle_js_queue no result"))
+}
@@ -0,0 +1,176 @@
+use crate::bindings;
+use rquickjs::{Ctx, Function, Object, Runtime, Value};
+use serde_json::{json, Value as JsonValue};
+use std::sync::Mutex;
+use std::time::Duration;
+
+// METRICS_ENV selects where the request metrics go, a comma list of "header" for the x-land-metrics
+// response header and "host" for the bindings service. The metrics are not reported by default,
+// the scripts can read the counters with the request_metrics hostcall anyway. The heap is sampled
+// only if the metrics are reported or the debug headers are enabled, a sample walks the whole heap.
+const METRICS_ENV: &str = "LAND_METRICS";
+
+/// METRICS_HEADER is the debug response header of the request metrics
+pub const METRICS_HEADER: &str = "x-land-metrics";
+
+/// Metrics are the counters of the running request
+#[derive(Debug, Default, Clone)]
+struct Metrics {
+    // sampled is true if the heap is sampled, the heap values are 0 otherwise
+    sampled: bool,
+    heap_before: i64,
+    heap_after: Option<i64>,
+    fetch_count: u32,
+    fetch_duration: Duration,
+    body_read_bytes: u64,
+    response_body_bytes: u64,
+    jobs: u32,
+}
+
+impl Metrics {
+    fn to_json(&self) -> JsonValue {
+        json!({
+            "heap_before": self.heap_before,
+            "heap_after": self.heap_after.unwrap_or(self.heap_before),
+            "fetch_count": self.fetch_count,
+            "fetch_ms": (self.fetch_duration.as_secs_f64() * 1000.0 * 1000.0).round() / 1000.0,
+            "body_read_bytes": self.body_read_bytes,
+            "response_body_bytes": self.response_body_bytes,
+            "jobs": self.jobs,
+        })
+    }
+}
+
+static METRICS: Mutex<Option<Metrics>> = Mutex::new(None);
+
+fn enabled(target: &str) -> bool {
+    crate::env::var(METRICS_ENV)
+        .is_some_and(|value| value.split(',').any(|item| item.trim() == target))
+}
+
+fn update(f: impl FnOnce(&mut Metrics)) {
+    if let Some(metrics) = METRICS.lock().unwrap().as_mut() {
+        f(metrics);
+    }
+}
+
+/// begin_request resets the counters, the heap usage before the handler runs is sampled
+/// if the metrics are reported or the debug headers of the request are enabled
+pub fn begin_request(runtime: &Runtime) {
+    let sampled = enabled("header") || enabled("host") || crate::debug::enabled();
+    *METRICS.lock().unwrap() = Some(Metrics {
+        sampled,
+        heap_before: if sampled {
+            runtime.memory_usage().memory_used_size
+        } else {
+            0
+        },
+        ..Default::default()
+    });
+}
+
+/// finish_script samples the heap after the handler if the heap is sampled,
+/// it is called once by handle_request when the response is ready
+pub fn finish_script(runtime: &Runtime) {
+    let sampled = METRICS
+        .lock()
+        .unwrap()
+        .as_ref()
+        .is_some_and(|metrics| metrics.sampled);
+    if !sampled {
+        return;
+    }
+    let heap_after = runtime.memory_usage().memory_used_size;
+    update(|metrics| metrics.heap_after = Some(heap_after));
+}
+
+/// record_fetch counts an outbound fetch and its duration
+pub fn record_fetch(duration: Duration) {
+    update(|metrics| {
+        metrics.fetch_count += 1;
+        metrics.fetch_duration += duration;
+    });
+}
+
+/// record_body_read counts the bytes read from a body handle
+pub fn record_body_read(bytes: usize) {
+    update(|metrics| metrics.body_read_bytes += bytes as u64);
+}
+
+/// record_response_body counts the bytes of the response body, the streamed bodies are not known
+pub fn record_response_body(bytes: usize) {
+    update(|metrics| metrics.response_body_bytes += bytes as u64);
+}
+
+/// record_job counts a pending job executed for the request, such as a promise reaction
+pub fn record_job() {
+    update(|metrics| metrics.jobs += 1);
+}
+
+/// snapshot returns the counters of the running request, None out of a request
+pub fn snapshot() -> Option<JsonValue> {
+    METRICS
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(|metrics| metrics.to_json())
+}
+
+/// header returns the x-land-metrics header value if it is enabled, such as "heap_before=1024, jobs=3"
+pub fn header() -> Option<String> {
+    if !enabled("header") {
+        return None;
+    }
+    let metrics = METRICS.lock().unwrap().clone()?;
+    let values = metrics.to_json();
+    let values = values.as_object()?;
+    Some(
+        values
+            .iter()
+            .map(|(key, value)| format!("{}={}", key, value))
+            .collect::<Vec<_>>()
+            .join(", "),
+    )
+}
+
+/// end_request sends the metrics of the request to the bindings service if it is enabled
+pub fn end_request(status: u16) {
+    let metrics = match METRICS.lock().unwrap().take() {
+        Some(metrics) => metrics,
+        None => return,
+    };
+    if !enabled("host") || bindings::endpoint().is_none() {
+        return;
+    }
+    let mut record = metrics.to_json();
+    record["status"] = json!(status);
+    record["script_id"] = json!(crate::env::var("LAND_SCRIPT_ID"));
+    let headers = vec![("content-type".to_string(), "application/json".to_string())];
+    if let Err(err) = bindings::call(
+        "POST",
+        "/metrics",
+        &headers,
+        Some(record.to_string().into_bytes()),
+    ) {
+        eprintln!("send request metrics error: {:?}", err);
+    }
+}
+
+/// build the metrics hostcalls, they are set on the hostcall object
+pub fn build<'js>(ctx: Ctx<'js>, hostcall: &Object<'js>) -> rquickjs::Result<()> {
+    // request_metrics() returns the counters of the running request, heap_after is null until the response
+    let request_metrics = Function::new(
+        ctx.clone(),
+        |cx: Ctx<'js>| -> Result<Value<'js>, rquickjs::Error> {
+            let metrics = METRICS.lock().unwrap().clone().unwrap_or_default();
+            let mut values = metrics.to_json();
+            if metrics.heap_after.is_none() {
+                values["heap_after"] = JsonValue::Null;
+            }
+            cx.json_parse(values.to_string())
+        },
+    )?;
+
+    hostcall.set("request_metrics", request_metrics)?;
+    Ok(())
+}
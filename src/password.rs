@@ -0,0 +1,297 @@
+use crate::hostcall::{arg_to_bytes, arg_to_string, check_args, to_js_error};
+use anyhow::{anyhow, Result};
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use rand_core::OsRng;
+use rquickjs::{prelude::Rest, Ctx, Function, Object, Value};
+use std::sync::Mutex;
+
+// The limits are static cost caps, they bound the CPU time and memory of a single hash or verify call.
+// verify checks the parameters stored in the hash too, so a crafted hash can not exceed them.
+// The calls of a request share REQUEST_BUDGET, a call that exceeds the rest of it fails before hashing.
+const ARGON2_MAX_MEMORY_COST: u32 = 65536; // KiB
+const ARGON2_MAX_TIME_COST: u32 = 10;
+const ARGON2_MAX_PARALLELISM: u32 = 4;
+const BCRYPT_MIN_COST: u32 = 4;
+const BCRYPT_MAX_COST: u32 = 14;
+const BCRYPT_MAX_PASSWORD_LENGTH: usize = 72;
+const SCRYPT_MAX_LOG_N: u8 = 16;
+const SCRYPT_MAX_R: u32 = 16;
+const SCRYPT_MAX_P: u32 = 4;
+// The cost of a call is the KiB of memory it processes, argon2id processes memory_cost KiB time_cost times,
+// scrypt processes 128 * r * 2^log_n bytes twice for each of p lanes. A bcrypt round is counted as 32 KiB.
+const REQUEST_BUDGET: u64 = 1 << 21;
+const BCRYPT_ROUND_COST: u64 = 32;
+
+static SPENT: Mutex<u64> = Mutex::new(0);
+
+/// begin_request resets the hash cost spent by the request
+pub fn begin_request() {
+    *SPENT.lock().unwrap() = 0;
+}
+
+fn charge(cost: u64) -> Result<()> {
+    let mut spent = SPENT.lock().unwrap();
+    if *spent + cost > REQUEST_BUDGET {
+        return Err(anyhow!(
+            "Password hashing budget of the request is exhausted, {} of {} spent",
+            *spent,
+            REQUEST_BUDGET
+        ));
+    }
+    *spent += cost;
+    Ok(())
+}
+
+/// MODULE is the source of the `land:password` module, it wraps the native password object
+pub static MODULE: &str = include_str!("../js-vendor/src/modules/password.js");
+
+/// argon2id_hash hashes password with argon2id and returns PHC string
+pub fn argon2id_hash(
+    password: &[u8],
+    memory_cost: u32,
+    time_cost: u32,
+    parallelism: u32,
+) -> Result<String> {
+    check_argon2_params(memory_cost, time_cost, parallelism)?;
+    charge(argon2_cost(memory_cost, time_cost))?;
+    let params = argon2::Params::new(memory_cost, time_cost, parallelism, None)
+        .map_err(|e| anyhow!("Invalid argon2id params: {}", e))?;
+    let hasher = argon2::Argon2::new(argon2::Algorithm::Argon2id, argon2::Version::V0x13, params);
+    let salt = SaltString::generate(&mut OsRng);
+    let hash = hasher
+        .hash_password(password, &salt)
+        .map_err(|e| anyhow!("argon2id hash failed: {}", e))?;
+    Ok(hash.to_string())
+}
+
+/// argon2id_verify verifies password against argon2id PHC string
+pub fn argon2id_verify(password: &[u8], hash: &str) -> Result<bool> {
+    let parsed = PasswordHash::new(hash).map_err(|e| anyhow!("Invalid argon2id hash: {}", e))?;
+    if parsed.algorithm != argon2::Algorithm::Argon2id.ident() {
+        return Err(anyhow!(
+            "Invalid argon2id hash: algorithm is {}",
+            parsed.algorithm
+        ));
+    }
+    let params =
+        argon2::Params::try_from(&parsed).map_err(|e| anyhow!("Invalid argon2id hash: {}", e))?;
+    check_argon2_params(params.m_cost(), params.t_cost(), params.p_cost())?;
+    charge(argon2_cost(params.m_cost(), params.t_cost()))?;
+    Ok(argon2::Argon2::default()
+        .verify_password(password, &parsed)
+        .is_ok())
+}
+
+fn check_argon2_params(memory_cost: u32, time_cost: u32, parallelism: u32) -> Result<()> {
+    if memory_cost > ARGON2_MAX_MEMORY_COST {
+        return Err(anyhow!(
+            "argon2id memoryCost {} exceeds {}",
+            memory_cost,
+            ARGON2_MAX_MEMORY_COST
+        ));
+    }
+    if time_cost > ARGON2_MAX_TIME_COST {
+        return Err(anyhow!(
+            "argon2id timeCost {} exceeds {}",
+            time_cost,
+            ARGON2_MAX_TIME_COST
+        ));
+    }
+    if parallelism > ARGON2_MAX_PARALLELISM {
+        return Err(anyhow!(
+            "argon2id parallelism {} exceeds {}",
+            parallelism,
+            ARGON2_MAX_PARALLELISM
+        ));
+    }
+    Ok(())
+}
+
+fn argon2_cost(memory_cost: u32, time_cost: u32) -> u64 {
+    memory_cost as u64 * time_cost as u64
+}
+
+/// bcrypt_hash hashes password with bcrypt and returns $2b$ string
+pub fn bcrypt_hash(password: &[u8], cost: u32) -> Result<String> {
+    check_bcrypt_params(password, cost)?;
+    charge(bcrypt_cost(cost))?;
+    Ok(bcrypt::hash(password, cost)?)
+}
+
+/// bcrypt_verify verifies password against bcrypt string
+pub fn bcrypt_verify(password: &[u8], hash: &str) -> Result<bool> {
+    let parts: bcrypt::HashParts = hash.parse()?;
+    check_bcrypt_params(password, parts.get_cost())?;
+    charge(bcrypt_cost(parts.get_cost()))?;
+    Ok(bcrypt::verify(password, hash)?)
+}
+
+fn check_bcrypt_params(password: &[u8], cost: u32) -> Result<()> {
+    if !(BCRYPT_MIN_COST..=BCRYPT_MAX_COST).contains(&cost) {
+        return Err(anyhow!(
+            "bcrypt cost {} is not between {} and {}",
+            cost,
+            BCRYPT_MIN_COST,
+            BCRYPT_MAX_COST
+        ));
+    }
+    // bcrypt only uses the first 72 bytes, reject longer passwords instead of truncating them
+    if password.len() > BCRYPT_MAX_PASSWORD_LENGTH {
+        return Err(anyhow!(
+            "bcrypt password exceeds {} bytes",
+            BCRYPT_MAX_PASSWORD_LENGTH
+        ));
+    }
+    Ok(())
+}
+
+fn bcrypt_cost(cost: u32) -> u64 {
+    (1u64 << cost) * BCRYPT_ROUND_COST
+}
+
+/// scrypt_hash hashes password with scrypt and returns PHC string
+pub fn scrypt_hash(password: &[u8], log_n: u8, r: u32, p: u32) -> Result<String> {
+    check_scrypt_params(log_n, r, p)?;
+    charge(scrypt_cost(log_n, r, p))?;
+    let params = scrypt::Params::new(log_n, r, p, scrypt::Params::RECOMMENDED_LEN)
+        .map_err(|e| anyhow!("Invalid scrypt params: {}", e))?;
+    let salt = SaltString::generate(&mut OsRng);
+    let hash = scrypt::Scrypt
+        .hash_password_customized(password, None, None, params, &salt)
+        .map_err(|e| anyhow!("scrypt hash failed: {}", e))?;
+    Ok(hash.to_string())
+}
+
+/// scrypt_verify verifies password against scrypt PHC string
+pub fn scrypt_verify(password: &[u8], hash: &str) -> Result<bool> {
+    let parsed = PasswordHash::new(hash).map_err(|e| anyhow!("Invalid scrypt hash: {}", e))?;
+    if parsed.algorithm != scrypt::ALG_ID {
+        return Err(anyhow!(
+            "Invalid scrypt hash: algorithm is {}",
+            parsed.algorithm
+        ));
+    }
+    let params =
+        scrypt::Params::try_from(&parsed).map_err(|e| anyhow!("Invalid scrypt hash: {}", e))?;
+    check_scrypt_params(params.log_n(), params.r(), params.p())?;
+    charge(scrypt_cost(params.log_n(), params.r(), params.p()))?;
+    Ok(scrypt::Scrypt.verify_password(password, &parsed).is_ok())
+}
+
+fn check_scrypt_params(log_n: u8, r: u32, p: u32) -> Result<()> {
+    if log_n > SCRYPT_MAX_LOG_N {
+        return Err(anyhow!(
+            "scrypt logN {} exceeds {}",
+            log_n,
+            SCRYPT_MAX_LOG_N
+        ));
+    }
+    if r > SCRYPT_MAX_R {
+        return Err(anyhow!("scrypt r {} exceeds {}", r, SCRYPT_MAX_R));
+    }
+    if p > SCRYPT_MAX_P {
+        return Err(anyhow!("scrypt p {} exceeds {}", p, SCRYPT_MAX_P));
+    }
+    Ok(())
+}
+
+fn scrypt_cost(log_n: u8, r: u32, p: u32) -> u64 {
+    // 128 * r * 2^log_n bytes are r * 2^log_n / 8 KiB
+    (1u64 << log_n) * r as u64 * p as u64 / 4
+}
+
+fn arg_to_u32(arg: &Value) -> Result<u32> {
+    match arg.as_number() {
+        Some(n) if n >= 0.0 && n <= u32::MAX as f64 && n.fract() == 0.0 => Ok(n as u32),
+        _ => Err(anyhow!("Failed to convert arg to unsigned integer")),
+    }
+}
+
+/// build native password object that used by the `land:password` module
+pub fn build<'js>(ctx: Ctx<'js>) -> rquickjs::Result<Object> {
+    let password = Object::new(ctx.clone())?;
+
+    // argon2id_hash(password, memory_cost, time_cost, parallelism) returns PHC string
+    let argon2id_hash_callback = Function::new(
+        ctx.clone(),
+        |cx: Ctx<'js>, args: Rest<Value<'js>>| -> Result<String, rquickjs::Error> {
+            check_args(&args, 4)?;
+            let hash = (|| {
+                argon2id_hash(
+                    &arg_to_bytes(&args[0])?,
+                    arg_to_u32(&args[1])?,
+                    arg_to_u32(&args[2])?,
+                    arg_to_u32(&args[3])?,
+                )
+            })();
+            hash.map_err(|e| to_js_error(cx.clone(), e))
+        },
+    )?;
+
+    // argon2id_verify(password, hash) returns whether password matches hash
+    let argon2id_verify_callback = Function::new(
+        ctx.clone(),
+        |cx: Ctx<'js>, args: Rest<Value<'js>>| -> Result<bool, rquickjs::Error> {
+            check_args(&args, 2)?;
+            let matched =
+                (|| argon2id_verify(&arg_to_bytes(&args[0])?, &arg_to_string(&args[1])?))();
+            matched.map_err(|e| to_js_error(cx.clone(), e))
+        },
+    )?;
+
+    // bcrypt_hash(password, cost) returns $2b$ string
+    let bcrypt_hash_callback = Function::new(
+        ctx.clone(),
+        |cx: Ctx<'js>, args: Rest<Value<'js>>| -> Result<String, rquickjs::Error> {
+            check_args(&args, 2)?;
+            let hash = (|| bcrypt_hash(&arg_to_bytes(&args[0])?, arg_to_u32(&args[1])?))();
+            hash.map_err(|e| to_js_error(cx.clone(), e))
+        },
+    )?;
+
+    // bcrypt_verify(password, hash) returns whether password matches hash
+    let bcrypt_verify_callback = Function::new(
+        ctx.clone(),
+        |cx: Ctx<'js>, args: Rest<Value<'js>>| -> Result<bool, rquickjs::Error> {
+            check_args(&args, 2)?;
+            let matched = (|| bcrypt_verify(&arg_to_bytes(&args[0])?, &arg_to_string(&args[1])?))();
+            matched.map_err(|e| to_js_error(cx.clone(), e))
+        },
+    )?;
+
+    // scrypt_hash(password, log_n, r, p) returns PHC string
+    let scrypt_hash_callback = Function::new(
+        ctx.clone(),
+        |cx: Ctx<'js>, args: Rest<Value<'js>>| -> Result<String, rquickjs::Error> {
+            check_args(&args, 4)?;
+            let hash = (|| {
+                let log_n = u8::try_from(arg_to_u32(&args[1])?)?;
+                scrypt_hash(
+                    &arg_to_bytes(&args[0])?,
+                    log_n,
+                    arg_to_u32(&args[2])?,
+                    arg_to_u32(&args[3])?,
+                )
+            })();
+            hash.map_err(|e| to_js_error(cx.clone(), e))
+        },
+    )?;
+
+    // scrypt_verify(password, hash) returns whether password matches hash
+    let scrypt_verify_callback = Function::new(
+        ctx.clone(),
+        |cx: Ctx<'js>, args: Rest<Value<'js>>| -> Result<bool, rquickjs::Error> {
+            check_args(&args, 2)?;
+            let matched = (|| scrypt_verify(&arg_to_bytes(&args[0])?, &arg_to_string(&args[1])?))();
+            matched.map_err(|e| to_js_error(cx.clone(), e))
+        },
+    )?;
+
+    password.set("argon2id_hash", argon2id_hash_callback)?;
+    password.set("argon2id_verify", argon2id_verify_callback)?;
+    password.set("bcrypt_hash", bcrypt_hash_callback)?;
+    password.set("bcrypt_verify", bcrypt_verify_callback)?;
+    password.set("scrypt_hash", scrypt_hash_callback)?;
+    password.set("scrypt_verify", scrypt_verify_callback)?;
+    Ok(password)
+}
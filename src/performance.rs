@@ -0,0 +1,177 @@
+use crate::hostcall::{arg_to_string, check_args, to_js_error};
+use anyhow::anyhow;
+use once_cell::sync::Lazy;
+use rquickjs::{prelude::Rest, Ctx, Function, Object, Value};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+// SERVER_TIMING_ENV enables the Server-Timing header for all handlers when it is "on",
+// a handler enables it with `export default { fetch, serverTiming: true }`
+const SERVER_TIMING_ENV: &str = "LAND_SERVER_TIMING";
+
+/// SERVER_TIMING_HEADER is the response header of the timing metrics
+pub const SERVER_TIMING_HEADER: &str = "server-timing";
+
+// Metric is a Server-Timing metric, such as an outbound fetch or a user measure
+struct Metric {
+    name: String,
+    duration: Duration,
+    description: Option<String>,
+}
+
+// Timing is the clock and the metrics of the running request
+struct Timing {
+    origin: Instant,
+    time_origin: f64,
+    server_timing: bool,
+    body_read: Duration,
+    metrics: Vec<Metric>,
+}
+
+impl Timing {
+    fn new() -> Self {
+        Self {
+            origin: Instant::now(),
+            time_origin: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs_f64() * 1000.0)
+                .unwrap_or_default(),
+            server_timing: false,
+            body_read: Duration::ZERO,
+            metrics: Vec::new(),
+        }
+    }
+}
+
+// TIMING starts at wizer.initialize, the instant of the snapshot is meaningless after it is restored,
+// so it starts again for every request
+static TIMING: Lazy<Mutex<Timing>> = Lazy::new(|| Mutex::new(Timing::new()));
+
+/// begin_request starts the clock of performance.now() and clears the metrics
+pub fn begin_request() {
+    *TIMING.lock().unwrap() = Timing::new();
+}
+
+/// now returns the milliseconds since the request began
+pub fn now() -> f64 {
+    TIMING.lock().unwrap().origin.elapsed().as_secs_f64() * 1000.0
+}
+
+/// time_origin returns the unix time in milliseconds when the request began
+pub fn time_origin() -> f64 {
+    TIMING.lock().unwrap().time_origin
+}
+
+/// record adds a metric of the Server-Timing header
+pub fn record(name: &str, duration: Duration, description: Option<String>) {
+    TIMING.lock().unwrap().metrics.push(Metric {
+        name: token(name),
+        duration,
+        description,
+    });
+}
+
+/// record_body_read adds the time spent reading a body, the reads are summed in one metric
+pub fn record_body_read(duration: Duration) {
+    TIMING.lock().unwrap().body_read += duration;
+}
+
+/// enable turns on the Server-Timing header of the request if the env or the handler enables it
+pub fn enable(ctx: &Ctx) {
+    let enabled = crate::env::var(SERVER_TIMING_ENV)
+        .map(|value| value.eq_ignore_ascii_case("on"))
+        .unwrap_or(false)
+        || ctx
+            .globals()
+            .get::<_, Object>("handler")
+            .and_then(|handler| handler.get::<_, bool>("serverTiming"))
+            .unwrap_or(false);
+    TIMING.lock().unwrap().server_timing = enabled;
+}
+
+/// server_timing returns the Server-Timing header value, None if it is not enabled.
+/// The script metric is the time since the request began, it covers the other metrics.
+pub fn server_timing() -> Option<String> {
+    let timing = TIMING.lock().unwrap();
+    if !timing.server_timing {
+        return None;
+    }
+    let mut metrics = vec![metric("script", timing.origin.elapsed(), None)];
+    if !timing.body_read.is_zero() {
+        metrics.push(metric("body", timing.body_read, None));
+    }
+    for item in timing.metrics.iter() {
+        metrics.push(metric(
+            &item.name,
+            item.duration,
+            item.description.as_deref(),
+        ));
+    }
+    Some(metrics.join(", "))
+}
+
+fn metric(name: &str, duration: Duration, description: Option<&str>) -> String {
+    let duration = duration.as_secs_f64() * 1000.0;
+    match description {
+        Some(description) => format!(
+            "{};desc=\"{}\";dur={:.3}",
+            name,
+            description.replace('\\', "\\\\").replace('"', "\\\""),
+            duration
+        ),
+        None => format!("{};dur={:.3}", name, duration),
+    }
+}
+
+// token replaces the characters that are not allowed in a Server-Timing metric name
+fn token(name: &str) -> String {
+    let name: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || "!#$%&'*+-.^_`|~".contains(c) {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    if name.is_empty() {
+        "-".to_string()
+    } else {
+        name
+    }
+}
+
+/// build the performance hostcalls, they are set on the hostcall object
+pub fn build<'js>(ctx: Ctx<'js>, hostcall: &Object<'js>) -> rquickjs::Result<()> {
+    // performance_now() returns the milliseconds since the request began
+    let now_callback = Function::new(ctx.clone(), now)?;
+
+    // performance_time_origin() returns the unix time in milliseconds when the request began
+    let time_origin_callback = Function::new(ctx.clone(), time_origin)?;
+
+    // server_timing_measure(name, duration) adds a performance.measure() to the Server-Timing header
+    let measure_callback = Function::new(
+        ctx.clone(),
+        |cx: Ctx<'js>, args: Rest<Value<'js>>| -> Result<(), rquickjs::Error> {
+            check_args(&args, 2)?;
+            let name = arg_to_string(&args[0]).map_err(|e| to_js_error(cx.clone(), e))?;
+            let duration = args[1]
+                .as_number()
+                .filter(|duration| duration.is_finite() && *duration >= 0.0)
+                .ok_or_else(|| {
+                    to_js_error(
+                        cx.clone(),
+                        anyhow!("Measure duration must be a non-negative number"),
+                    )
+                })?;
+            record(&name, Duration::from_secs_f64(duration / 1000.0), None);
+            Ok(())
+        },
+    )?;
+
+    hostcall.set("performance_now", now_callback)?;
+    hostcall.set("performance_time_origin", time_origin_callback)?;
+    hostcall.set("server_timing_measure", measure_callback)?;
+    Ok(())
+}
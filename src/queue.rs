@@ -0,0 +1,225 @@
+use crate::bindings::{self, decode_hex, encode_hex, encode_segment};
+use crate::hostcall::{arg_to_bytes, arg_to_string, check_args, to_js_error};
+use anyhow::{anyhow, Result};
+use hmac::{Hmac, Mac};
+use rquickjs::{prelude::Rest, Array, ArrayBuffer, Ctx, FromJs, Function, IntoJs, Object, Value};
+use serde_json::{json, Value as JsonValue};
+use sha2::Sha256;
+
+// The limits follow the queue limits of the platform
+const MAX_MESSAGE_SIZE: usize = 128 * 1024;
+const MAX_BATCH_MESSAGES: usize = 100;
+
+/// DELIVERY_HEADER marks a request that delivers a batch of messages to the queue handler,
+/// its value is the queue name. The deliveries must be signed with SIGNATURE_HEADER.
+pub const DELIVERY_HEADER: &str = "x-land-queue";
+
+/// SIGNATURE_HEADER is the hex HMAC-SHA256 of the delivery body with LAND_QUEUE_SECRET
+pub const SIGNATURE_HEADER: &str = "x-land-queue-signature";
+
+// SECRET_ENV is the platform secret of the deliveries, all deliveries are rejected if it is not set
+const SECRET_ENV: &str = "LAND_QUEUE_SECRET";
+
+/// ContentType is the serialization of a message body
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ContentType {
+    Json,
+    Text,
+    Bytes,
+}
+
+impl ContentType {
+    fn parse(value: &str) -> Result<Self> {
+        match value {
+            "json" => Ok(ContentType::Json),
+            "text" => Ok(ContentType::Text),
+            "bytes" => Ok(ContentType::Bytes),
+            _ => Err(anyhow!("Queue content type '{}' is not supported", value)),
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            ContentType::Json => "json",
+            ContentType::Text => "text",
+            ContentType::Bytes => "bytes",
+        }
+    }
+}
+
+/// OutgoingMessage is a serialized message to send
+#[derive(Debug)]
+pub struct OutgoingMessage {
+    pub body: Vec<u8>,
+    pub content_type: ContentType,
+}
+
+impl<'js> FromJs<'js> for OutgoingMessage {
+    fn from_js(ctx: &Ctx<'js>, value: Value<'js>) -> rquickjs::Result<Self> {
+        if !value.is_object() {
+            return Err(ctx.throw(
+                rquickjs::String::from_str(ctx.clone(), "OutgoingMessage need from an object")?
+                    .into_value(),
+            ));
+        }
+        let message_object = value.as_object().unwrap();
+        let content_type: Value = message_object.get("contentType")?;
+        let content_type = arg_to_string(&content_type).unwrap_or_else(|_| "json".to_string());
+        let content_type =
+            ContentType::parse(&content_type).map_err(|e| to_js_error(ctx.clone(), e))?;
+        let body: Value = message_object.get("body")?;
+        let body = encode_body(ctx, body, content_type).map_err(|e| to_js_error(ctx.clone(), e))?;
+        Ok(Self { body, content_type })
+    }
+}
+
+// encode_body serializes body by content_type, json bodies are JSON text
+fn encode_body<'js>(
+    ctx: &Ctx<'js>,
+    body: Value<'js>,
+    content_type: ContentType,
+) -> Result<Vec<u8>> {
+    match content_type {
+        ContentType::Json => {
+            let json = ctx
+                .json_stringify(body)?
+                .ok_or_else(|| anyhow!("Queue message body can not be serialized as JSON"))?;
+            Ok(json.to_string()?.into_bytes())
+        }
+        ContentType::Text => {
+            if !body.is_string() {
+                return Err(anyhow!("Queue text message body must be a string"));
+            }
+            arg_to_bytes(&body)
+        }
+        ContentType::Bytes => {
+            if ArrayBuffer::from_value(body.clone()).is_none() {
+                return Err(anyhow!("Queue bytes message body must be an ArrayBuffer"));
+            }
+            arg_to_bytes(&body)
+        }
+    }
+}
+
+fn decode_body<'js>(
+    ctx: &Ctx<'js>,
+    body: Vec<u8>,
+    content_type: ContentType,
+) -> Result<Value<'js>> {
+    Ok(match content_type {
+        ContentType::Json => ctx.json_parse(body)?,
+        ContentType::Text => String::from_utf8(body)?.into_js(ctx)?,
+        ContentType::Bytes => ArrayBuffer::new(ctx.clone(), body)?.into_value(),
+    })
+}
+
+/// send sends messages to queue by the bindings service
+pub fn send(queue: &str, messages: Vec<OutgoingMessage>) -> Result<()> {
+    if bindings::endpoint().is_none() {
+        return Err(anyhow!(
+            "Queue '{}' needs the bindings service, LAND_BINDINGS_URL is not set",
+            queue
+        ));
+    }
+    if messages.is_empty() {
+        return Ok(());
+    }
+    if messages.len() > MAX_BATCH_MESSAGES {
+        return Err(anyhow!(
+            "Queue batch has more than {} messages",
+            MAX_BATCH_MESSAGES
+        ));
+    }
+    if messages
+        .iter()
+        .any(|message| message.body.len() > MAX_MESSAGE_SIZE)
+    {
+        return Err(anyhow!(
+            "Queue message is larger than {} bytes",
+            MAX_MESSAGE_SIZE
+        ));
+    }
+    let messages: Vec<JsonValue> = messages
+        .iter()
+        .map(|message| {
+            json!({
+                "body": encode_hex(&message.body),
+                "content_type": message.content_type.as_str(),
+            })
+        })
+        .collect();
+    let body = serde_json::to_vec(&json!({ "messages": messages }))?;
+    let headers = vec![("content-type".to_string(), "application/json".to_string())];
+    let path = format!("/queue/{}", encode_segment(queue));
+    let response = bindings::call("POST", &path, &headers, Some(body))?;
+    if response.status != 200 {
+        return Err(anyhow!(
+            "Queue send failed with status {}: {}",
+            response.status,
+            String::from_utf8_lossy(&response.body)
+        ));
+    }
+    Ok(())
+}
+
+/// verify checks the signature of a delivery body, it is checked before the body is parsed
+pub fn verify(signature: Option<&str>, delivery: &[u8]) -> bool {
+    let secret = match crate::env::var(SECRET_ENV).filter(|secret| !secret.is_empty()) {
+        Some(secret) => secret,
+        None => return false,
+    };
+    let signature = match signature.and_then(|signature| decode_hex(signature.trim()).ok()) {
+        Some(signature) => signature,
+        None => return false,
+    };
+    let mut mac = match Hmac::<Sha256>::new_from_slice(secret.as_bytes()) {
+        Ok(mac) => mac,
+        Err(_) => return false,
+    };
+    mac.update(delivery);
+    mac.verify_slice(&signature).is_ok()
+}
+
+/// batch_into_js returns the delivered batch {queue, messages: [{id, timestamp, attempts, body}]},
+/// the delivery is JSON of the bindings service with the hex bodies
+pub fn batch_into_js<'js>(ctx: &Ctx<'js>, delivery: &[u8]) -> Result<Value<'js>> {
+    let delivery: JsonValue = serde_json::from_slice(delivery)
+        .map_err(|e| anyhow!("Queue delivery is invalid: {}", e))?;
+    let messages = Array::new(ctx.clone())?;
+    for (index, message) in delivery["messages"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .enumerate()
+    {
+        let content_type = ContentType::parse(message["content_type"].as_str().unwrap_or("json"))?;
+        let body = decode_hex(message["body"].as_str().unwrap_or_default())?;
+        let item = Object::new(ctx.clone())?;
+        item.set("id", message["id"].as_str().unwrap_or_default())?;
+        item.set("timestamp", message["timestamp"].as_f64().unwrap_or(0.0))?;
+        item.set("attempts", message["attempts"].as_u64().unwrap_or(1) as f64)?;
+        item.set("body", decode_body(ctx, body, content_type)?)?;
+        messages.set(index, item)?;
+    }
+    let batch = Object::new(ctx.clone())?;
+    batch.set("queue", delivery["queue"].as_str().unwrap_or_default())?;
+    batch.set("messages", messages)?;
+    Ok(batch.into_value())
+}
+
+/// build the queue hostcalls, they are set on the hostcall object
+pub fn build<'js>(ctx: Ctx<'js>, hostcall: &Object<'js>) -> rquickjs::Result<()> {
+    // queue_send(queue, [{body, contentType}]) sends the messages
+    let send_callback = Function::new(
+        ctx.clone(),
+        |cx: Ctx<'js>, args: Rest<Value<'js>>| -> Result<(), rquickjs::Error> {
+            check_args(&args, 2)?;
+            let queue = arg_to_string(&args[0]).map_err(|e| to_js_error(cx.clone(), e))?;
+            let messages = Vec::<OutgoingMessage>::from_js(&cx, args[1].clone())?;
+            send(&queue, messages).map_err(|e| to_js_error(cx.clone(), e))
+        },
+    )?;
+
+    hostcall.set("queue_send", send_callback)?;
+    Ok(())
+}
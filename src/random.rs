@@ -0,0 +1,108 @@
+use rquickjs::{Ctx, Function};
+use std::sync::Mutex;
+
+// RANDOM_SEED_ENV sets a fixed seed for Math.random, it is read in wizer.initialize,
+// so a script built with it replays the same sequence in every instance. Only for tests.
+const RANDOM_SEED_ENV: &str = "LAND_RANDOM_SEED";
+
+// RNG is the Math.random state. wizer snapshots the memory after init_js_context,
+// so it is reset to None at the end of init and seeded from WASI randomness on first use after restore.
+static RNG: Mutex<Option<Xoshiro256>> = Mutex::new(None);
+
+// SEED is the deterministic seed read from RANDOM_SEED_ENV during init
+static SEED: Mutex<Option<u64>> = Mutex::new(None);
+
+/// Xoshiro256 is xoshiro256** generator, the same family as the engines in browsers
+struct Xoshiro256 {
+    s: [u64; 4],
+}
+
+impl Xoshiro256 {
+    fn from_seed(seed: u64) -> Self {
+        // expand the seed with splitmix64 as recommended by the xoshiro authors
+        let mut x = seed;
+        let mut s = [0u64; 4];
+        for v in s.iter_mut() {
+            x = x.wrapping_add(0x9e3779b97f4a7c15);
+            let mut z = x;
+            z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+            *v = z ^ (z >> 31);
+        }
+        Self { s }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let result = self.s[1].wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+        let t = self.s[1] << 17;
+        self.s[2] ^= self.s[0];
+        self.s[3] ^= self.s[1];
+        self.s[1] ^= self.s[2];
+        self.s[0] ^= self.s[3];
+        self.s[2] ^= t;
+        self.s[3] = self.s[3].rotate_left(45);
+        result
+    }
+
+    // next_f64 returns a float in [0, 1) from the high 53 bits
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 * (1.0 / (1u64 << 53) as f64)
+    }
+}
+
+/// init reads the deterministic seed from env, it runs in wizer.initialize
+pub fn init() -> anyhow::Result<()> {
+    if let Some(value) = crate::env::var(RANDOM_SEED_ENV) {
+        let seed = value
+            .parse::<u64>()
+            .map_err(|e| anyhow::anyhow!("Invalid {}: {}", RANDOM_SEED_ENV, e))?;
+        *SEED.lock().unwrap() = Some(seed);
+    }
+    Ok(())
+}
+
+/// reset drops the state used during init, so it is not shared by instances restored from the snapshot
+pub fn reset() {
+    let seed = *SEED.lock().unwrap();
+    *RNG.lock().unwrap() = seed.map(Xoshiro256::from_seed);
+}
+
+fn next_f64() -> f64 {
+    let mut rng = RNG.lock().unwrap();
+    rng.get_or_insert_with(|| {
+        let mut buf = [0u8; 8];
+        // the generator is only for Math.random, fall back to the clock if WASI random fails
+        if getrandom::getrandom(&mut buf).is_err() {
+            let nanos = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_nanos() as u64)
+                .unwrap_or_default();
+            buf = nanos.to_le_bytes();
+        }
+        Xoshiro256::from_seed(u64::from_le_bytes(buf))
+    })
+    .next_f64()
+}
+
+/// hex_id returns size random bytes as lowercase hex, such as the ids of requests and spans.
+/// It is not affected by RANDOM_SEED_ENV, the ids of every instance must be unique.
+pub fn hex_id(size: usize) -> String {
+    let mut buf = vec![0u8; size];
+    if getrandom::getrandom(&mut buf).is_err() {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or_default();
+        for (index, byte) in buf.iter_mut().enumerate() {
+            *byte = (nanos >> ((index % 16) * 8)) as u8;
+        }
+    }
+    crate::bindings::encode_hex(&buf)
+}
+
+/// build Math.random function that replaces the QuickJS one
+pub fn build<'js>(ctx: Ctx<'js>) -> rquickjs::Result<Function<'js>> {
+    let random = Function::new(ctx.clone(), || -> f64 { next_f64() })?;
+    random.set_name("random")?;
+    Ok(random)
+}
@@ -0,0 +1,96 @@
+use crate::console::{self, Level};
+use crate::error_page::Failure;
+use rquickjs::{Context, Ctx, Persistent, Runtime, Value};
+use std::cell::{Cell, RefCell};
+
+// MAX_PENDING limits the unhandled rejections of a request that are kept until they are reported
+const MAX_PENDING: usize = 64;
+
+// Rejection is a rejected promise without a handler, the reason is described when it is reported,
+// so the tracker does not run JS code, such as the getters of the reason, while QuickJS rejects the promise
+struct Rejection {
+    promise: Persistent<Value<'static>>,
+    reason: Persistent<Value<'static>>,
+}
+
+thread_local! {
+    // PENDING are the rejected promises without a handler,
+    // a promise is removed if a handler is attached before the rejections are reported
+    static PENDING: RefCell<Vec<Rejection>> = const { RefCell::new(Vec::new()) };
+    // DROPPED counts the rejections beyond MAX_PENDING, they are not kept
+    static DROPPED: Cell<usize> = const { Cell::new(0) };
+}
+
+// track is the host promise rejection tracker, QuickJS calls it when a promise is rejected without a handler
+// and again with is_handled when a handler is attached to the rejected promise later
+fn track<'js>(ctx: Ctx<'js>, promise: Value<'js>, reason: Value<'js>, is_handled: bool) {
+    PENDING.with(|pending| {
+        let mut pending = pending.borrow_mut();
+        if is_handled {
+            pending.retain(|rejection| {
+                rejection
+                    .promise
+                    .clone()
+                    .restore(&ctx)
+                    .map_or(true, |saved| saved != promise)
+            });
+            return;
+        }
+        if pending.len() < MAX_PENDING {
+            pending.push(Rejection {
+                promise: Persistent::save(&ctx, promise),
+                reason: Persistent::save(&ctx, reason),
+            });
+        } else {
+            DROPPED.with(|dropped| dropped.set(dropped.get() + 1));
+        }
+    });
+}
+
+/// install sets the promise rejection tracker of the runtime
+pub fn install(runtime: &Runtime) {
+    runtime.set_host_promise_rejection_tracker(Some(Box::new(track)));
+}
+
+/// report logs the rejections that are still unhandled in context, it is called when the pending jobs are done
+pub fn report(context: &Context) {
+    let rejections: Vec<Rejection> =
+        PENDING.with(|pending| pending.borrow_mut().drain(..).collect());
+    let dropped = DROPPED.with(|dropped| dropped.replace(0));
+    if rejections.is_empty() && dropped == 0 {
+        return;
+    }
+    let texts: Vec<String> = context.with(|ctx| {
+        rejections
+            .into_iter()
+            .map(|rejection| match rejection.reason.restore(&ctx) {
+                Ok(reason) => Failure::from_js(&ctx, reason).text(),
+                Err(err) => format!("unknown reason: {}", err),
+            })
+            .collect()
+    });
+    for text in texts {
+        console::log(
+            Level::Error,
+            &format!("Unhandled promise rejection: {}", text.trim_end()),
+        );
+    }
+    if dropped > 0 {
+        console::log(
+            Level::Error,
+            &format!(
+                "{} more unhandled promise rejections were not reported, the limit is {}",
+                dropped, MAX_PENDING
+            ),
+        );
+    }
+}
+
+/// job_error logs the exception of a pending job in context, such as a throwing queueMicrotask callback
+pub fn job_error(context: Context) {
+    let text = context.with(|ctx| Failure::from_js(&ctx, ctx.catch()).text());
+    console::log(
+        Level::Error,
+        &format!("Uncaught exception in a pending job: {}", text.trim_end()),
+    );
+}
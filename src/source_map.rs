@@ -0,0 +1,159 @@
+use serde_json::Value as JsonValue;
+
+// INLINE_PREFIX is the inline source map comment of the bundlers, such as esbuild --sourcemap=inline
+const INLINE_PREFIX: &str = "//# sourceMappingURL=data:application/json;";
+
+const BASE64: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Location is a position in an original source, the line and the column are 1-based.
+/// The source and the content are borrowed from the source map.
+#[derive(Debug, PartialEq)]
+pub struct Location<'a> {
+    pub source: &'a str,
+    pub line: u32,
+    pub column: u32,
+    pub content: Option<&'a str>,
+}
+
+// Segment is a mapping of a generated column to an original position, the values are 0-based
+struct Segment {
+    column: u32,
+    source: usize,
+    line: u32,
+    original_column: u32,
+}
+
+/// SourceMap is a decoded source map v3, the mappings are grouped by the generated lines
+pub struct SourceMap {
+    sources: Vec<String>,
+    contents: Vec<Option<String>>,
+    lines: Vec<Vec<Segment>>,
+}
+
+fn base64_value(c: u8) -> Option<u32> {
+    BASE64.iter().position(|b| *b == c).map(|v| v as u32)
+}
+
+fn base64_decode(text: &str) -> Option<Vec<u8>> {
+    let mut output = Vec::with_capacity(text.len() / 4 * 3);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for c in text
+        .bytes()
+        .filter(|c| !c.is_ascii_whitespace() && *c != b'=')
+    {
+        buffer = (buffer << 6) | base64_value(c)?;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            output.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    Some(output)
+}
+
+// decode_vlq decodes the base64 VLQ values of a mapping segment
+fn decode_vlq(segment: &str) -> Option<Vec<i64>> {
+    let mut values = Vec::new();
+    let mut value = 0i64;
+    let mut shift = 0;
+    for c in segment.bytes() {
+        let digit = base64_value(c)? as i64;
+        value += (digit & 31) << shift;
+        if digit & 32 != 0 {
+            shift += 5;
+            if shift > 60 {
+                return None;
+            }
+            continue;
+        }
+        let negative = value & 1 == 1;
+        value >>= 1;
+        values.push(if negative { -value } else { value });
+        value = 0;
+        shift = 0;
+    }
+    Some(values)
+}
+
+impl SourceMap {
+    /// parse decodes a source map v3 JSON, the index maps with sections are not supported
+    pub fn parse(json: &str) -> Option<Self> {
+        let map: JsonValue = serde_json::from_str(json).ok()?;
+        let root = map["sourceRoot"].as_str().unwrap_or_default();
+        let sources: Vec<String> = map["sources"]
+            .as_array()?
+            .iter()
+            .map(|source| format!("{}{}", root, source.as_str().unwrap_or_default()))
+            .collect();
+        let contents = (0..sources.len())
+            .map(|i| map["sourcesContent"][i].as_str().map(|s| s.to_string()))
+            .collect();
+
+        let mut lines = Vec::new();
+        // the source, line and column fields are relative to the previous segment of the map,
+        // the generated column is relative to the previous segment of the line
+        let (mut source, mut line, mut original_column) = (0i64, 0i64, 0i64);
+        for mappings in map["mappings"].as_str()?.split(';') {
+            let mut segments = Vec::new();
+            let mut column = 0i64;
+            for segment in mappings.split(',').filter(|s| !s.is_empty()) {
+                let values = decode_vlq(segment).filter(|values| !values.is_empty())?;
+                column += values[0];
+                if values.len() < 4 {
+                    continue;
+                }
+                source += values[1];
+                line += values[2];
+                original_column += values[3];
+                if column < 0 || source < 0 || line < 0 || original_column < 0 {
+                    return None;
+                }
+                segments.push(Segment {
+                    column: column as u32,
+                    source: source as usize,
+                    line: line as u32,
+                    original_column: original_column as u32,
+                });
+            }
+            lines.push(segments);
+        }
+        Some(Self {
+            sources,
+            contents,
+            lines,
+        })
+    }
+
+    /// parse_inline decodes the inline base64 source map of a script, None if it has none
+    pub fn parse_inline(script: &str) -> Option<Self> {
+        let comment = script
+            .lines()
+            .rev()
+            .find(|line| line.starts_with(INLINE_PREFIX))?;
+        let (_, data) = comment[INLINE_PREFIX.len()..].split_once("base64,")?;
+        let json = base64_decode(data.trim())?;
+        Self::parse(std::str::from_utf8(&json).ok()?)
+    }
+
+    /// lookup returns the original location of a 1-based generated line and column,
+    /// the first mapping of the line is used if the column is unknown
+    pub fn lookup(&self, line: u32, column: Option<u32>) -> Option<Location<'_>> {
+        let segments = self.lines.get(line.checked_sub(1)? as usize)?;
+        let segment = match column {
+            Some(column) => segments
+                .iter()
+                .rev()
+                .find(|segment| segment.column < column)
+                .or_else(|| segments.first())?,
+            None => segments.first()?,
+        };
+        Some(Location {
+            source: self.sources.get(segment.source)?,
+            line: segment.line + 1,
+            column: segment.original_column + 1,
+            content: self.contents.get(segment.source)?.as_deref(),
+        })
+    }
+}
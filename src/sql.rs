@@ -0,0 +1,265 @@
+use crate::bindings::{self, decode_hex, encode_hex, encode_segment};
+use crate::hostcall::{arg_to_string, check_args, to_js_error};
+use anyhow::{anyhow, Result};
+use rquickjs::{
+    prelude::Rest, Array, ArrayBuffer, BigInt, Ctx, FromJs, Function, IntoJs, Object, TypedArray,
+    Value,
+};
+use serde_json::{json, Value as JsonValue};
+
+// MAX_SAFE_INTEGER is the largest integer a JS number holds exactly, larger integers are returned as BigInt
+const MAX_SAFE_INTEGER: i64 = (1 << 53) - 1;
+
+// BLOB_KEY marks a blob value in the JSON of the bindings service, {"blob": "hex bytes"}
+const BLOB_KEY: &str = "blob";
+
+/// SqlValue is a statement parameter or a column value
+#[derive(Debug, Clone, PartialEq)]
+pub enum SqlValue {
+    Null,
+    Integer(i64),
+    Real(f64),
+    Text(String),
+    Blob(Vec<u8>),
+}
+
+impl SqlValue {
+    fn to_json(&self) -> JsonValue {
+        match self {
+            SqlValue::Null => JsonValue::Null,
+            SqlValue::Integer(value) => json!(value),
+            SqlValue::Real(value) => json!(value),
+            SqlValue::Text(value) => json!(value),
+            SqlValue::Blob(value) => json!({ BLOB_KEY: encode_hex(value) }),
+        }
+    }
+
+    fn from_json(value: &JsonValue) -> Result<Self> {
+        Ok(match value {
+            JsonValue::Null => SqlValue::Null,
+            JsonValue::Bool(value) => SqlValue::Integer(*value as i64),
+            JsonValue::Number(number) => match number.as_i64() {
+                Some(value) => SqlValue::Integer(value),
+                None => SqlValue::Real(number.as_f64().unwrap_or(f64::NAN)),
+            },
+            JsonValue::String(value) => SqlValue::Text(value.clone()),
+            JsonValue::Object(object) => {
+                let hex = object
+                    .get(BLOB_KEY)
+                    .and_then(|hex| hex.as_str())
+                    .ok_or_else(|| anyhow!("SQL value object must be a blob"))?;
+                SqlValue::Blob(decode_hex(hex)?)
+            }
+            JsonValue::Array(_) => return Err(anyhow!("SQL value must not be an array")),
+        })
+    }
+
+    fn into_js<'js>(self, ctx: &Ctx<'js>) -> rquickjs::Result<Value<'js>> {
+        match self {
+            SqlValue::Null => Ok(Value::new_null(ctx.clone())),
+            SqlValue::Integer(value)
+                if !(-MAX_SAFE_INTEGER..=MAX_SAFE_INTEGER).contains(&value) =>
+            {
+                Ok(BigInt::from_i64(ctx.clone(), value)?.into_value())
+            }
+            SqlValue::Integer(value) => Ok(Value::new_number(ctx.clone(), value as f64)),
+            SqlValue::Real(value) => Ok(Value::new_number(ctx.clone(), value)),
+            SqlValue::Text(value) => value.into_js(ctx),
+            SqlValue::Blob(value) => TypedArray::<u8>::new(ctx.clone(), value)?.into_js(ctx),
+        }
+    }
+}
+
+impl<'js> FromJs<'js> for SqlValue {
+    fn from_js(ctx: &Ctx<'js>, value: Value<'js>) -> rquickjs::Result<Self> {
+        if value.is_null() || value.is_undefined() {
+            return Ok(SqlValue::Null);
+        }
+        if let Some(value) = value.as_bool() {
+            return Ok(SqlValue::Integer(value as i64));
+        }
+        if let Some(value) = value.as_int() {
+            return Ok(SqlValue::Integer(value as i64));
+        }
+        if let Some(value) = value.as_float() {
+            // integral numbers are bound as integers, so 1.0 matches an INTEGER column
+            if value.is_finite() && value.fract() == 0.0 && value.abs() <= i64::MAX as f64 {
+                return Ok(SqlValue::Integer(value as i64));
+            }
+            return Ok(SqlValue::Real(value));
+        }
+        if let Some(value) = value.as_string() {
+            return Ok(SqlValue::Text(value.to_string()?));
+        }
+        if let Some(value) = value.as_big_int() {
+            return Ok(SqlValue::Integer(value.clone().to_i64()?));
+        }
+        if let Some(buffer) = ArrayBuffer::from_value(value.clone()) {
+            return Ok(SqlValue::Blob(
+                buffer.as_bytes().unwrap_or_default().to_vec(),
+            ));
+        }
+        Err(ctx.throw(
+            rquickjs::String::from_str(
+                ctx.clone(),
+                &format!(
+                    "SQL parameter type '{}' is not supported",
+                    value.type_name()
+                ),
+            )?
+            .into_value(),
+        ))
+    }
+}
+
+/// Statement is a SQL statement with the bound parameters
+#[derive(Debug, Default)]
+pub struct Statement {
+    pub sql: String,
+    pub params: Vec<SqlValue>,
+}
+
+impl<'js> FromJs<'js> for Statement {
+    fn from_js(ctx: &Ctx<'js>, value: Value<'js>) -> rquickjs::Result<Self> {
+        if !value.is_object() {
+            return Err(ctx.throw(
+                rquickjs::String::from_str(ctx.clone(), "Statement need from an object")?
+                    .into_value(),
+            ));
+        }
+        let statement_object = value.as_object().unwrap();
+        let sql: Value = statement_object.get("sql")?;
+        Ok(Self {
+            sql: arg_to_string(&sql).unwrap_or_default(),
+            params: statement_object.get("params")?,
+        })
+    }
+}
+
+/// QueryResult is the result of a statement, changes and last_row_id are set by the writing statements,
+/// duration is the milliseconds the database took to run it
+#[derive(Debug, Default)]
+pub struct QueryResult {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<SqlValue>>,
+    pub changes: u64,
+    pub last_row_id: i64,
+    pub duration: f64,
+}
+
+impl QueryResult {
+    fn from_json(value: &JsonValue) -> Result<Self> {
+        let columns = value["columns"]
+            .as_array()
+            .map(|columns| {
+                columns
+                    .iter()
+                    .map(|column| column.as_str().unwrap_or_default().to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+        let mut rows = vec![];
+        for row in value["rows"].as_array().into_iter().flatten() {
+            let row = row
+                .as_array()
+                .ok_or_else(|| anyhow!("SQL result row must be an array"))?;
+            rows.push(row.iter().map(SqlValue::from_json).collect::<Result<_>>()?);
+        }
+        Ok(Self {
+            columns,
+            rows,
+            changes: value["changes"].as_u64().unwrap_or(0),
+            last_row_id: value["last_row_id"].as_i64().unwrap_or(0),
+            duration: value["duration"].as_f64().unwrap_or(0.0),
+        })
+    }
+
+    /// into_js returns {columns, rows, meta}, the rows are arrays if raw, or objects by column names
+    fn into_js<'js>(self, ctx: &Ctx<'js>, raw: bool) -> rquickjs::Result<Value<'js>> {
+        let rows = Array::new(ctx.clone())?;
+        for (index, row) in self.rows.into_iter().enumerate() {
+            if raw {
+                let values = Array::new(ctx.clone())?;
+                for (column, value) in row.into_iter().enumerate() {
+                    values.set(column, value.into_js(ctx)?)?;
+                }
+                rows.set(index, values)?;
+            } else {
+                let values = Object::new(ctx.clone())?;
+                for (column, value) in self.columns.iter().zip(row.into_iter()) {
+                    values.set(column.as_str(), value.into_js(ctx)?)?;
+                }
+                rows.set(index, values)?;
+            }
+        }
+        let meta = Object::new(ctx.clone())?;
+        meta.set("changes", self.changes as f64)?;
+        meta.set("last_row_id", self.last_row_id as f64)?;
+        meta.set("duration", self.duration)?;
+        let result = Object::new(ctx.clone())?;
+        result.set("columns", self.columns)?;
+        result.set("rows", rows)?;
+        result.set("meta", meta)?;
+        Ok(Value::from_object(result))
+    }
+}
+
+/// query runs statements in database in one transaction, a failed statement rolls back all of them
+pub fn query(database: &str, statements: Vec<Statement>) -> Result<Vec<QueryResult>> {
+    if bindings::endpoint().is_none() {
+        return Err(anyhow!(
+            "SQL database '{}' needs the bindings service, LAND_BINDINGS_URL is not set",
+            database
+        ));
+    }
+    let statements: Vec<JsonValue> = statements
+        .iter()
+        .map(|statement| {
+            json!({
+                "sql": statement.sql,
+                "params": statement.params.iter().map(SqlValue::to_json).collect::<Vec<_>>(),
+            })
+        })
+        .collect();
+    let body = serde_json::to_vec(&json!({ "statements": statements }))?;
+    let headers = vec![("content-type".to_string(), "application/json".to_string())];
+    let path = format!("/sql/{}", encode_segment(database));
+    let response = bindings::call("POST", &path, &headers, Some(body))?;
+    let value: JsonValue = serde_json::from_slice(&response.body)
+        .map_err(|e| anyhow!("SQL response is invalid: {}", e))?;
+    if response.status != 200 {
+        return Err(anyhow!(
+            "SQL error: {}",
+            value["error"].as_str().unwrap_or("unknown error")
+        ));
+    }
+    value["results"]
+        .as_array()
+        .ok_or_else(|| anyhow!("SQL response has no results"))?
+        .iter()
+        .map(QueryResult::from_json)
+        .collect()
+}
+
+/// build the SQL hostcalls, they are set on the hostcall object
+pub fn build<'js>(ctx: Ctx<'js>, hostcall: &Object<'js>) -> rquickjs::Result<()> {
+    // sql_query(database, [{sql, params}], raw) returns [{columns, rows, meta}]
+    let query_callback = Function::new(
+        ctx.clone(),
+        |cx: Ctx<'js>, args: Rest<Value<'js>>| -> Result<Array<'js>, rquickjs::Error> {
+            check_args(&args, 3)?;
+            let database = arg_to_string(&args[0]).map_err(|e| to_js_error(cx.clone(), e))?;
+            let statements = Vec::<Statement>::from_js(&cx, args[1].clone())?;
+            let raw = args[2].as_bool().unwrap_or(false);
+            let results = query(&database, statements).map_err(|e| to_js_error(cx.clone(), e))?;
+            let array = Array::new(cx.clone())?;
+            for (index, result) in results.into_iter().enumerate() {
+                array.set(index, result.into_js(&cx, raw)?)?;
+            }
+            Ok::<_, rquickjs::Error>(array)
+        },
+    )?;
+
+    hostcall.set("sql_query", query_callback)?;
+    Ok(())
+}
@@ -0,0 +1,294 @@
+use crate::bindings;
+use serde_json::{json, Value as JsonValue};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+// TRACE_EXPORT_ENV selects the span export. "log" writes the OTLP-JSON of every request as a line to stdout,
+// "host" sends it to the bindings service when the request is done. The spans are not exported by default,
+// the traceparent is propagated to the outbound requests anyway.
+const TRACE_EXPORT_ENV: &str = "LAND_TRACE_EXPORT";
+
+// SERVICE_NAME_ENV is the service.name of the exported spans, it is set by the platform
+const SERVICE_NAME_ENV: &str = "LAND_SCRIPT_ID";
+
+/// TRACEPARENT_HEADER and TRACESTATE_HEADER are the W3C trace context headers
+pub const TRACEPARENT_HEADER: &str = "traceparent";
+pub const TRACESTATE_HEADER: &str = "tracestate";
+
+// MAX_SPANS limits the spans of a request, the body reads of a large stream can be many spans
+const MAX_SPANS: usize = 512;
+
+/// Kind is the OTLP span kind
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Kind {
+    Internal = 1,
+    Server = 2,
+    Client = 3,
+}
+
+#[derive(Debug, PartialEq)]
+enum Export {
+    Off,
+    Log,
+    Host,
+}
+
+fn export_mode() -> Export {
+    match crate::env::var(TRACE_EXPORT_ENV).as_deref() {
+        Some("log") => Export::Log,
+        Some("host") if bindings::endpoint().is_some() => Export::Host,
+        _ => Export::Off,
+    }
+}
+
+// Trace is the trace context of the running request, the spans are children of the handler span
+struct Trace {
+    trace_id: String,
+    flags: String,
+    state: Option<String>,
+    root: Span,
+    spans: Vec<JsonValue>,
+    dropped: usize,
+}
+
+// TRACE is None out of a request, such as in wizer.initialize, the spans are not recorded then
+static TRACE: Mutex<Option<Trace>> = Mutex::new(None);
+
+fn unix_nanos() -> u128 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or_default()
+}
+
+fn is_hex(value: &str, len: usize) -> bool {
+    value.len() == len
+        && value
+            .bytes()
+            .all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'))
+}
+
+// parse_traceparent returns the trace id, the parent span id and the flags of a valid traceparent
+fn parse_traceparent(value: &str) -> Option<(String, String, String)> {
+    let parts: Vec<&str> = value.trim().split('-').collect();
+    if parts.len() < 4 {
+        return None;
+    }
+    let (version, trace_id, parent_id, flags) = (parts[0], parts[1], parts[2], parts[3]);
+    if !is_hex(version, 2) || version == "ff" || (version == "00" && parts.len() != 4) {
+        return None;
+    }
+    if !is_hex(trace_id, 32) || trace_id.bytes().all(|b| b == b'0') {
+        return None;
+    }
+    if !is_hex(parent_id, 16) || parent_id.bytes().all(|b| b == b'0') || !is_hex(flags, 2) {
+        return None;
+    }
+    Some((
+        trace_id.to_string(),
+        parent_id.to_string(),
+        flags.to_string(),
+    ))
+}
+
+/// Span is a running span, it is recorded to the trace of the request when it ends
+pub struct Span {
+    id: String,
+    parent_id: Option<String>,
+    name: String,
+    kind: Kind,
+    start: u128,
+    attributes: Vec<(String, JsonValue)>,
+    error: Option<String>,
+}
+
+impl Span {
+    fn new(name: &str, kind: Kind, parent_id: Option<String>) -> Self {
+        Self {
+            id: crate::random::hex_id(8),
+            parent_id,
+            name: name.to_string(),
+            kind,
+            start: unix_nanos(),
+            attributes: Vec::new(),
+            error: None,
+        }
+    }
+
+    /// attribute sets an attribute, numbers are OTLP int values and others are string values
+    pub fn attribute(&mut self, key: &str, value: impl Into<JsonValue>) {
+        self.attributes.push((key.to_string(), value.into()));
+    }
+
+    /// url_attributes sets the scheme, the host and the path of url, the query and the fragment
+    /// are not exported, they often carry tokens and signed url parameters
+    pub fn url_attributes(&mut self, url: &str) {
+        let uri = match url.parse::<http::Uri>() {
+            Ok(uri) => uri,
+            Err(_) => return,
+        };
+        if let Some(scheme) = uri.scheme_str() {
+            self.attribute("url.scheme", scheme);
+        }
+        if let Some(host) = uri.host() {
+            self.attribute("server.address", host);
+        }
+        self.attribute("url.path", uri.path());
+    }
+
+    /// fail sets the error status of the span
+    pub fn fail(&mut self, message: impl ToString) {
+        self.error = Some(message.to_string());
+    }
+
+    /// traceparent returns the traceparent header of the requests sent in the span,
+    /// None out of a request
+    pub fn traceparent(&self) -> Option<String> {
+        let trace = TRACE.lock().unwrap();
+        trace
+            .as_ref()
+            .map(|trace| format!("00-{}-{}-{}", trace.trace_id, self.id, trace.flags))
+    }
+
+    fn to_json(&self, trace_id: &str) -> JsonValue {
+        let attributes: Vec<JsonValue> = self
+            .attributes
+            .iter()
+            .map(|(key, value)| {
+                let value = match value {
+                    JsonValue::Number(number) if number.is_i64() || number.is_u64() => {
+                        json!({ "intValue": number.to_string() })
+                    }
+                    JsonValue::Number(number) => json!({ "doubleValue": number }),
+                    JsonValue::Bool(value) => json!({ "boolValue": value }),
+                    JsonValue::String(value) => json!({ "stringValue": value }),
+                    value => json!({ "stringValue": value.to_string() }),
+                };
+                json!({ "key": key, "value": value })
+            })
+            .collect();
+        let mut span = json!({
+            "traceId": trace_id,
+            "spanId": self.id,
+            "name": self.name,
+            "kind": self.kind as u8,
+            "startTimeUnixNano": self.start.to_string(),
+            "endTimeUnixNano": unix_nanos().to_string(),
+            "attributes": attributes,
+            "status": match &self.error {
+                Some(message) => json!({ "code": 2, "message": message }),
+                None => json!({ "code": 0 }),
+            },
+        });
+        if let Some(parent_id) = &self.parent_id {
+            span["parentSpanId"] = json!(parent_id);
+        }
+        span
+    }
+
+    /// end records the span to the trace of the request
+    pub fn end(self) {
+        let duration = unix_nanos().saturating_sub(self.start);
+        crate::debug::record_timing(&self.name, Duration::from_nanos(duration as u64));
+        let mut trace = TRACE.lock().unwrap();
+        if let Some(trace) = trace.as_mut() {
+            if trace.spans.len() >= MAX_SPANS {
+                trace.dropped += 1;
+                return;
+            }
+            let span = self.to_json(&trace.trace_id);
+            trace.spans.push(span);
+        }
+    }
+}
+
+/// start starts a span of the running request, it is a child of the handler span
+pub fn start(name: &str, kind: Kind) -> Span {
+    let parent_id = TRACE
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(|trace| trace.root.id.clone());
+    Span::new(name, kind, parent_id)
+}
+
+/// tracestate returns the tracestate of the request, it is propagated with the traceparent
+pub fn tracestate() -> Option<String> {
+    TRACE
+        .lock()
+        .unwrap()
+        .as_ref()
+        .and_then(|trace| trace.state.clone())
+}
+
+/// begin_request starts the handler span, it continues the trace of a valid traceparent or starts a new one
+pub fn begin_request(traceparent: Option<&str>, tracestate: Option<&str>, method: &str, url: &str) {
+    let (trace_id, parent_id, flags, state) = match traceparent.and_then(parse_traceparent) {
+        Some((trace_id, parent_id, flags)) => (
+            trace_id,
+            Some(parent_id),
+            flags,
+            tracestate.map(|state| state.to_string()),
+        ),
+        // a tracestate without a valid traceparent is dropped
+        None => (crate::random::hex_id(16), None, "01".to_string(), None),
+    };
+    let mut root = Span::new("handler", Kind::Server, parent_id);
+    root.attribute("http.request.method", method);
+    root.url_attributes(url);
+    *TRACE.lock().unwrap() = Some(Trace {
+        trace_id,
+        flags,
+        state,
+        root,
+        spans: Vec::new(),
+        dropped: 0,
+    });
+}
+
+/// end_request ends the handler span with the response status and exports the spans
+pub fn end_request(status: u16) {
+    let trace = match TRACE.lock().unwrap().take() {
+        Some(trace) => trace,
+        None => return,
+    };
+    let export = export_mode();
+    if export == Export::Off {
+        return;
+    }
+    let mut root = trace.root;
+    root.attribute("http.response.status_code", status);
+    if trace.dropped > 0 {
+        root.attribute("land.dropped_spans", trace.dropped);
+    }
+    if status >= 500 {
+        root.fail(format!("Response status {}", status));
+    }
+    let mut spans = vec![root.to_json(&trace.trace_id)];
+    spans.extend(trace.spans);
+    let service_name = crate::env::var(SERVICE_NAME_ENV).unwrap_or_else(|| "land-js".to_string());
+    let body = json!({
+        "resourceSpans": [{
+            "resource": {
+                "attributes": [{ "key": "service.name", "value": { "stringValue": service_name } }],
+            },
+            "scopeSpans": [{
+                "scope": { "name": "runtime-land-js", "version": crate::PKG_VERSION },
+                "spans": spans,
+            }],
+        }],
+    });
+    if export == Export::Log {
+        println!("{}", body);
+        return;
+    }
+    let headers = vec![("content-type".to_string(), "application/json".to_string())];
+    if let Err(err) = bindings::call(
+        "POST",
+        "/traces",
+        &headers,
+        Some(body.to_string().into_bytes()),
+    ) {
+        eprintln!("send trace spans error: {:?}", err);
+    }
+}
@@ -0,0 +1,146 @@
+async function handleRequest(request) {
+    const assert = (condition, message) => {
+        if (!condition) {
+            throw new Error(message || "Assertion failed");
+        }
+    };
+
+    const assertEquals = (actual, expected, message) => {
+        assert(
+            actual === expected,
+            message || `Expected ${expected} but got ${actual}`
+        );
+    };
+
+    const hex = (buffer) =>
+        Array.from(new Uint8Array(buffer), (b) => b.toString(16).padStart(2, "0")).join("");
+    const fromHex = (str) =>
+        new Uint8Array(str.match(/.{2}/g).map((b) => parseInt(b, 16)));
+
+    try {
+        const subtle = crypto.subtle;
+        const rawKey = fromHex("000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f");
+        const data = new TextEncoder().encode("hello world, this is a test message!!");
+
+        // Test getRandomValues and randomUUID
+        const random = crypto.getRandomValues(new Uint8Array(32));
+        assertEquals(random.length, 32, "getRandomValues returned wrong length");
+        assert(
+            /^[0-9a-f]{8}-[0-9a-f]{4}-4[0-9a-f]{3}-[89ab][0-9a-f]{3}-[0-9a-f]{12}$/.test(crypto.randomUUID()),
+            "randomUUID is not a v4 uuid"
+        );
+
+        // Test the native helpers are not exposed on the crypto global
+        assert(!("landCrypto" in globalThis), "landCrypto should not be a global");
+        for (const name of ["random_bytes", "aes_encrypt", "key_import", "sign", "pbkdf2", "ecdh"]) {
+            assert(!(name in crypto), `crypto.${name} should not be exposed`);
+        }
+
+        // Test AES-GCM with additionalData, matching browsers and Node
+        const gcmKey = await subtle.importKey("raw", rawKey, "AES-GCM", true, ["encrypt", "decrypt"]);
+        assert(gcmKey instanceof CryptoKey, "importKey did not return a CryptoKey");
+        assertEquals(gcmKey.algorithm.length, 256, "AES-GCM key length is wrong");
+        const gcmParams = {
+            name: "AES-GCM",
+            iv: fromHex("000102030405060708090a0b"),
+            additionalData: new TextEncoder().encode("aad"),
+        };
+        const gcmEncrypted = await subtle.encrypt(gcmParams, gcmKey, data);
+        assertEquals(
+            hex(gcmEncrypted),
+            "2f67ba77aac5b574ff2df3a7919d1004f0f6ee47d01a7f085d1491a5700c73c16077cbdd8e25d78ef011496f603f90c3bf42ba56dd",
+            "AES-GCM encrypt result is wrong"
+        );
+        const gcmDecrypted = await subtle.decrypt(gcmParams, gcmKey, gcmEncrypted);
+        assertEquals(new TextDecoder().decode(gcmDecrypted), "hello world, this is a test message!!");
+
+        // Test AES-GCM with a shorter tag
+        const shortTag = await subtle.encrypt(Object.assign({ tagLength: 96 }, gcmParams), gcmKey, data);
+        assertEquals(shortTag.byteLength, data.length + 12, "AES-GCM tagLength 96 is wrong");
+
+        // Test AES-GCM rejects tampered data
+        const tampered = new Uint8Array(gcmEncrypted.slice(0));
+        tampered[0] ^= 1;
+        let failed = false;
+        try {
+            await subtle.decrypt(gcmParams, gcmKey, tampered);
+        } catch (error) {
+            failed = error.name === "OperationError";
+        }
+        assert(failed, "AES-GCM decrypt should fail with OperationError");
+
+        // Test AES-CBC
+        const cbcKey = await subtle.importKey("raw", rawKey, { name: "AES-CBC" }, false, ["encrypt", "decrypt"]);
+        const cbcParams = { name: "AES-CBC", iv: fromHex("000102030405060708090a0b0c0d0e0f") };
+        const cbcEncrypted = await subtle.encrypt(cbcParams, cbcKey, data);
+        assertEquals(
+            hex(cbcEncrypted),
+            "5d0c2c39c0391c8869c3b859ab3a4766f66236fbc2451a4d1bd7e1c38cec3374e4fccc90940f266f5c6c5bcc8870cd5a",
+            "AES-CBC encrypt result is wrong"
+        );
+        const cbcDecrypted = await subtle.decrypt(cbcParams, cbcKey, cbcEncrypted);
+        assertEquals(new TextDecoder().decode(cbcDecrypted), "hello world, this is a test message!!");
+
+        // Test AES-CTR with a 4 bit counter
+        const ctrKey = await subtle.importKey("raw", rawKey, "AES-CTR", false, ["encrypt", "decrypt"]);
+        const ctrParams = { name: "AES-CTR", counter: fromHex("000102030405060708090a0b0c0d0eff"), length: 4 };
+        const ctrEncrypted = await subtle.encrypt(ctrParams, ctrKey, data);
+        assertEquals(
+            hex(ctrEncrypted),
+            "7ac685dfc1de8025a3129848eb6e0f3fe4ae99c80dfca9304238e2f8dfd1dbddb447196d42",
+            "AES-CTR encrypt result is wrong"
+        );
+
+        // Test JWK export and import
+        const jwk = await subtle.exportKey("jwk", gcmKey);
+        assertEquals(jwk.kty, "oct");
+        assertEquals(jwk.alg, "A256GCM");
+        assertEquals(jwk.k, "AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8");
+        const jwkKey = await subtle.importKey("jwk", jwk, "AES-GCM", true, ["decrypt"]);
+        const jwkDecrypted = await subtle.decrypt(gcmParams, jwkKey, gcmEncrypted);
+        assertEquals(new TextDecoder().decode(jwkDecrypted), "hello world, this is a test message!!");
+
+        // Test non-extractable keys can not be exported
+        failed = false;
+        try {
+            await subtle.exportKey("raw", cbcKey);
+        } catch (error) {
+            failed = error.name === "InvalidAccessError";
+        }
+        assert(failed, "exportKey should fail for non-extractable key");
+
+        // Test generateKey
+        const generated = await subtle.generateKey({ name: "AES-GCM", length: 128 }, true, ["encrypt", "decrypt"]);
+        const generatedRaw = await subtle.exportKey("raw", generated);
+        assertEquals(generatedRaw.byteLength, 16, "generateKey length is wrong");
+
+        // Test AES-KW wrapKey and unwrapKey
+        const kek = await subtle.importKey("raw", rawKey.slice(0, 16), "AES-KW", false, ["wrapKey", "unwrapKey"]);
+        const wrapped = await subtle.wrapKey("raw", gcmKey, kek, "AES-KW");
+        assertEquals(
+            hex(wrapped),
+            "0e7808f506f2c3e7aa6edad793ac4495b093eb482e5c7ca9c170c9faa07dc0cbbb87512e19fd4092",
+            "AES-KW wrapKey result is wrong"
+        );
+        const unwrapped = await subtle.unwrapKey("raw", wrapped, kek, "AES-KW", "AES-GCM", true, ["encrypt"]);
+        assertEquals(hex(await subtle.exportKey("raw", unwrapped)), hex(rawKey), "AES-KW unwrapKey result is wrong");
+
+        // Test wrapKey with AES-GCM and jwk format
+        const gcmWrapKey = await subtle.importKey("raw", rawKey, "AES-GCM", false, ["wrapKey", "unwrapKey"]);
+        const wrappedJwk = await subtle.wrapKey("jwk", generated, gcmWrapKey, gcmParams);
+        const unwrappedJwk = await subtle.unwrapKey("jwk", wrappedJwk, gcmWrapKey, gcmParams, "AES-GCM", true, ["encrypt"]);
+        assertEquals(hex(await subtle.exportKey("raw", unwrappedJwk)), hex(generatedRaw));
+
+        return new Response("All tests passed!", {
+            headers: { "content-type": "text/plain" },
+        });
+    } catch (error) {
+        return new Response(error.message + "\n" + error.stack, { status: 500 });
+    }
+}
+
+export default {
+    async fetch(request) {
+        return handleRequest(request);
+    }
+}
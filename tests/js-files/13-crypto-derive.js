@@ -0,0 +1,135 @@
+async function handleRequest(request) {
+    const assert = (condition, message) => {
+        if (!condition) {
+            throw new Error(message || "Assertion failed");
+        }
+    };
+
+    const assertEquals = (actual, expected, message) => {
+        assert(
+            actual === expected,
+            message || `Expected ${expected} but got ${actual}`
+        );
+    };
+
+    const hex = (buffer) =>
+        Array.from(new Uint8Array(buffer), (b) => b.toString(16).padStart(2, "0")).join("");
+    const fromHex = (str) =>
+        new Uint8Array(str.match(/.{2}/g).map((b) => parseInt(b, 16)));
+
+    try {
+        const subtle = crypto.subtle;
+        const encoder = new TextEncoder();
+
+        // Test PBKDF2 deriveBits, matching browsers and Node
+        const password = await subtle.importKey("raw", encoder.encode("password"), "PBKDF2", false, ["deriveBits"]);
+        const pbkdf2Bits = await subtle.deriveBits(
+            { name: "PBKDF2", hash: "SHA-256", salt: encoder.encode("salt"), iterations: 1000 },
+            password,
+            256
+        );
+        assertEquals(
+            hex(pbkdf2Bits),
+            "632c2812e46d4604102ba7618e9d6d7d2f8128f6266b4a03264d2a0460b7dcb3",
+            "PBKDF2 deriveBits result is wrong"
+        );
+
+        // Test PBKDF2 keys can not be extractable
+        let failed = false;
+        try {
+            await subtle.importKey("raw", encoder.encode("password"), "PBKDF2", true, ["deriveBits"]);
+        } catch (error) {
+            failed = error.name === "SyntaxError";
+        }
+        assert(failed, "importKey should fail for extractable PBKDF2 key");
+
+        // Test PBKDF2 iterations and length are capped
+        for (const [iterations, length] of [[2 ** 20 + 1, 256], [1e12, 256], [1000, 4096 * 8 + 8]]) {
+            failed = false;
+            try {
+                await subtle.deriveBits({ name: "PBKDF2", hash: "SHA-256", salt: encoder.encode("salt"), iterations }, password, length);
+            } catch (error) {
+                failed = error.name === "OperationError";
+            }
+            assert(failed, `PBKDF2 deriveBits should fail for ${iterations} iterations and length ${length}`);
+        }
+
+        // Test HKDF deriveBits and deriveKey
+        const hkdfParams = { name: "HKDF", hash: "SHA-256", salt: encoder.encode("salt"), info: encoder.encode("info") };
+        const ikm = await subtle.importKey("raw", encoder.encode("input key material"), "HKDF", false, ["deriveBits", "deriveKey"]);
+        const hkdfBits = await subtle.deriveBits(hkdfParams, ikm, 384);
+        assertEquals(
+            hex(hkdfBits),
+            "f1840c1f388c8fc7a9137c561360962da050de51672f0d7c9fee3ff15c251fec952b1167ce74a43929bf04d2decbb65d",
+            "HKDF deriveBits result is wrong"
+        );
+        const derivedKey = await subtle.deriveKey(hkdfParams, ikm, { name: "AES-GCM", length: 128 }, true, ["encrypt"]);
+        assertEquals(derivedKey.algorithm.name, "AES-GCM");
+        assertEquals(hex(await subtle.exportKey("raw", derivedKey)), "f1840c1f388c8fc7a9137c561360962d", "HKDF deriveKey result is wrong");
+
+        // Test X25519 with the RFC 7748 vector
+        const alicePrivate = await subtle.importKey(
+            "pkcs8",
+            fromHex("302e020100300506032b656e0422042077076d0a7318a57d3c16c17251b26645df4c2f87ebc0992ab177fba51db92c2a"),
+            "X25519",
+            false,
+            ["deriveBits"]
+        );
+        const bobPublic = await subtle.importKey(
+            "raw",
+            fromHex("de9edb7d7b7dc1b4d35b61c2ece435373f8343c85b78674dadfc7e146f882b4f"),
+            "X25519",
+            true,
+            []
+        );
+        const x25519Bits = await subtle.deriveBits({ name: "X25519", public: bobPublic }, alicePrivate, 256);
+        assertEquals(
+            hex(x25519Bits),
+            "4a5d9d5ba4ce2de1728e3bf480350f25e07e21c947d19e3376f09b3c1e161742",
+            "X25519 deriveBits result is wrong"
+        );
+
+        // Test ECDH key agreement between generated key pairs
+        for (const namedCurve of ["P-256", "P-384"]) {
+            const alice = await subtle.generateKey({ name: "ECDH", namedCurve }, false, ["deriveBits", "deriveKey"]);
+            const bob = await subtle.generateKey({ name: "ECDH", namedCurve }, false, ["deriveBits", "deriveKey"]);
+            assertEquals(alice.publicKey.usages.length, 0, "ECDH public key should have no usages");
+            const aliceBits = await subtle.deriveBits({ name: "ECDH", public: bob.publicKey }, alice.privateKey, null);
+            const bobBits = await subtle.deriveBits({ name: "ECDH", public: alice.publicKey }, bob.privateKey, null);
+            assertEquals(aliceBits.byteLength, namedCurve === "P-256" ? 32 : 48, `${namedCurve} secret length is wrong`);
+            assertEquals(hex(aliceBits), hex(bobBits), `${namedCurve} shared secrets differ`);
+
+            const aesKey = await subtle.deriveKey(
+                { name: "ECDH", public: bob.publicKey },
+                alice.privateKey,
+                { name: "AES-GCM", length: 256 },
+                true,
+                ["encrypt"]
+            );
+            assertEquals(hex(await subtle.exportKey("raw", aesKey)), hex(aliceBits.slice(0, 32)), `${namedCurve} deriveKey result is wrong`);
+        }
+
+        // Test ECDH rejects a public key on another curve
+        const p256 = await subtle.generateKey({ name: "ECDH", namedCurve: "P-256" }, false, ["deriveBits"]);
+        const p384 = await subtle.generateKey({ name: "ECDH", namedCurve: "P-384" }, false, ["deriveBits"]);
+        failed = false;
+        try {
+            await subtle.deriveBits({ name: "ECDH", public: p384.publicKey }, p256.privateKey, 256);
+        } catch (error) {
+            failed = error.name === "InvalidAccessError";
+        }
+        assert(failed, "deriveBits should fail for mismatched curves");
+
+        return new Response("All tests passed!", {
+            headers: { "content-type": "text/plain" },
+        });
+    } catch (error) {
+        return new Response(error.message + "\n" + error.stack, { status: 500 });
+    }
+}
+
+export default {
+    async fetch(request) {
+        return handleRequest(request);
+    }
+}
@@ -0,0 +1,119 @@
+import { argon2id, bcrypt, scrypt, verify } from "land:password";
+
+async function handleRequest(request) {
+    const assert = (condition, message) => {
+        if (!condition) {
+            throw new Error(message || "Assertion failed");
+        }
+    };
+
+    const assertEquals = (actual, expected, message) => {
+        assert(
+            actual === expected,
+            message || `Expected ${expected} but got ${actual}`
+        );
+    };
+
+    try {
+        if (new URL(request.url).pathname === "/budget") {
+            return await handleBudget(assert);
+        }
+
+        // Test argon2id hash and verify
+        const argon2Hash = await argon2id.hash("correct horse", { memoryCost: 1024, timeCost: 2 });
+        assert(argon2Hash.startsWith("$argon2id$v=19$m=1024,t=2,p=1$"), "argon2id hash is not a PHC string");
+        assert(await argon2id.verify("correct horse", argon2Hash), "argon2id verify failed");
+        assert(!(await argon2id.verify("wrong horse", argon2Hash)), "argon2id verify should fail");
+        assert(argon2Hash !== (await argon2id.hash("correct horse", { memoryCost: 1024 })), "argon2id salt is not random");
+
+        // Test argon2id verifies hashes from other implementations
+        assert(
+            await argon2id.verify(
+                "correct horse",
+                "$argon2id$v=19$m=1024,t=2,p=1$bGFuZHNhbHRsYW5kc2FsdA$BlXBiHA1ZjUZ9gM8HRqV+lNYp0sjHVxtU0I9xAhlVTc"
+            ),
+            "argon2id verify known hash failed"
+        );
+
+        // Test bcrypt hash and verify
+        const bcryptHash = await bcrypt.hash("correct horse", { cost: 4 });
+        assert(bcryptHash.startsWith("$2b$04$"), "bcrypt hash prefix is wrong");
+        assertEquals(bcryptHash.length, 60, "bcrypt hash length is wrong");
+        assert(await bcrypt.verify("correct horse", bcryptHash), "bcrypt verify failed");
+        assert(!(await bcrypt.verify("wrong horse", bcryptHash)), "bcrypt verify should fail");
+        assert(
+            await bcrypt.verify("U*U", "$2a$05$CCCCCCCCCCCCCCCCCCCCC.E5YPO9kmyuRGyh0XouQYb4YMJKvyOeW"),
+            "bcrypt verify known hash failed"
+        );
+
+        // Test scrypt hash and verify
+        const scryptHash = await scrypt.hash("correct horse", { logN: 10 });
+        assert(scryptHash.startsWith("$scrypt$ln=10,r=8,p=1$"), "scrypt hash is not a PHC string");
+        assert(await scrypt.verify("correct horse", scryptHash), "scrypt verify failed");
+        assert(
+            await scrypt.verify(
+                "correct horse",
+                "$scrypt$ln=10,r=8,p=1$bGFuZHNhbHRsYW5kc2FsdA$C5JgUj/scX4ykr+rV0gU6oxs3k3qemRiPVNwUbqy7qg"
+            ),
+            "scrypt verify known hash failed"
+        );
+
+        // Test verify detects the algorithm
+        assert(await verify("correct horse", argon2Hash), "verify argon2id failed");
+        assert(await verify("correct horse", bcryptHash), "verify bcrypt failed");
+        assert(!(await verify("wrong horse", scryptHash)), "verify scrypt should fail");
+
+        // Test cost limits are enforced for hash and verify
+        let failed = false;
+        try {
+            await bcrypt.hash("correct horse", { cost: 20 });
+        } catch (error) {
+            failed = true;
+        }
+        assert(failed, "bcrypt hash should fail for cost 20");
+        failed = false;
+        try {
+            await argon2id.verify(
+                "correct horse",
+                "$argon2id$v=19$m=4194304,t=2,p=1$bGFuZHNhbHRsYW5kc2FsdA$BlXBiHA1ZjUZ9gM8HRqV+lNYp0sjHVxtU0I9xAhlVTc"
+            );
+        } catch (error) {
+            failed = true;
+        }
+        assert(failed, "argon2id verify should fail for memory cost over the limit");
+
+        return new Response("All tests passed!", {
+            headers: { "content-type": "text/plain" },
+        });
+    } catch (error) {
+        return new Response(error.message + "\n" + error.stack, { status: 500 });
+    }
+}
+
+// handleBudget spends the hashing budget of the request, the budget is reset for the next request
+async function handleBudget(assert) {
+    for (let i = 0; i < 3; i++) {
+        await argon2id.hash("correct horse", { memoryCost: 65536, timeCost: 10 });
+    }
+    let message = "";
+    try {
+        await argon2id.hash("correct horse", { memoryCost: 65536, timeCost: 3 });
+    } catch (error) {
+        message = error.message;
+    }
+    assert(message.includes("budget"), `argon2id hash should fail over the request budget, got ${message}`);
+    // the rejected call is not charged, a call that fits the rest of the budget still runs
+    assert(
+        (await bcrypt.hash("correct horse", { cost: 4 })).startsWith("$2b$04$"),
+        "bcrypt hash should fit the rest of the budget"
+    );
+    return new Response("All tests passed!", {
+        headers: { "content-type": "text/plain" },
+    });
+}
+
+export default {
+    async fetch(request) {
+        return handleRequest(request);
+    }
+}
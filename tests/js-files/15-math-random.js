@@ -0,0 +1,58 @@
+// initValue is generated in wizer.initialize, before the snapshot is taken
+const initValue = Math.random();
+
+// firstValue is the first value of the instance restored from the snapshot
+let firstValue = null;
+
+async function handleRequest(request) {
+    if (firstValue === null) {
+        firstValue = Math.random();
+    }
+
+    // ?sequence=n returns the first value of the instance and n - 1 next values
+    const url = new URL(request.url);
+    if (url.searchParams.has("sequence")) {
+        const sequence = [firstValue];
+        for (let i = 1; i < Number(url.searchParams.get("sequence")); i++) {
+            sequence.push(Math.random());
+        }
+        return new Response(JSON.stringify(sequence), {
+            headers: { "content-type": "application/json" },
+        });
+    }
+
+    const assert = (condition, message) => {
+        if (!condition) {
+            throw new Error(message || "Assertion failed");
+        }
+    };
+
+    try {
+        // Test Math.random returns floats in [0, 1)
+        const values = [];
+        for (let i = 0; i < 1000; i++) {
+            const value = Math.random();
+            assert(typeof value === "number" && value >= 0 && value < 1, `Math.random returned ${value}`);
+            values.push(value);
+        }
+        assert(new Set(values).size === values.length, "Math.random returned duplicate values");
+        assert(values.indexOf(initValue) === -1, "Math.random repeats the sequence from init");
+
+        // Test values are spread over the range
+        const mean = values.reduce((a, b) => a + b, 0) / values.length;
+        assert(mean > 0.4 && mean < 0.6, `Math.random mean ${mean} is not near 0.5`);
+        assert(Math.random.name === "random", "Math.random name is wrong");
+
+        return new Response("All tests passed!", {
+            headers: { "content-type": "text/plain" },
+        });
+    } catch (error) {
+        return new Response(error.message + "\n" + error.stack, { status: 500 });
+    }
+}
+
+export default {
+    async fetch(request) {
+        return handleRequest(request);
+    }
+}
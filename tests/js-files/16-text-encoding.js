@@ -0,0 +1,126 @@
+async function handleRequest(request) {
+    const assert = (condition, message) => {
+        if (!condition) {
+            throw new Error(message || "Assertion failed");
+        }
+    };
+
+    const assertEquals = (actual, expected, message) => {
+        assert(
+            actual === expected,
+            message || `Expected ${expected} but got ${actual}`
+        );
+    };
+
+    const hex = (bytes) => Array.from(bytes, (b) => b.toString(16).padStart(2, "0")).join("");
+
+    try {
+        // Test TextEncoder
+        const encoder = new TextEncoder();
+        assertEquals(encoder.encoding, "utf-8");
+        assertEquals(hex(encoder.encode("héllo €𝄞")), "68c3a96c6c6f20e282acf09d849e", "encode result is wrong");
+        assertEquals(hex(encoder.encode("a\uD800b")), "61efbfbd62", "lone surrogate should encode as U+FFFD");
+        assertEquals(encoder.encode().length, 0, "encode without input should be empty");
+
+        // Test TextEncoder.encodeInto stops before a character that does not fit
+        const target = new Uint8Array(5);
+        const result = encoder.encodeInto("ab€c", target);
+        assertEquals(result.read, 3, "encodeInto read is wrong");
+        assertEquals(result.written, 5, "encodeInto written is wrong");
+        const small = encoder.encodeInto("€", new Uint8Array(2));
+        assertEquals(small.read, 0);
+        assertEquals(small.written, 0);
+
+        // Test TextDecoder with utf-8, BOM and views
+        assertEquals(new TextDecoder().decode(new Uint8Array([0xef, 0xbb, 0xbf, 0x68, 0x69])), "hi", "BOM should be removed");
+        assertEquals(
+            new TextDecoder("utf-8", { ignoreBOM: true }).decode(new Uint8Array([0xef, 0xbb, 0xbf, 0x68])),
+            "﻿h",
+            "BOM should be kept with ignoreBOM"
+        );
+        const view = new Uint8Array([0x00, 0x68, 0x69, 0x00]).subarray(1, 3);
+        assertEquals(new TextDecoder().decode(view), "hi", "decode of subarray is wrong");
+        assertEquals(new TextDecoder().decode(new Uint8Array([0x61, 0xff])), "a�", "invalid byte should be replaced");
+
+        // Test fatal mode throws TypeError
+        let failed = false;
+        try {
+            new TextDecoder("utf-8", { fatal: true }).decode(new Uint8Array([0x61, 0xff]));
+        } catch (error) {
+            failed = error instanceof TypeError;
+        }
+        assert(failed, "fatal decode should throw TypeError");
+
+        // Test unknown labels throw RangeError
+        failed = false;
+        try {
+            new TextDecoder("no-such-encoding");
+        } catch (error) {
+            failed = error instanceof RangeError;
+        }
+        assert(failed, "unknown label should throw RangeError");
+
+        // Test legacy encodings and label normalization
+        assertEquals(new TextDecoder(" Latin1 ").encoding, "windows-1252");
+        assertEquals(new TextDecoder("windows-1252").decode(new Uint8Array([0x80, 0xe9])), "€é");
+        assertEquals(new TextDecoder("iso-8859-2").decode(new Uint8Array([0xa3, 0xf3, 0xb3])), "Łół");
+        assertEquals(new TextDecoder("shift_jis").decode(new Uint8Array([0x82, 0xa0, 0x82, 0xa2])), "あい");
+        assertEquals(new TextDecoder("gbk").decode(new Uint8Array([0xc4, 0xe3, 0xba, 0xc3])), "你好");
+        assertEquals(new TextDecoder("euc-kr").decode(new Uint8Array([0xc7, 0xd1, 0xb1, 0xdb])), "한글");
+        assertEquals(new TextDecoder("utf-16le").decode(new Uint8Array([0x68, 0x00, 0x69, 0x00])), "hi");
+        assertEquals(new TextDecoder("utf-16be").decode(new Uint8Array([0x00, 0x68, 0x00, 0x69])), "hi");
+
+        // Test stream decoding across chunk boundaries
+        const streamDecoder = new TextDecoder();
+        const euro = encoder.encode("€");
+        let text = streamDecoder.decode(euro.subarray(0, 1), { stream: true });
+        text += streamDecoder.decode(euro.subarray(1, 2), { stream: true });
+        text += streamDecoder.decode(euro.subarray(2), { stream: true });
+        text += streamDecoder.decode();
+        assertEquals(text, "€", "stream decode across chunks is wrong");
+        const sjisDecoder = new TextDecoder("shift_jis");
+        text = sjisDecoder.decode(new Uint8Array([0x82]), { stream: true });
+        text += sjisDecoder.decode(new Uint8Array([0xa0]));
+        assertEquals(text, "あ", "shift_jis stream decode is wrong");
+
+        // Test unfinished stream is flushed with replacement
+        const flushDecoder = new TextDecoder();
+        text = flushDecoder.decode(euro.subarray(0, 2), { stream: true });
+        text += flushDecoder.decode();
+        assertEquals(text, "�", "flush of incomplete sequence is wrong");
+
+        // Test TextEncoderStream and TextDecoderStream
+        const stream = new ReadableStream({
+            start(controller) {
+                controller.enqueue("hello ");
+                controller.enqueue("\uD834");
+                controller.enqueue("\uDD1E world");
+                controller.close();
+            },
+        })
+            .pipeThrough(new TextEncoderStream())
+            .pipeThrough(new TextDecoderStream());
+        let output = "";
+        const reader = stream.getReader();
+        while (true) {
+            const { value, done } = await reader.read();
+            if (done) {
+                break;
+            }
+            output += value;
+        }
+        assertEquals(output, "hello 𝄞 world", "text streams result is wrong");
+
+        return new Response("All tests passed!", {
+            headers: { "content-type": "text/plain" },
+        });
+    } catch (error) {
+        return new Response(error.message + "\n" + error.stack, { status: 500 });
+    }
+}
+
+export default {
+    async fetch(request) {
+        return handleRequest(request);
+    }
+}
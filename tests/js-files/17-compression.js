@@ -0,0 +1,103 @@
+async function handleRequest(request) {
+    const assert = (condition, message) => {
+        if (!condition) {
+            throw new Error(message || "Assertion failed");
+        }
+    };
+
+    const assertEquals = (actual, expected, message) => {
+        assert(
+            actual === expected,
+            message || `Expected ${expected} but got ${actual}`
+        );
+    };
+
+    const fromHex = (str) =>
+        new Uint8Array(str.match(/.{2}/g).map((b) => parseInt(b, 16)));
+
+    // pipe writes chunks through a transform stream and returns the output bytes
+    const pipe = async (chunks, transform) => {
+        const stream = new ReadableStream({
+            start(controller) {
+                for (const chunk of chunks) {
+                    controller.enqueue(chunk);
+                }
+                controller.close();
+            },
+        }).pipeThrough(transform);
+        return new Uint8Array(await new Response(stream).arrayBuffer());
+    };
+
+    try {
+        const text = "hello compression stream, ".repeat(100);
+        const data = new TextEncoder().encode(text);
+
+        // Test compress and decompress round trip
+        for (const format of ["gzip", "deflate", "deflate-raw"]) {
+            const compressed = await pipe([data.subarray(0, 100), data.subarray(100)], new CompressionStream(format));
+            assert(compressed.length < data.length, `${format} did not compress`);
+            const decompressed = await pipe([compressed], new DecompressionStream(format));
+            assertEquals(new TextDecoder().decode(decompressed), text, `${format} round trip is wrong`);
+        }
+
+        // Test gzip header and decompressing one byte at a time
+        const gzip = await pipe([data], new CompressionStream("gzip"));
+        assertEquals(gzip[0], 0x1f, "gzip magic is wrong");
+        assertEquals(gzip[1], 0x8b, "gzip magic is wrong");
+        const bytes = Array.from(gzip, (b) => new Uint8Array([b]));
+        const gunzipped = await pipe(bytes, new DecompressionStream("gzip"));
+        assertEquals(new TextDecoder().decode(gunzipped), text, "gzip byte chunks are wrong");
+
+        // Test decompressing payloads from other implementations
+        const decode = async (format, hex) =>
+            new TextDecoder().decode(await pipe([fromHex(hex)], new DecompressionStream(format)));
+        assertEquals(await decode("gzip", "1f8b0800000000000003cb48cdc9c95748afca2c0000196ad2df0a000000"), "hello gzip");
+        assertEquals(await decode("br", "8b058068656c6c6f2062726f746c6903"), "hello brotli");
+        assertEquals(await decode("zstd", "28b52ffd045851000068656c6c6f207a737464cfdb609c"), "hello zstd");
+
+        // Test decoding zstd one byte at a time
+        const zstd = Array.from(fromHex("28b52ffd045851000068656c6c6f207a737464cfdb609c"), (b) => new Uint8Array([b]));
+        assertEquals(new TextDecoder().decode(await pipe(zstd, new DecompressionStream("zstd"))), "hello zstd");
+
+        // Test the decompressed output is limited
+        const zeros = new Uint8Array(1024 * 1024);
+        const bomb = await pipe(new Array(65).fill(zeros), new CompressionStream("gzip"));
+        let limited = false;
+        try {
+            await pipe([bomb], new DecompressionStream("gzip"));
+        } catch (error) {
+            limited = error.message.includes("exceeds");
+        }
+        assert(limited, "decompressed output should be limited");
+
+        // Test unsupported formats
+        let failed = false;
+        try {
+            new CompressionStream("br");
+        } catch (error) {
+            failed = error instanceof TypeError;
+        }
+        assert(failed, "CompressionStream should not support br");
+
+        // Test corrupt data errors the stream
+        failed = false;
+        try {
+            await pipe([new Uint8Array([1, 2, 3, 4, 5, 6, 7, 8])], new DecompressionStream("gzip"));
+        } catch (error) {
+            failed = true;
+        }
+        assert(failed, "corrupt gzip data should fail");
+
+        return new Response("All tests passed!", {
+            headers: { "content-type": "text/plain" },
+        });
+    } catch (error) {
+        return new Response(error.message + "\n" + error.stack, { status: 500 });
+    }
+}
+
+export default {
+    async fetch(request) {
+        return handleRequest(request);
+    }
+}
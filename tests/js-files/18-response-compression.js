@@ -0,0 +1,23 @@
+async function handleRequest(request) {
+    const url = new URL(request.url);
+    if (url.pathname === "/small") {
+        return new Response("small body", {
+            headers: { "content-type": "text/plain" },
+        });
+    }
+    if (url.pathname === "/encoded") {
+        return new Response("x".repeat(4096), {
+            headers: { "content-type": "text/plain", "content-encoding": "identity" },
+        });
+    }
+    return new Response("<p>hello compression</p>\n".repeat(200), {
+        headers: { "content-type": "text/html; charset=utf-8" },
+    });
+}
+
+export default {
+    compression: "auto",
+    async fetch(request) {
+        return handleRequest(request);
+    }
+}
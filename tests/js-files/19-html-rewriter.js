@@ -0,0 +1,158 @@
+async function handleRequest(request) {
+    const assert = (condition, message) => {
+        if (!condition) {
+            throw new Error(message || "Assertion failed");
+        }
+    };
+
+    const assertEquals = (actual, expected, message) => {
+        assert(
+            actual === expected,
+            message || `Expected ${expected} but got ${actual}`
+        );
+    };
+
+    const page = `<!DOCTYPE html><html><head><title>Land</title></head>` +
+        `<body><!-- nav --><a href="/docs">Docs</a><a href="https://example.com/">Out</a>` +
+        `<p class="ad">buy</p><div id="content">old</div></body></html>`;
+
+    try {
+        // Test element handlers rewrite a buffered body
+        let rewriter = new HTMLRewriter()
+            .on("a[href^='/']", {
+                element(element) {
+                    assertEquals(element.tagName, "a");
+                    element.setAttribute("href", "/proxy" + element.getAttribute("href"));
+                    assertEquals(element.getAttribute("href"), "/proxy/docs");
+                },
+            })
+            .on("head", {
+                element(element) {
+                    element.append('<script src="/analytics.js"></script>', { html: true });
+                },
+            })
+            .on("p.ad", {
+                element(element) {
+                    element.remove();
+                    assert(element.removed, "removed element should be marked");
+                },
+            })
+            .on("#content", {
+                element(element) {
+                    element.setInnerContent("<b>new</b>", { html: true });
+                    element.prepend("<i>escaped</i>");
+                },
+            });
+        let response = rewriter.transform(new Response(page, {
+            headers: { "content-type": "text/html", "content-length": String(page.length) },
+        }));
+        assertEquals(response.headers.get("content-type"), "text/html");
+        assertEquals(response.headers.get("content-length"), null);
+        let html = await response.text();
+        assert(html.includes('<a href="/proxy/docs">Docs</a>'), html);
+        assert(html.includes('<a href="https://example.com/">Out</a>'), html);
+        assert(html.includes('<script src="/analytics.js"></script></head>'), html);
+        assert(!html.includes("buy"), html);
+        assert(html.includes('<div id="content">&lt;i&gt;escaped&lt;/i&gt;<b>new</b></div>'), html);
+
+        // Test text and comment handlers
+        let texts = [];
+        html = await new HTMLRewriter()
+            .on("title", {
+                text(text) {
+                    texts.push(text.text);
+                    if (text.text) {
+                        text.replace("Land Proxy");
+                    }
+                },
+            })
+            .on("body", {
+                comments(comment) {
+                    assertEquals(comment.text, " nav ");
+                    comment.remove();
+                },
+            })
+            .transform(new Response(page))
+            .text();
+        assertEquals(texts.join(""), "Land");
+        assert(html.includes("<title>Land Proxy</title>"), html);
+        assert(!html.includes("<!--"), html);
+
+        // Test document handlers
+        let doctype = null;
+        html = await new HTMLRewriter()
+            .onDocument({
+                doctype(value) {
+                    doctype = value.name;
+                },
+                end(end) {
+                    end.append("<!-- end -->", { html: true });
+                },
+            })
+            .transform(new Response(page))
+            .text();
+        assertEquals(doctype, "html");
+        assert(html.endsWith("</html><!-- end -->"), html);
+
+        // Test a streamed body split inside a tag
+        let chunks = ['<ul><li cla', 'ss="x">one</li><li>tw', 'o</li></ul>'];
+        let stream = new ReadableStream({
+            start(controller) {
+                for (const chunk of chunks) {
+                    controller.enqueue(new TextEncoder().encode(chunk));
+                }
+                controller.close();
+            },
+        });
+        let count = 0;
+        html = await new HTMLRewriter()
+            .on("li", {
+                element(element) {
+                    count++;
+                    element.setAttribute("data-index", String(count));
+                    element.removeAttribute("class");
+                },
+            })
+            .transform(new Response(stream))
+            .text();
+        assertEquals(count, 2);
+        assertEquals(html, '<ul><li data-index="1">one</li><li data-index="2">two</li></ul>');
+
+        // Test handler errors fail the body
+        let failed = false;
+        try {
+            await new HTMLRewriter()
+                .on("a", {
+                    element() {
+                        throw new Error("handler failed");
+                    },
+                })
+                .transform(new Response(page))
+                .text();
+        } catch (error) {
+            failed = error.message === "handler failed";
+        }
+        assert(failed, "handler error should be thrown");
+
+        // Test invalid selectors are rejected
+        failed = false;
+        try {
+            new HTMLRewriter().on("a[", { element() {} }).transform(new Response(page));
+        } catch (error) {
+            failed = true;
+        }
+        assert(failed, "invalid selector should fail");
+
+        return new Response("All tests passed!", {
+            headers: { "content-type": "text/plain" },
+        });
+    } catch (error) {
+        return new Response(error.message + "\n" + error.stack, { status: 500 });
+    }
+}
+
+export default {
+    async fetch(request) {
+        return handleRequest(request);
+    }
+}
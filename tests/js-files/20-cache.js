@@ -0,0 +1,119 @@
+async function handleRequest(request) {
+    const assert = (condition, message) => {
+        if (!condition) {
+            throw new Error(message || "Assertion failed");
+        }
+    };
+
+    const assertEquals = (actual, expected, message) => {
+        assert(
+            actual === expected,
+            message || `Expected ${expected} but got ${actual}`
+        );
+    };
+
+    // every request uses new keys, the cache may be shared with earlier runs
+    const base = `https://cache.test/${crypto.randomUUID()}`;
+
+    try {
+        const cache = caches.default;
+        assert(cache instanceof Cache, "caches.default should be a Cache");
+        assertEquals(await cache.match(`${base}/missing`), undefined);
+
+        // Test put and match
+        let response = new Response("cached body", {
+            status: 201,
+            headers: { "content-type": "text/plain", "cache-control": "max-age=60" },
+        });
+        await cache.put(`${base}/a`, response.clone());
+        assertEquals(await response.text(), "cached body");
+        let cached = await cache.match(new Request(`${base}/a`));
+        assert(cached, "response should be cached");
+        assertEquals(cached.status, 201);
+        assertEquals(cached.headers.get("content-type"), "text/plain");
+        assertEquals(await cached.text(), "cached body");
+
+        // Test the url fragment is not part of the key
+        assert(await cache.match(`${base}/a#top`), "fragment should be ignored");
+
+        // Test only GET requests are matched
+        assertEquals(await cache.match(new Request(`${base}/a`, { method: "POST" })), undefined);
+        assert(await cache.match(new Request(`${base}/a`, { method: "POST" }), { ignoreMethod: true }));
+
+        // Test delete
+        assertEquals(await cache.delete(`${base}/a`), true);
+        assertEquals(await cache.delete(`${base}/a`), false);
+        assertEquals(await cache.match(`${base}/a`), undefined);
+
+        // Test responses that must not be stored
+        for (const headers of [
+            { "cache-control": "no-store" },
+            { "cache-control": "private, max-age=60" },
+            { "cache-control": "max-age=0" },
+            { "set-cookie": "session=1" },
+        ]) {
+            await cache.put(`${base}/skip`, new Response("skip", { headers }));
+            assertEquals(await cache.match(`${base}/skip`), undefined, JSON.stringify(headers));
+        }
+
+        // Test Vary selects the request headers
+        await cache.put(
+            new Request(`${base}/vary`, { headers: { "accept-language": "en" } }),
+            new Response("english", { headers: { vary: "Accept-Language" } }),
+        );
+        cached = await cache.match(new Request(`${base}/vary`, { headers: { "accept-language": "en" } }));
+        assertEquals(await cached.text(), "english");
+        cached = await cache.match(new Request(`${base}/vary`, { headers: { "accept-language": "fr" } }));
+        assertEquals(cached, undefined);
+        cached = await cache.match(new Request(`${base}/vary`), { ignoreVary: true });
+        assertEquals(await cached.text(), "english");
+        assertEquals(cached.headers.get("vary"), "Accept-Language");
+
+        // Test every Vary variant is kept, the deleted variants are not matched again
+        const varyRequest = (language) => new Request(`${base}/vary`, { headers: { "accept-language": language } });
+        await cache.put(varyRequest("fr"), new Response("french", { headers: { vary: "Accept-Language" } }));
+        assertEquals(await (await cache.match(varyRequest("en"))).text(), "english");
+        assertEquals(await (await cache.match(varyRequest("fr"))).text(), "french");
+        assertEquals(await (await cache.match(`${base}/vary`, { ignoreVary: true })).text(), "french");
+        assertEquals(await cache.match(varyRequest("de")), undefined);
+        assert(await cache.delete(`${base}/vary`), "delete should remove the vary entry");
+        await cache.put(varyRequest("en"), new Response("english again", { headers: { vary: "Accept-Language" } }));
+        assertEquals(await cache.match(varyRequest("fr")), undefined);
+        assertEquals(await (await cache.match(varyRequest("en"))).text(), "english again");
+
+        // Test invalid puts
+        let failed = false;
+        try {
+            await cache.put(`${base}/partial`, new Response("part", { status: 206 }));
+        } catch (error) {
+            failed = error instanceof TypeError;
+        }
+        assert(failed, "206 response should be rejected");
+        failed = false;
+        try {
+            await cache.put(`${base}/star`, new Response("star", { headers: { vary: "*" } }));
+        } catch (error) {
+            failed = error instanceof TypeError;
+        }
+        assert(failed, "Vary: * response should be rejected");
+
+        // Test named caches are separated
+        const named = await caches.open("named");
+        assert(named === await caches.open("named"), "open should return the same cache");
+        await named.put(`${base}/named`, new Response("named"));
+        assertEquals(await cache.match(`${base}/named`), undefined);
+        assertEquals(await (await named.match(`${base}/named`)).text(), "named");
+
+        return new Response("All tests passed!", {
+            headers: { "content-type": "text/plain" },
+        });
+    } catch (error) {
+        return new Response(error.message + "\n" + error.stack, { status: 500 });
+    }
+}
+
+export default {
+    async fetch(request) {
+        return handleRequest(request);
+    }
+}
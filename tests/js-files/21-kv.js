@@ -0,0 +1,97 @@
+async function handleRequest(request, env) {
+    const assert = (condition, message) => {
+        if (!condition) {
+            throw new Error(message || "Assertion failed");
+        }
+    };
+
+    const assertEquals = (actual, expected, message) => {
+        assert(
+            actual === expected,
+            message || `Expected ${expected} but got ${actual}`
+        );
+    };
+
+    // every request uses new keys, the namespace may be shared with earlier runs
+    const prefix = `${crypto.randomUUID()}:`;
+
+    try {
+        const kv = env.SESSIONS;
+        assert(kv instanceof KVNamespace, "env.SESSIONS should be a KVNamespace");
+        assertEquals(env.MISSING_BINDING, null);
+
+        // Test get of a missing key
+        assertEquals(await kv.get(`${prefix}missing`), null);
+
+        // Test text, json and arrayBuffer values
+        await kv.put(`${prefix}text`, "hello kv");
+        assertEquals(await kv.get(`${prefix}text`), "hello kv");
+        await kv.put(`${prefix}json`, JSON.stringify({ user: "land", admin: true }));
+        let session = await kv.get(`${prefix}json`, { type: "json" });
+        assertEquals(session.user, "land");
+        assertEquals(session.admin, true);
+        await kv.put(`${prefix}bytes`, new Uint8Array([1, 2, 3]));
+        let bytes = new Uint8Array(await kv.get(`${prefix}bytes`, "arrayBuffer"));
+        assertEquals(bytes.join(","), "1,2,3");
+        let stream = await kv.get(`${prefix}text`, "stream");
+        assertEquals(await new Response(stream).text(), "hello kv");
+
+        // Test metadata and expiration
+        await kv.put(`${prefix}meta`, "value", {
+            expirationTtl: 3600,
+            metadata: { flag: "beta", rollout: 0.5 },
+        });
+        let { value, metadata } = await kv.getWithMetadata(`${prefix}meta`);
+        assertEquals(value, "value");
+        assertEquals(metadata.flag, "beta");
+        assertEquals(metadata.rollout, 0.5);
+        assertEquals((await kv.getWithMetadata(`${prefix}text`)).metadata, null);
+
+        // Test delete
+        await kv.delete(`${prefix}text`);
+        assertEquals(await kv.get(`${prefix}text`), null);
+
+        // Test list with prefix and cursor
+        for (const name of ["a", "b", "c"]) {
+            await kv.put(`${prefix}list:${name}`, name, { metadata: { name } });
+        }
+        let page = await kv.list({ prefix: `${prefix}list:`, limit: 2 });
+        assertEquals(page.keys.length, 2);
+        assertEquals(page.keys[0].name, `${prefix}list:a`);
+        assertEquals(page.keys[1].metadata.name, "b");
+        assertEquals(page.list_complete, false);
+        page = await kv.list({ prefix: `${prefix}list:`, cursor: page.cursor });
+        assertEquals(page.keys.length, 1);
+        assertEquals(page.keys[0].name, `${prefix}list:c`);
+        assertEquals(page.list_complete, true);
+        assertEquals(page.cursor, undefined);
+
+        // Test invalid options
+        let failed = false;
+        try {
+            await kv.put(`${prefix}ttl`, "value", { expirationTtl: -1 });
+        } catch (error) {
+            failed = error instanceof TypeError;
+        }
+        assert(failed, "negative expirationTtl should fail");
+        failed = false;
+        try {
+            await kv.get("");
+        } catch (error) {
+            failed = error instanceof TypeError;
+        }
+        assert(failed, "empty key should fail");
+
+        return new Response("All tests passed!", {
+            headers: { "content-type": "text/plain" },
+        });
+    } catch (error) {
+        return new Response(error.message + "\n" + error.stack, { status: 500 });
+    }
+}
+
+export default {
+    async fetch(request, env) {
+        return handleRequest(request, env);
+    }
+}
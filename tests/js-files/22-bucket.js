@@ -0,0 +1,134 @@
+async function handleRequest(request, env) {
+    const assert = (condition, message) => {
+        if (!condition) {
+            throw new Error(message || "Assertion failed");
+        }
+    };
+
+    const assertEquals = (actual, expected, message) => {
+        assert(
+            actual === expected,
+            message || `Expected ${expected} but got ${actual}`
+        );
+    };
+
+    // every request uses new keys, the bucket may be shared with earlier runs
+    const prefix = `${crypto.randomUUID()}/`;
+
+    try {
+        const bucket = env.ASSETS;
+        assert(bucket instanceof Bucket, "env.ASSETS should be a Bucket");
+
+        // Test get and head of a missing key
+        assertEquals(await bucket.get(`${prefix}missing`), null);
+        assertEquals(await bucket.head(`${prefix}missing`), null);
+
+        // Test put and get with metadata
+        let object = await bucket.put(`${prefix}hello.txt`, "hello bucket", {
+            httpMetadata: { contentType: "text/plain", cacheControl: "max-age=60" },
+            customMetadata: { Owner: "land", note: "héllo" },
+        });
+        assert(object instanceof BucketObject, "put should return a BucketObject");
+        assertEquals(object.key, `${prefix}hello.txt`);
+        assertEquals(object.size, 12);
+        assert(object.etag.length > 0, "etag should not be empty");
+        assertEquals(object.httpEtag, `"${object.etag}"`);
+        assert(object.uploaded instanceof Date, "uploaded should be a Date");
+
+        let got = await bucket.get(`${prefix}hello.txt`);
+        assert(got instanceof BucketObjectBody, "get should return a BucketObjectBody");
+        assertEquals(got.bodyUsed, false);
+        assertEquals(await got.text(), "hello bucket");
+        assertEquals(got.bodyUsed, true);
+        assertEquals(got.etag, object.etag);
+        assertEquals(got.httpMetadata.contentType, "text/plain");
+        assertEquals(got.httpMetadata.cacheControl, "max-age=60");
+        assertEquals(got.customMetadata.owner, "land");
+        assertEquals(got.customMetadata.note, "héllo");
+        let headers = new Headers();
+        got.writeHttpMetadata(headers);
+        assertEquals(headers.get("content-type"), "text/plain");
+
+        // Test head
+        let head = await bucket.head(`${prefix}hello.txt`);
+        assertEquals(head.size, 12);
+        assertEquals(head.customMetadata.owner, "land");
+        assertEquals(head.body, undefined);
+
+        // Test bytes and stream bodies
+        await bucket.put(`${prefix}bytes.bin`, new Uint8Array([1, 2, 3, 4, 5]));
+        let bytes = new Uint8Array(await (await bucket.get(`${prefix}bytes.bin`)).arrayBuffer());
+        assertEquals(bytes.join(","), "1,2,3,4,5");
+        await bucket.put(`${prefix}stream.txt`, new Response("streamed body").body);
+        got = await bucket.get(`${prefix}stream.txt`);
+        assertEquals(await new Response(got.body).text(), "streamed body");
+
+        // Test a got body is put to another key
+        got = await bucket.get(`${prefix}hello.txt`);
+        await bucket.put(`${prefix}copy.txt`, got.body);
+        assertEquals(await (await bucket.get(`${prefix}copy.txt`)).text(), "hello bucket");
+
+        // Test ranges
+        got = await bucket.get(`${prefix}hello.txt`, { range: { offset: 6, length: 6 } });
+        assertEquals(await got.text(), "bucket");
+        assertEquals(got.range.offset, 6);
+        assertEquals(got.range.length, 6);
+        got = await bucket.get(`${prefix}hello.txt`, { range: { suffix: 3 } });
+        assertEquals(await got.text(), "ket");
+        got = await bucket.get(`${prefix}hello.txt`, { range: { offset: 10 } });
+        assertEquals(await got.text(), "et");
+
+        // Test list with prefix, delimiter and cursor
+        for (const name of ["a.txt", "b.txt", "dir/c.txt", "dir/d.txt"]) {
+            await bucket.put(`${prefix}list/${name}`, name);
+        }
+        let page = await bucket.list({ prefix: `${prefix}list/`, limit: 2 });
+        assertEquals(page.objects.length, 2);
+        assertEquals(page.objects[0].key, `${prefix}list/a.txt`);
+        assertEquals(page.truncated, true);
+        page = await bucket.list({ prefix: `${prefix}list/`, cursor: page.cursor });
+        assertEquals(page.objects.length, 2);
+        assertEquals(page.objects[1].key, `${prefix}list/dir/d.txt`);
+        assertEquals(page.truncated, false);
+        assertEquals(page.cursor, undefined);
+        page = await bucket.list({ prefix: `${prefix}list/`, delimiter: "/" });
+        assertEquals(page.objects.length, 2);
+        assertEquals(page.delimitedPrefixes.length, 1);
+        assertEquals(page.delimitedPrefixes[0], `${prefix}list/dir/`);
+
+        // Test delete of one and many keys
+        await bucket.delete(`${prefix}hello.txt`);
+        assertEquals(await bucket.get(`${prefix}hello.txt`), null);
+        await bucket.delete([`${prefix}list/a.txt`, `${prefix}list/b.txt`]);
+        page = await bucket.list({ prefix: `${prefix}list/`, delimiter: "/" });
+        assertEquals(page.objects.length, 0);
+
+        // Test invalid arguments
+        let failed = false;
+        try {
+            await bucket.get(`${prefix}bytes.bin`, { range: { suffix: 2, offset: 1 } });
+        } catch (error) {
+            failed = error instanceof TypeError;
+        }
+        assert(failed, "suffix with offset should fail");
+        failed = false;
+        try {
+            await bucket.put("", "value");
+        } catch (error) {
+            failed = error instanceof TypeError;
+        }
+        assert(failed, "empty key should fail");
+
+        return new Response("All tests passed!", {
+            headers: { "content-type": "text/plain" },
+        });
+    } catch (error) {
+        return new Response(error.message + "\n" + error.stack, { status: 500 });
+    }
+}
+
+export default {
+    async fetch(request, env) {
+        return handleRequest(request, env);
+    }
+}
@@ -0,0 +1,124 @@
+async function handleRequest(request, env) {
+    const assert = (condition, message) => {
+        if (!condition) {
+            throw new Error(message || "Assertion failed");
+        }
+    };
+
+    const assertEquals = (actual, expected, message) => {
+        assert(
+            actual === expected,
+            message || `Expected ${expected} but got ${actual}`
+        );
+    };
+
+    // every request uses a new table, the database may be shared with earlier runs
+    const table = `users_${crypto.randomUUID().replaceAll("-", "")}`;
+
+    try {
+        const db = env.DB;
+        assert(db instanceof SqlDatabase, "env.DB should be a SqlDatabase");
+
+        // Test create and insert with bound parameters
+        let result = await db.prepare(
+            `CREATE TABLE ${table} (id INTEGER PRIMARY KEY, name TEXT NOT NULL, score REAL, avatar BLOB, active INTEGER)`
+        ).run();
+        assertEquals(result.success, true);
+        const insert = db.prepare(`INSERT INTO ${table} (name, score, avatar, active) VALUES (?, ?, ?, ?)`);
+        result = await insert.bind("alice", 9.5, new Uint8Array([1, 2, 3]), true).run();
+        assertEquals(result.meta.changes, 1);
+        assertEquals(result.meta.last_row_id, 1);
+        await insert.bind("bob", null, null, false).run();
+
+        // Test all with objects, reals, blobs and nulls
+        result = await db.prepare(`SELECT * FROM ${table} ORDER BY id`).all();
+        assertEquals(result.results.length, 2);
+        let alice = result.results[0];
+        assertEquals(alice.id, 1);
+        assertEquals(alice.name, "alice");
+        assertEquals(alice.score, 9.5);
+        assert(alice.avatar instanceof Uint8Array, "blob should be a Uint8Array");
+        assertEquals(alice.avatar.join(","), "1,2,3");
+        assertEquals(alice.active, 1);
+        assertEquals(result.results[1].score, null);
+        assertEquals(result.results[1].avatar, null);
+        assertEquals(result.results[1].active, 0);
+
+        // Test first with and without a column
+        let bob = await db.prepare(`SELECT * FROM ${table} WHERE name = ?`).bind("bob").first();
+        assertEquals(bob.id, 2);
+        assertEquals(await db.prepare(`SELECT count(*) AS total FROM ${table}`).first("total"), 2);
+        assertEquals(await db.prepare(`SELECT * FROM ${table} WHERE id = ?`).bind(404).first(), null);
+
+        // Test raw rows with column names
+        let raw = await db.prepare(`SELECT id, name FROM ${table} ORDER BY id`).raw({ columnNames: true });
+        assertEquals(raw[0].join(","), "id,name");
+        assertEquals(raw[1].join(","), "1,alice");
+        assertEquals(raw.length, 3);
+
+        // Test integers beyond Number.MAX_SAFE_INTEGER are BigInt
+        let big = await db.prepare("SELECT ? AS big, ? AS small").bind(9007199254740993n, 42n).first();
+        assertEquals(typeof big.big, "bigint");
+        assertEquals(big.big, 9007199254740993n);
+        assertEquals(big.small, 42);
+
+        // Test batch runs in one transaction
+        let results = await db.batch([
+            insert.bind("carol", 7, null, true),
+            db.prepare(`UPDATE ${table} SET score = score + 1 WHERE name = ?`).bind("alice"),
+            db.prepare(`SELECT name, score FROM ${table} ORDER BY id`),
+        ]);
+        assertEquals(results.length, 3);
+        assertEquals(results[0].meta.last_row_id, 3);
+        assertEquals(results[1].meta.changes, 1);
+        assertEquals(results[2].results[0].score, 10.5);
+        assertEquals(results[2].results[2].name, "carol");
+
+        // Test a failed batch is rolled back
+        let failed = false;
+        try {
+            await db.batch([
+                insert.bind("dave", 1, null, true),
+                insert.bind(null, 1, null, true),
+            ]);
+        } catch (error) {
+            failed = true;
+        }
+        assert(failed, "batch with a NOT NULL violation should fail");
+        assertEquals(await db.prepare(`SELECT count(*) AS total FROM ${table}`).first("total"), 3);
+
+        // Test update and delete changes
+        result = await db.prepare(`DELETE FROM ${table} WHERE active = ?`).bind(0).run();
+        assertEquals(result.meta.changes, 1);
+
+        // Test invalid SQL and parameters
+        failed = false;
+        try {
+            await db.prepare("SELEC nothing").all();
+        } catch (error) {
+            failed = true;
+        }
+        assert(failed, "invalid SQL should fail");
+        failed = false;
+        try {
+            insert.bind("eve", undefined);
+        } catch (error) {
+            failed = error instanceof TypeError;
+        }
+        assert(failed, "undefined parameter should fail");
+
+        await db.prepare(`DROP TABLE ${table}`).run();
+
+        return new Response("All tests passed!", {
+            headers: { "content-type": "text/plain" },
+        });
+    } catch (error) {
+        return new Response(error.message + "\n" + error.stack, { status: 500 });
+    }
+}
+
+export default {
+    async fetch(request, env) {
+        return handleRequest(request, env);
+    }
+}
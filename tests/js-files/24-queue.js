@@ -0,0 +1,106 @@
+// The fetch handler sends the messages with ?step=send and checks them with ?step=check,
+// the queue handler records the delivered messages in the KV binding
+async function handleRequest(request, env) {
+    const assert = (condition, message) => {
+        if (!condition) {
+            throw new Error(message || "Assertion failed");
+        }
+    };
+
+    const assertEquals = (actual, expected, message) => {
+        assert(
+            actual === expected,
+            message || `Expected ${expected} but got ${actual}`
+        );
+    };
+
+    try {
+        const url = new URL(request.url);
+        const run = url.searchParams.get("run");
+        const jobs = env.JOBS;
+        assert(jobs instanceof Queue, "env.JOBS should be a Queue");
+
+        if (url.searchParams.get("step") === "send") {
+            // Test json, text and bytes messages
+            await jobs.send({ run, task: "json", tags: ["a", "b"] });
+            await jobs.send(`text:${run}`, { contentType: "text" });
+            await jobs.sendBatch([
+                { body: new TextEncoder().encode(`bytes:${run}`), contentType: "bytes" },
+                { body: { run, task: "retry" } },
+            ]);
+
+            // Test invalid messages
+            let failed = false;
+            try {
+                await jobs.send("value", { contentType: "xml" });
+            } catch (error) {
+                failed = error instanceof TypeError;
+            }
+            assert(failed, "unknown content type should fail");
+            failed = false;
+            try {
+                await jobs.send(undefined);
+            } catch (error) {
+                failed = error instanceof TypeError;
+            }
+            assert(failed, "undefined body should fail");
+            return new Response("Sent");
+        }
+
+        let results = {};
+        for (const kind of ["json", "text", "bytes", "retry"]) {
+            results[kind] = await env.SESSIONS.get(`queue:${run}:${kind}`, "json");
+        }
+        if (Object.values(results).some(result => result === null)) {
+            return new Response("Pending");
+        }
+        assertEquals(results.json.tags, "a,b");
+        assertEquals(results.json.attempts, 1);
+        assertEquals(results.text.attempts, 1);
+        assertEquals(results.bytes.attempts, 1);
+        // the retry message is retried once, then it is acked
+        assertEquals(results.retry.attempts, 2);
+        assertEquals(results.retry.queue, "JOBS");
+
+        return new Response("All tests passed!", {
+            headers: { "content-type": "text/plain" },
+        });
+    } catch (error) {
+        return new Response(error.message + "\n" + error.stack, { status: 500 });
+    }
+}
+
+async function handleQueue(batch, env, ctx) {
+    if (!(batch instanceof MessageBatch)) {
+        throw new Error("batch should be a MessageBatch");
+    }
+    for (const message of batch.messages) {
+        if (!(message.timestamp instanceof Date) || typeof message.id !== "string") {
+            throw new Error("message should have an id and a timestamp");
+        }
+        let run, kind, record = { attempts: message.attempts, queue: batch.queue };
+        if (message.body instanceof ArrayBuffer) {
+            [kind, run] = new TextDecoder().decode(message.body).split(":");
+        } else if (typeof message.body === "string") {
+            [kind, run] = message.body.split(":");
+        } else {
+            run = message.body.run;
+            kind = message.body.task;
+            record.tags = (message.body.tags || []).join(",");
+        }
+        if (kind === "retry" && message.attempts === 1) {
+            message.retry();
+            continue;
+        }
+        ctx.waitUntil(env.SESSIONS.put(`queue:${run}:${kind}`, JSON.stringify(record)));
+    }
+}
+
+export default {
+    async fetch(request, env) {
+        return handleRequest(request, env);
+    },
+    async queue(batch, env, ctx) {
+        return handleQueue(batch, env, ctx);
+    },
+}
@@ -0,0 +1,85 @@
+import { env as moduleEnv } from "land:env";
+import defaultEnv from "land:env";
+
+// helpers out of the handlers read the env from land:env
+function appName() {
+    return moduleEnv.APP_NAME;
+}
+
+async function handleRequest(request, env) {
+    const assert = (condition, message) => {
+        if (!condition) {
+            throw new Error(message || "Assertion failed");
+        }
+    };
+
+    const assertEquals = (actual, expected, message) => {
+        assert(
+            actual === expected,
+            message || `Expected ${expected} but got ${actual}`
+        );
+    };
+
+    try {
+        // Test the native objects of the builtin modules are not exposed
+        for (const name of ["landEnv", "landPassword", "landUtil"]) {
+            assert(!(name in globalThis), `${name} should not be a global`);
+        }
+
+        // Test string vars, the names are case-insensitive
+        assertEquals(env.APP_NAME, "land-tests");
+        assertEquals(env.app_name, "land-tests");
+        assertEquals(env.MISSING_VAR, null);
+
+        // Test JSON vars are parsed
+        assertEquals(typeof env.APP_CONFIG, "object");
+        assertEquals(env.APP_CONFIG.region, "local");
+        assertEquals(env.APP_CONFIG.limits.requests, 100);
+        assertEquals(env.APP_CONFIG.features.join(","), "kv,sql");
+
+        // Test enumeration of the vars and the bindings
+        let keys = Object.keys(env);
+        for (const key of ["APP_NAME", "APP_CONFIG", "API_TOKEN", "SESSIONS", "ASSETS", "DB", "JOBS"]) {
+            assert(keys.includes(key), `Object.keys(env) should include ${key}`);
+        }
+        assert(!keys.includes("LAND_SECRETS"), "platform configuration should not be listed");
+        assert(!keys.includes("LAND_BINDINGS_URL"), "platform configuration should not be listed");
+
+        // Test the platform configuration can not be read, the binding declarations are read by js-vendor
+        for (const key of ["LAND_SECRETS", "LAND_BINDINGS_URL", "LAND_DEBUG_SECRET", "LAND_QUEUE_SECRET"]) {
+            assertEquals(env[key], null, `env.${key} should be null`);
+            assert(!(key in env), `${key} should not be in env`);
+            assertEquals(hostcall.read_env(key), null, `read_env(${key}) should be null`);
+        }
+        assertEquals(env.LAND_KV_BINDINGS, null);
+        assertEquals(hostcall.read_env("LAND_KV_BINDINGS"), "SESSIONS");
+        assert("APP_NAME" in env, "APP_NAME should be in env");
+        assert("SESSIONS" in env, "SESSIONS should be in env");
+        assert(!("MISSING_VAR" in env), "MISSING_VAR should not be in env");
+        let copy = { ...env };
+        assertEquals(copy.APP_NAME, "land-tests");
+        assert(copy.SESSIONS instanceof KVNamespace, "spread env should have the bindings");
+
+        // Test secrets are readable, they are redacted in the console output
+        assertEquals(env.API_TOKEN, "test-secret-token");
+        console.log(`api token is ${env.API_TOKEN}`);
+
+        // Test land:env
+        assert(defaultEnv === moduleEnv, "default export should be env");
+        assertEquals(moduleEnv.APP_NAME, "land-tests");
+        assertEquals(moduleEnv.APP_CONFIG.region, "local");
+        assertEquals(appName(), "land-tests");
+
+        return new Response("All tests passed!", {
+            headers: { "content-type": "text/plain" },
+        });
+    } catch (error) {
+        return new Response(error.message + "\n" + error.stack, { status: 500 });
+    }
+}
+
+export default {
+    async fetch(request, env) {
+        return handleRequest(request, env);
+    }
+}
@@ -0,0 +1,37 @@
+async function handleRequest(request) {
+    const assert = (condition, message) => {
+        if (!condition) {
+            throw new Error(message || "Assertion failed");
+        }
+    };
+
+    try {
+        // Test every level writes a record
+        console.debug("debug record", 1);
+        console.log("info record", { id: 1 });
+        console.info("info record", undefined);
+        console.warn("warn record", null);
+        console.error("error record", new Error("failed"));
+
+        // Test unknown levels are rejected
+        let failed = false;
+        try {
+            console.write("verbose", "record");
+        } catch (error) {
+            failed = true;
+        }
+        assert(failed, "unknown level should fail");
+
+        return new Response("All tests passed!", {
+            headers: { "content-type": "text/plain" },
+        });
+    } catch (error) {
+        return new Response(error.message + "\n" + error.stack, { status: 500 });
+    }
+}
+
+export default {
+    async fetch(request) {
+        return handleRequest(request);
+    }
+}
@@ -0,0 +1,91 @@
+import { format, inspect } from "land:util";
+
+async function handleRequest(request) {
+    const assertEqual = (actual, expected) => {
+        if (actual !== expected) {
+            throw new Error(`Expected ${JSON.stringify(expected)}, got ${JSON.stringify(actual)}`);
+        }
+    };
+
+    try {
+        // Test format specifiers
+        assertEqual(format("%s is %d years", "Bob", 42), "Bob is 42 years");
+        assertEqual(format("%i %f", 42.9, "1.5"), "42 1.5");
+        assertEqual(format("%d", 10n), "10n");
+        assertEqual(format("%j", { a: 1 }), '{"a":1}');
+        assertEqual(format("%c styled", "color: red"), " styled");
+        assertEqual(format("100%% %s", "done"), "100% done");
+        assertEqual(format("%s %s", "a"), "a %s");
+        assertEqual(format("%s"), "%s");
+        assertEqual(format("%o", { a: { b: { c: { d: 1 } } } }), "{ a: { b: { c: { d: 1 } } } }");
+        assertEqual(format("%O", { a: { b: { c: { d: 1 } } } }), "{ a: { b: { c: [Object] } } }");
+
+        // Test the args after the specifiers
+        assertEqual(format("a", { b: 1 }, "c"), "a { b: 1 } c");
+        assertEqual(format(null, undefined, Symbol("x")), "null undefined Symbol(x)");
+
+        // Test primitives
+        assertEqual(inspect("it's"), `"it's"`);
+        assertEqual(inspect("line\n"), "'line\\n'");
+        assertEqual(inspect(-0), "-0");
+        assertEqual(inspect(1.5), "1.5");
+        assertEqual(inspect(10n), "10n");
+        assertEqual(inspect(undefined), "undefined");
+        assertEqual(inspect(null), "null");
+
+        // Test objects and collections
+        assertEqual(inspect({ a: 1, b: "x", "a-b": true }), "{ a: 1, b: 'x', 'a-b': true }");
+        assertEqual(inspect([1, [2, [3, [4]]]]), "[ 1, [ 2, [ 3, [Array] ] ] ]");
+        assertEqual(inspect({ a: {} }, { depth: 0 }), "{ a: [Object] }");
+        assertEqual(inspect(new Map([["a", 1]])), "Map(1) { 'a' => 1 }");
+        assertEqual(inspect(new Set([1, 2])), "Set(2) { 1, 2 }");
+        assertEqual(inspect(new Uint8Array([1, 2])), "Uint8Array(2) [ 1, 2 ]");
+        assertEqual(inspect(new Uint8Array([1, 2]).buffer), "ArrayBuffer { [Uint8Contents]: <01 02>, byteLength: 2 }");
+        assertEqual(inspect(Object.create(null)), "[Object: null prototype] {}");
+        assertEqual(inspect(new Date(0)), "1970-01-01T00:00:00.000Z");
+
+        // Test functions and classes
+        class Point {
+            constructor() {
+                this.x = 1;
+            }
+        }
+        assertEqual(inspect(function named() { }), "[Function: named]");
+        assertEqual(inspect(Point), "[class Point]");
+        assertEqual(inspect(new Point()), "Point { x: 1 }");
+
+        // Test cyclic references
+        const cyclic = { name: "cyclic" };
+        cyclic.self = cyclic;
+        assertEqual(inspect(cyclic), "{ name: 'cyclic', self: [Circular] }");
+        assertEqual(format("%j", cyclic), "[Circular]");
+
+        // Test errors have the stack
+        const error = inspect(new TypeError("boom"));
+        if (!error.startsWith("TypeError: boom")) {
+            throw new Error(`Unexpected error format: ${error}`);
+        }
+
+        // Test length limits and line breaks
+        const long = inspect(Array.from({ length: 101 }, () => 0));
+        if (!long.endsWith("  ... 1 more item\n]")) {
+            throw new Error(`Unexpected array format: ${long}`);
+        }
+        assertEqual(inspect("x".repeat(10001)).endsWith("'... 1 more characters"), true);
+
+        // Test console accepts any values
+        console.log("%s has %o", "object", { a: [1, 2] }, null, undefined);
+
+        return new Response("All tests passed!", {
+            headers: { "content-type": "text/plain" },
+        });
+    } catch (error) {
+        return new Response(error.message + "\n" + error.stack, { status: 500 });
+    }
+}
+
+export default {
+    async fetch(request) {
+        return handleRequest(request);
+    }
+}
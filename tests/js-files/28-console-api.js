@@ -0,0 +1,68 @@
+async function handleRequest(request) {
+    const assert = (condition, message) => {
+        if (!condition) {
+            throw new Error(message || "Assertion failed");
+        }
+    };
+
+    try {
+        // Test the console namespace is complete
+        const methods = [
+            "debug", "log", "info", "warn", "error", "time", "timeLog", "timeEnd", "count",
+            "countReset", "assert", "table", "trace", "group", "groupCollapsed", "groupEnd", "dir",
+        ];
+        for (const method of methods) {
+            assert(typeof console[method] === "function", `console.${method} is not a function`);
+        }
+
+        // Test timers
+        console.time();
+        console.time("request");
+        console.time("request");
+        console.timeLog("request", "halfway", { step: 1 });
+        console.timeEnd("request");
+        console.timeEnd("request");
+        console.timeEnd();
+
+        // Test counters
+        console.count();
+        console.count("items");
+        console.count("items");
+        console.countReset("items");
+        console.countReset("missing");
+
+        // Test assertions only write records
+        console.assert(true, "not written");
+        console.assert(false, "written %s", "assertion");
+        console.assert(0, { reason: "falsy" });
+        console.assert();
+
+        // Test tables
+        console.table([{ a: 1, b: "x" }, { a: 2, c: true }, 3]);
+        console.table({ first: { a: 1 }, second: { a: 2 } }, ["a"]);
+        console.table("not tabular");
+
+        // Test traces, groups and dir
+        console.trace("traced %d", 1);
+        console.group("outer");
+        console.groupCollapsed();
+        console.log("nested\nlines");
+        console.groupEnd();
+        console.groupEnd();
+        console.groupEnd();
+        console.dir({ a: { b: { c: { d: 1 } } } }, { depth: null });
+        console.dir("text");
+
+        return new Response("All tests passed!", {
+            headers: { "content-type": "text/plain" },
+        });
+    } catch (error) {
+        return new Response(error.message + "\n" + error.stack, { status: 500 });
+    }
+}
+
+export default {
+    async fetch(request) {
+        return handleRequest(request);
+    }
+}
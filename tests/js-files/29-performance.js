@@ -0,0 +1,59 @@
+async function handleRequest(request, env) {
+    const assert = (condition, message) => {
+        if (!condition) {
+            throw new Error(message || "Assertion failed");
+        }
+    };
+
+    try {
+        // Test the clock is monotonic and starts with the request
+        const start = performance.now();
+        assert(start >= 0, "now should not be negative");
+        assert(performance.now() >= start, "now should be monotonic");
+        assert(Math.abs(performance.timeOrigin + start - Date.now()) < 1000, "timeOrigin should be the request time");
+
+        // Test marks and measures
+        performance.mark("start");
+        const response = await fetch(`${env.TEST_BINDINGS_URL}/kv/PERF`);
+        await response.arrayBuffer();
+        const mark = performance.mark("end", { detail: { status: response.status } });
+        assert(mark instanceof PerformanceMark, "mark should be a PerformanceMark");
+        assert(mark.detail.status === response.status, "mark should have the detail");
+
+        const measure = performance.measure("upstream", "start", "end");
+        assert(measure instanceof PerformanceMeasure, "measure should be a PerformanceMeasure");
+        assert(measure.duration === mark.startTime - performance.getEntriesByName("start")[0].startTime, "measure duration is wrong");
+        performance.measure("render", { start: "end", duration: 1 });
+        assert(performance.getEntries().length === 4, "there should be 4 entries");
+        assert(performance.getEntriesByType("measure").length === 2, "there should be 2 measures");
+
+        // Test unknown marks are rejected
+        let failed = false;
+        try {
+            performance.measure("missing", "not-a-mark");
+        } catch (error) {
+            failed = error instanceof SyntaxError;
+        }
+        assert(failed, "unknown marks should fail");
+
+        performance.clearMarks();
+        assert(performance.getEntriesByType("mark").length === 0, "marks should be cleared");
+
+        return new Response("All tests passed!", {
+            headers: { "content-type": "text/plain" },
+        });
+    } catch (error) {
+        return new Response(error.message + "\n" + error.stack, { status: 500 });
+    }
+}
+
+export default {
+    async fetch(request, env) {
+        // the error page of an uncaught error has the Server-Timing header too
+        if (new URL(request.url).pathname === "/throw") {
+            throw new Error("performance failure");
+        }
+        return handleRequest(request, env);
+    },
+    serverTiming: true,
+}
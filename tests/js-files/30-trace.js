@@ -0,0 +1,44 @@
+async function handleRequest(request, env) {
+    const assert = (condition, message) => {
+        if (!condition) {
+            throw new Error(message || "Assertion failed");
+        }
+    };
+
+    try {
+        const [, traceId, parentId] = request.headers.get("traceparent").split("-");
+
+        // Test the trace context is propagated to the outbound requests
+        let response = await fetch(`${env.TEST_BINDINGS_URL}/trace-context`);
+        let context = await response.json();
+        const [version, outboundTraceId, spanId, flags] = context.traceparent.split("-");
+        assert(version === "00", "traceparent version should be 00");
+        assert(outboundTraceId === traceId, "outbound trace id should be the request trace id");
+        assert(spanId.length === 16 && spanId !== parentId, "outbound parent should be the fetch span");
+        assert(flags === "01", "traceparent flags should be kept");
+        assert(context.tracestate === request.headers.get("tracestate"), "tracestate should be kept");
+
+        // Test the traceparent of the script is not replaced
+        const custom = "00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-00";
+        response = await fetch(`${env.TEST_BINDINGS_URL}/trace-context`, {
+            headers: { traceparent: custom },
+        });
+        context = await response.json();
+        assert(context.traceparent === custom, "custom traceparent should be kept");
+
+        // Test the bindings calls have spans
+        await env.SESSIONS.get("trace-key");
+
+        return new Response("All tests passed!", {
+            headers: { "content-type": "text/plain" },
+        });
+    } catch (error) {
+        return new Response(error.message + "\n" + error.stack, { status: 500 });
+    }
+}
+
+export default {
+    async fetch(request, env) {
+        return handleRequest(request, env);
+    }
+}
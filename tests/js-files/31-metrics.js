@@ -0,0 +1,36 @@
+async function handleRequest(request, env) {
+    const assert = (condition, message) => {
+        if (!condition) {
+            throw new Error(message || "Assertion failed");
+        }
+    };
+
+    try {
+        const body = await request.text();
+        const response = await fetch(`${env.TEST_BINDINGS_URL}/trace-context`);
+        await response.json();
+        await Promise.all([1, 2, 3].map(async value => value * 2));
+
+        // Test the counters of the running request
+        const metrics = hostcall.request_metrics();
+        assert(metrics.heap_before > 0, "heap_before should be set");
+        assert(metrics.heap_after === null, "heap_after should be set after the response");
+        assert(metrics.fetch_count === 1, "fetch_count should be 1");
+        assert(metrics.fetch_ms >= 0, "fetch_ms should be set");
+        assert(metrics.body_read_bytes >= body.length, "body_read_bytes should count the request body");
+        assert(metrics.jobs > 0, "jobs should be counted");
+        assert(metrics.response_body_bytes === 0, "response_body_bytes should be 0 before the response");
+
+        return new Response("All tests passed!", {
+            headers: { "content-type": "text/plain" },
+        });
+    } catch (error) {
+        return new Response(error.message + "\n" + error.stack, { status: 500 });
+    }
+}
+
+export default {
+    async fetch(request, env) {
+        return handleRequest(request, env);
+    }
+}
@@ -0,0 +1,18 @@
+// The items are kept in a global property, they are never freed, so the retained heap grows
+// with every request and the leak detector reports leakedItems
+globalThis.leakedItems = globalThis.leakedItems || [];
+
+async function handleRequest(request) {
+    for (let i = 0; i < 100; i++) {
+        globalThis.leakedItems.push({ index: i, url: request.url });
+    }
+    return new Response("All tests passed!", {
+        headers: { "content-type": "text/plain" },
+    });
+}
+
+export default {
+    async fetch(request) {
+        return handleRequest(request);
+    }
+}
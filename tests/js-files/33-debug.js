@@ -0,0 +1,22 @@
+async function handleRequest(request, env) {
+    const url = new URL(request.url);
+
+    console.log("debug log line", { step: 1 });
+    console.warn("debug warning");
+    const response = await fetch(`${env.TEST_BINDINGS_URL}/trace-context`);
+    await response.json();
+
+    // the error of a rejected handler is returned in x-land-debug-error
+    if (url.searchParams.get("throw")) {
+        throw new Error("debug handler failure");
+    }
+    return new Response("All tests passed!", {
+        headers: { "content-type": "text/plain" },
+    });
+}
+
+export default {
+    async fetch(request, env) {
+        return handleRequest(request, env);
+    }
+}
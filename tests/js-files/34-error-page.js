@@ -0,0 +1,11 @@
+function failingHandler(request) {
+    const error = new TypeError("page failure <b>");
+    error.cause = new RangeError("inner failure");
+    throw error;
+}
+
+export default {
+    async fetch(request) {
+        return failingHandler(request);
+    }
+}
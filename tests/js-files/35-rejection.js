@@ -0,0 +1,42 @@
+async function handleRequest(request) {
+    const url = new URL(request.url);
+    const assert = (condition, message) => {
+        if (!condition) {
+            throw new Error(message || "Assertion failed");
+        }
+    };
+
+    // the errors of an AggregateError are rendered in the error page
+    if (url.searchParams.get("throw") === "aggregate") {
+        throw new AggregateError([new Error("first failure"), new TypeError("second failure")], "all failed");
+    }
+
+    // a rejection without a handler is reported with its cause
+    Promise.reject(new Error("forgotten rejection", { cause: new RangeError("root cause") }));
+
+    // a rejection that is handled later is not reported
+    const handledLater = Promise.reject(new Error("handled later"));
+    await null;
+    handledLater.catch(() => {});
+
+    // Error.cause and AggregateError
+    const error = new Error("outer", { cause: "inner" });
+    assert(error.cause === "inner", "cause should be set");
+    try {
+        await Promise.any([Promise.reject(new Error("a")), Promise.reject(new Error("b"))]);
+        assert(false, "Promise.any should reject");
+    } catch (error) {
+        assert(error instanceof AggregateError, "Promise.any should reject with an AggregateError");
+        assert(error.errors.length === 2, "AggregateError should have 2 errors");
+    }
+
+    return new Response("All tests passed!", {
+        headers: { "content-type": "text/plain" },
+    });
+}
+
+export default {
+    async fetch(request) {
+        return handleRequest(request);
+    }
+}
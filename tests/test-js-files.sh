@@ -6,4 +6,8 @@ CLI=./target/release/test-runner
 for file in tests/js-files/*.js; do
     echo "Building $file"
     $CLI build $file --js-engine js-engine.wasm
-done
\ No newline at end of file
+done
+
+# the seeded build replays the same Math.random sequence, the copy is another instance of the same script
+LAND_RANDOM_SEED=42 $CLI build tests/js-files/15-math-random.js -o tests/js-files/15-math-random-seeded.js.wasm --js-engine js-engine.wasm
+$CLI build tests/js-files/15-math-random.js -o tests/js-files/15-math-random-copy.js.wasm --js-engine js-engine.wasm
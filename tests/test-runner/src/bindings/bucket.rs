@@ -0,0 +1,275 @@
+use super::{file_name, now_millis, State};
+use axum::{
+    body::Bytes,
+    extract::{Path, Query, State as AxumState},
+    http::{HeaderMap, HeaderName, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use std::path::{Path as FsPath, PathBuf};
+
+const SIZE_HEADER: &str = "x-land-object-size";
+const ETAG_HEADER: &str = "x-land-object-etag";
+const UPLOADED_HEADER: &str = "x-land-object-uploaded";
+const HTTP_PREFIX: &str = "x-land-object-http-";
+const META_PREFIX: &str = "x-land-object-meta-";
+
+// An object is two files, {key}.meta has the key, size, etag, uploaded time and metadata headers in lines,
+// {key}.data has the body
+fn bucket_dir(state: &State, bucket: &str) -> PathBuf {
+    state.dir.join("bucket").join(file_name(bucket))
+}
+
+struct Meta {
+    key: String,
+    size: u64,
+    etag: String,
+    uploaded: u64,
+    headers: Vec<(String, String)>,
+}
+
+impl Meta {
+    fn header_map(&self) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(SIZE_HEADER, HeaderValue::from(self.size));
+        headers.insert(ETAG_HEADER, HeaderValue::from_str(&self.etag).unwrap());
+        headers.insert(UPLOADED_HEADER, HeaderValue::from(self.uploaded));
+        for (name, value) in self.headers.iter() {
+            if let (Ok(name), Ok(value)) = (
+                HeaderName::from_bytes(name.as_bytes()),
+                HeaderValue::from_str(value),
+            ) {
+                headers.insert(name, value);
+            }
+        }
+        headers
+    }
+
+    fn to_json(&self) -> Value {
+        let mut http_metadata = Map::new();
+        let mut custom_metadata = Map::new();
+        for (name, value) in self.headers.iter() {
+            if let Some(name) = name.strip_prefix(HTTP_PREFIX) {
+                http_metadata.insert(name.to_string(), json!(value));
+            } else if let Some(name) = name.strip_prefix(META_PREFIX) {
+                custom_metadata.insert(name.to_string(), json!(value));
+            }
+        }
+        json!({
+            "key": self.key,
+            "size": self.size,
+            "etag": self.etag,
+            "uploaded": self.uploaded,
+            "httpMetadata": http_metadata,
+            "customMetadata": custom_metadata,
+        })
+    }
+}
+
+fn read_meta(path: &FsPath) -> Option<Meta> {
+    let meta = std::fs::read_to_string(path).ok()?;
+    let mut lines = meta.lines();
+    Some(Meta {
+        key: lines.next()?.to_string(),
+        size: lines.next()?.parse().ok()?,
+        etag: lines.next()?.to_string(),
+        uploaded: lines.next()?.parse().ok()?,
+        headers: lines
+            .filter_map(|line| line.split_once(": "))
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect(),
+    })
+}
+
+fn read_object(state: &State, bucket: &str, key: &str) -> Option<(Meta, PathBuf)> {
+    let path = bucket_dir(state, bucket).join(file_name(key));
+    let meta = read_meta(&path.with_extension("meta")).filter(|meta| meta.key == key)?;
+    Some((meta, path.with_extension("data")))
+}
+
+// etag is the hash of the body in hex
+fn etag(body: &[u8]) -> String {
+    use std::hash::{DefaultHasher, Hasher};
+    let mut hasher = DefaultHasher::new();
+    hasher.write(body);
+    format!("{:016x}", hasher.finish())
+}
+
+// parse_range returns the start and end (inclusive) of a "bytes=" range header in an object of size
+fn parse_range(range: &str, size: u64) -> Option<(u64, u64)> {
+    let (start, end) = range.strip_prefix("bytes=")?.split_once('-')?;
+    if size == 0 {
+        return None;
+    }
+    if start.is_empty() {
+        let suffix: u64 = end.parse().ok()?;
+        return Some((size - suffix.min(size), size - 1));
+    }
+    let start: u64 = start.parse().ok()?;
+    let end: u64 = if end.is_empty() {
+        size - 1
+    } else {
+        end.parse::<u64>().ok()?.min(size - 1)
+    };
+    if start > end {
+        return None;
+    }
+    Some((start, end))
+}
+
+pub async fn get(
+    AxumState(state): AxumState<State>,
+    Path((bucket, key)): Path<(String, String)>,
+    headers: HeaderMap,
+) -> Response {
+    let (meta, data_path) = match read_object(&state, &bucket, &key) {
+        Some(object) => object,
+        None => return StatusCode::NOT_FOUND.into_response(),
+    };
+    let data = match std::fs::read(data_path) {
+        Ok(data) => data,
+        Err(_) => return StatusCode::NOT_FOUND.into_response(),
+    };
+    let mut response_headers = meta.header_map();
+    let range = headers
+        .get("range")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| parse_range(value, meta.size));
+    match range {
+        Some((start, end)) => {
+            response_headers.insert(
+                "content-range",
+                HeaderValue::from_str(&format!("bytes {}-{}/{}", start, end, meta.size)).unwrap(),
+            );
+            let body = data[start as usize..=end as usize].to_vec();
+            (StatusCode::PARTIAL_CONTENT, response_headers, body).into_response()
+        }
+        None => (StatusCode::OK, response_headers, data).into_response(),
+    }
+}
+
+pub async fn put(
+    AxumState(state): AxumState<State>,
+    Path((bucket, key)): Path<(String, String)>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    let meta = Meta {
+        key: key.clone(),
+        size: body.len() as u64,
+        etag: etag(&body),
+        uploaded: now_millis(),
+        headers: headers
+            .iter()
+            .filter(|(name, _)| {
+                name.as_str().starts_with(HTTP_PREFIX) || name.as_str().starts_with(META_PREFIX)
+            })
+            .map(|(name, value)| {
+                (
+                    name.to_string(),
+                    value.to_str().unwrap_or_default().to_string(),
+                )
+            })
+            .collect(),
+    };
+    let mut content = format!(
+        "{}\n{}\n{}\n{}\n",
+        meta.key, meta.size, meta.etag, meta.uploaded
+    );
+    for (name, value) in meta.headers.iter() {
+        content.push_str(&format!("{}: {}\n", name, value));
+    }
+    let path = bucket_dir(&state, &bucket).join(file_name(&key));
+    let result = std::fs::create_dir_all(path.parent().unwrap())
+        .and_then(|_| std::fs::write(path.with_extension("data"), &body))
+        .and_then(|_| std::fs::write(path.with_extension("meta"), content));
+    match result {
+        Ok(_) => (StatusCode::OK, meta.header_map()).into_response(),
+        Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response(),
+    }
+}
+
+pub async fn delete(
+    AxumState(state): AxumState<State>,
+    Path((bucket, key)): Path<(String, String)>,
+) -> Response {
+    let path = bucket_dir(&state, &bucket).join(file_name(&key));
+    let _ = std::fs::remove_file(path.with_extension("meta"));
+    let _ = std::fs::remove_file(path.with_extension("data"));
+    StatusCode::NO_CONTENT.into_response()
+}
+
+#[derive(Deserialize)]
+pub struct ListQuery {
+    #[serde(default)]
+    prefix: String,
+    #[serde(default)]
+    delimiter: String,
+    #[serde(default)]
+    cursor: String,
+    limit: Option<usize>,
+}
+
+pub async fn list(
+    AxumState(state): AxumState<State>,
+    Path(bucket): Path<String>,
+    Query(query): Query<ListQuery>,
+) -> Response {
+    let mut metas: Vec<Meta> = std::fs::read_dir(bucket_dir(&state, &bucket))
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .filter(|path| path.extension().map(|e| e == "meta").unwrap_or(false))
+                .filter_map(|path| read_meta(&path))
+                .filter(|meta| meta.key.starts_with(&query.prefix) && meta.key > query.cursor)
+                .collect()
+        })
+        .unwrap_or_default();
+    metas.sort_by(|a, b| a.key.cmp(&b.key));
+    let limit = query.limit.unwrap_or(1000).clamp(1, 1000);
+    let mut objects = vec![];
+    let mut prefixes: Vec<String> = vec![];
+    let mut count = 0;
+    let mut last_key = None;
+    let mut truncated = false;
+    for meta in metas.iter() {
+        // the keys with the delimiter after the prefix are rolled up into a delimited prefix
+        let delimited = if query.delimiter.is_empty() {
+            None
+        } else {
+            meta.key[query.prefix.len()..]
+                .find(&query.delimiter)
+                .map(|index| {
+                    meta.key[..query.prefix.len() + index + query.delimiter.len()].to_string()
+                })
+        };
+        if let Some(prefix) = &delimited {
+            if prefixes.contains(prefix) {
+                last_key = Some(meta.key.clone());
+                continue;
+            }
+        }
+        if count == limit {
+            truncated = true;
+            break;
+        }
+        match delimited {
+            Some(prefix) => prefixes.push(prefix),
+            None => objects.push(meta.to_json()),
+        }
+        count += 1;
+        last_key = Some(meta.key.clone());
+    }
+    let mut result = json!({
+        "objects": objects,
+        "delimitedPrefixes": prefixes,
+        "truncated": truncated,
+    });
+    if truncated {
+        result["cursor"] = json!(last_key);
+    }
+    Json(result).into_response()
+}
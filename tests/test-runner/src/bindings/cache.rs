@@ -0,0 +1,111 @@
+use super::{file_name, now_millis, State};
+use axum::{
+    body::Bytes,
+    extract::{Path, State as AxumState},
+    http::{HeaderMap, HeaderName, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+};
+use std::path::PathBuf;
+
+const STATUS_HEADER: &str = "x-land-cache-status";
+const TTL_HEADER: &str = "x-land-cache-ttl";
+const HEADER_PREFIX: &str = "x-land-cache-header-";
+
+// An entry is two files, {key}.meta has the key, status, expiry time and headers in lines,
+// {key}.body has the body
+fn entry_path(state: &State, name: &str, key: &str) -> PathBuf {
+    state
+        .dir
+        .join("cache")
+        .join(file_name(name))
+        .join(file_name(key))
+}
+
+fn read_meta(path: &PathBuf, key: &str) -> Option<(String, Vec<(String, String)>)> {
+    let meta = std::fs::read_to_string(path.with_extension("meta")).ok()?;
+    let mut lines = meta.lines();
+    if lines.next()? != key {
+        return None;
+    }
+    let status = lines.next()?.to_string();
+    let expires_at: u64 = lines.next()?.parse().ok()?;
+    if expires_at <= now_millis() {
+        return None;
+    }
+    let headers = lines
+        .filter_map(|line| line.split_once(": "))
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect();
+    Some((status, headers))
+}
+
+pub async fn get(
+    AxumState(state): AxumState<State>,
+    Path((name, key)): Path<(String, String)>,
+) -> Response {
+    let path = entry_path(&state, &name, &key);
+    let (status, headers) = match read_meta(&path, &key) {
+        Some(meta) => meta,
+        None => return StatusCode::NOT_FOUND.into_response(),
+    };
+    let body = match std::fs::read(path.with_extension("body")) {
+        Ok(body) => body,
+        Err(_) => return StatusCode::NOT_FOUND.into_response(),
+    };
+    let mut response_headers = HeaderMap::new();
+    response_headers.insert(STATUS_HEADER, HeaderValue::from_str(&status).unwrap());
+    for (name, value) in headers {
+        let name = format!("{}{}", HEADER_PREFIX, name);
+        if let (Ok(name), Ok(value)) = (
+            HeaderName::from_bytes(name.as_bytes()),
+            HeaderValue::from_str(&value),
+        ) {
+            response_headers.insert(name, value);
+        }
+    }
+    (StatusCode::OK, response_headers, body).into_response()
+}
+
+pub async fn put(
+    AxumState(state): AxumState<State>,
+    Path((name, key)): Path<(String, String)>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+    let status = header(STATUS_HEADER).unwrap_or("200").to_string();
+    let ttl: u64 = header(TTL_HEADER).and_then(|v| v.parse().ok()).unwrap_or(0);
+    let mut meta = format!("{}\n{}\n{}\n", key, status, now_millis() + ttl * 1000);
+    for (name, value) in headers.iter() {
+        if let Some(name) = name.as_str().strip_prefix(HEADER_PREFIX) {
+            meta.push_str(&format!(
+                "{}: {}\n",
+                name,
+                value.to_str().unwrap_or_default()
+            ));
+        }
+    }
+    let path = entry_path(&state, &name, &key);
+    let result = std::fs::create_dir_all(path.parent().unwrap())
+        .and_then(|_| std::fs::write(path.with_extension("body"), &body))
+        .and_then(|_| std::fs::write(path.with_extension("meta"), meta));
+    match result {
+        Ok(_) => StatusCode::NO_CONTENT.into_response(),
+        Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response(),
+    }
+}
+
+pub async fn delete(
+    AxumState(state): AxumState<State>,
+    Path((name, key)): Path<(String, String)>,
+) -> Response {
+    let path = entry_path(&state, &name, &key);
+    let found = read_meta(&path, &key).is_some();
+    let _ = std::fs::remove_file(path.with_extension("meta"));
+    let _ = std::fs::remove_file(path.with_extension("body"));
+    if found {
+        StatusCode::NO_CONTENT.into_response()
+    } else {
+        StatusCode::NOT_FOUND.into_response()
+    }
+}
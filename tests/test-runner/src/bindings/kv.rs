@@ -0,0 +1,149 @@
+use super::{file_name, State};
+use axum::{
+    body::Bytes,
+    extract::{Path, Query, State as AxumState},
+    http::{HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Deserialize;
+use serde_json::json;
+use std::path::PathBuf;
+
+const METADATA_HEADER: &str = "x-land-kv-metadata";
+const EXPIRATION_HEADER: &str = "x-land-kv-expiration";
+
+// An entry is two files, {key}.meta has the key, expiration and metadata in lines,
+// {key}.value has the value
+fn namespace_dir(state: &State, namespace: &str) -> PathBuf {
+    state.dir.join("kv").join(file_name(namespace))
+}
+
+struct Meta {
+    key: String,
+    expiration: u64,
+    metadata: Option<String>,
+}
+
+fn now_secs() -> u64 {
+    super::now_millis() / 1000
+}
+
+fn read_meta(path: &std::path::Path) -> Option<Meta> {
+    let meta = std::fs::read_to_string(path).ok()?;
+    let mut lines = meta.lines();
+    let key = lines.next()?.to_string();
+    let expiration: u64 = lines.next()?.parse().ok()?;
+    if expiration != 0 && expiration <= now_secs() {
+        return None;
+    }
+    let metadata = lines.next().map(|m| m.to_string());
+    Some(Meta {
+        key,
+        expiration,
+        metadata,
+    })
+}
+
+pub async fn get(
+    AxumState(state): AxumState<State>,
+    Path((namespace, key)): Path<(String, String)>,
+) -> Response {
+    let path = namespace_dir(&state, &namespace).join(file_name(&key));
+    let meta = match read_meta(&path.with_extension("meta")) {
+        Some(meta) if meta.key == key => meta,
+        _ => return StatusCode::NOT_FOUND.into_response(),
+    };
+    let value = match std::fs::read(path.with_extension("value")) {
+        Ok(value) => value,
+        Err(_) => return StatusCode::NOT_FOUND.into_response(),
+    };
+    let mut headers = HeaderMap::new();
+    headers.insert(EXPIRATION_HEADER, HeaderValue::from(meta.expiration));
+    if let Some(metadata) = meta.metadata.and_then(|m| HeaderValue::from_str(&m).ok()) {
+        headers.insert(METADATA_HEADER, metadata);
+    }
+    (StatusCode::OK, headers, value).into_response()
+}
+
+pub async fn put(
+    AxumState(state): AxumState<State>,
+    Path((namespace, key)): Path<(String, String)>,
+    headers: HeaderMap,
+    value: Bytes,
+) -> Response {
+    let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+    let expiration = header(EXPIRATION_HEADER).unwrap_or("0");
+    let mut meta = format!("{}\n{}\n", key, expiration);
+    if let Some(metadata) = header(METADATA_HEADER) {
+        meta.push_str(metadata);
+        meta.push('\n');
+    }
+    let path = namespace_dir(&state, &namespace).join(file_name(&key));
+    let result = std::fs::create_dir_all(path.parent().unwrap())
+        .and_then(|_| std::fs::write(path.with_extension("value"), &value))
+        .and_then(|_| std::fs::write(path.with_extension("meta"), meta));
+    match result {
+        Ok(_) => StatusCode::NO_CONTENT.into_response(),
+        Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response(),
+    }
+}
+
+pub async fn delete(
+    AxumState(state): AxumState<State>,
+    Path((namespace, key)): Path<(String, String)>,
+) -> Response {
+    let path = namespace_dir(&state, &namespace).join(file_name(&key));
+    let _ = std::fs::remove_file(path.with_extension("meta"));
+    let _ = std::fs::remove_file(path.with_extension("value"));
+    StatusCode::NO_CONTENT.into_response()
+}
+
+#[derive(Deserialize)]
+pub struct ListQuery {
+    #[serde(default)]
+    prefix: String,
+    #[serde(default)]
+    cursor: String,
+    limit: Option<usize>,
+}
+
+pub async fn list(
+    AxumState(state): AxumState<State>,
+    Path(namespace): Path<String>,
+    Query(query): Query<ListQuery>,
+) -> Response {
+    let mut metas: Vec<Meta> = std::fs::read_dir(namespace_dir(&state, &namespace))
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .filter(|path| path.extension().map(|e| e == "meta").unwrap_or(false))
+                .filter_map(|path| read_meta(&path))
+                .filter(|meta| meta.key.starts_with(&query.prefix) && meta.key > query.cursor)
+                .collect()
+        })
+        .unwrap_or_default();
+    metas.sort_by(|a, b| a.key.cmp(&b.key));
+    let limit = query.limit.unwrap_or(1000).clamp(1, 1000);
+    let list_complete = metas.len() <= limit;
+    metas.truncate(limit);
+    let keys: Vec<_> = metas
+        .iter()
+        .map(|meta| {
+            let mut key = json!({ "name": meta.key });
+            if meta.expiration != 0 {
+                key["expiration"] = json!(meta.expiration);
+            }
+            if let Some(metadata) = &meta.metadata {
+                key["metadata"] = json!(metadata);
+            }
+            key
+        })
+        .collect();
+    let mut result = json!({ "keys": keys, "list_complete": list_complete });
+    if !list_complete {
+        result["cursor"] = json!(metas.last().map(|meta| meta.key.clone()));
+    }
+    Json(result).into_response()
+}
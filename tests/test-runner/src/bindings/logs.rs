@@ -0,0 +1,18 @@
+use axum::{http::StatusCode, Json};
+use serde::Deserialize;
+use serde_json::Value;
+use tracing::info;
+
+// The console records of the scripts with LAND_LOG_FORMAT=host, they are written to the test-runner log
+
+#[derive(Deserialize)]
+pub struct LogRequest {
+    records: Vec<Value>,
+}
+
+pub async fn write(Json(request): Json<LogRequest>) -> StatusCode {
+    for record in request.records {
+        info!("Script log: {}", record);
+    }
+    StatusCode::OK
+}
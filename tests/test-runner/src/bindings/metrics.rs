@@ -0,0 +1,31 @@
+use super::State;
+use axum::{extract::State as AxumState, http::StatusCode, Json};
+use serde_json::Value;
+use std::sync::{Arc, Mutex};
+use tracing::info;
+
+// The request metrics of the scripts with LAND_METRICS=host, they are written to the test-runner log
+// and the latest records are kept in memory, so the expensive scripts can be found
+
+// MAX_RECORDS is the number of the latest records that are kept
+const MAX_RECORDS: usize = 1000;
+
+/// Metrics are the latest request metrics records
+#[derive(Clone, Default)]
+pub struct Metrics {
+    records: Arc<Mutex<Vec<Value>>>,
+}
+
+pub async fn write(AxumState(state): AxumState<State>, Json(record): Json<Value>) -> StatusCode {
+    info!("Script metrics: {}", record);
+    let mut records = state.metrics.records.lock().unwrap();
+    if records.len() >= MAX_RECORDS {
+        records.remove(0);
+    }
+    records.push(record);
+    StatusCode::OK
+}
+
+pub async fn list(AxumState(state): AxumState<State>) -> Json<Vec<Value>> {
+    Json(state.metrics.records.lock().unwrap().clone())
+}
@@ -0,0 +1,86 @@
+use anyhow::Result;
+use axum::{
+    extract::DefaultBodyLimit,
+    routing::{get, post},
+    Router,
+};
+use std::{net::SocketAddr, path::PathBuf};
+use tracing::info;
+
+mod bucket;
+mod cache;
+mod kv;
+mod logs;
+mod metrics;
+mod queue;
+mod sql;
+mod traces;
+
+pub use queue::Consumers;
+
+/// State is shared by the bindings handlers
+#[derive(Clone)]
+pub struct State {
+    pub dir: PathBuf,
+    pub queues: queue::Queues,
+    pub traces: traces::Traces,
+    pub metrics: metrics::Metrics,
+}
+
+/// start runs the local bindings service that stands in for the platform bindings.
+/// The data is stored as files in dir, the queue messages are kept in memory and delivered to consumers.
+pub async fn start(addr: SocketAddr, dir: PathBuf, consumers: Consumers) -> Result<()> {
+    std::fs::create_dir_all(&dir)?;
+    let queues = queue::Queues::default();
+    tokio::spawn(queue::deliver(queues.clone(), consumers));
+    let app = Router::new()
+        .route(
+            "/cache/:name/:key",
+            get(cache::get).put(cache::put).delete(cache::delete),
+        )
+        .route("/kv/:namespace", get(kv::list))
+        .route(
+            "/kv/:namespace/:key",
+            get(kv::get).put(kv::put).delete(kv::delete),
+        )
+        .route("/bucket/:bucket", get(bucket::list))
+        .route(
+            "/bucket/:bucket/:key",
+            get(bucket::get).put(bucket::put).delete(bucket::delete),
+        )
+        .route("/sql/:database", post(sql::query))
+        .route("/queue/:queue", post(queue::send))
+        .route("/logs", post(logs::write))
+        .route("/metrics", get(metrics::list).post(metrics::write))
+        .route("/traces", post(traces::export))
+        .route("/traces/:trace_id", get(traces::get))
+        .route("/trace-context", get(traces::context))
+        // objects are larger than the default body limit
+        .layer(DefaultBodyLimit::disable())
+        .with_state(State {
+            dir,
+            queues,
+            traces: traces::Traces::default(),
+            metrics: metrics::Metrics::default(),
+        });
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    info!("Bindings listening on {}", addr);
+    axum::serve(listener, app).await?;
+    Ok(())
+}
+
+/// file_name returns a file name for key that is safe on every filesystem
+pub fn file_name(key: &str) -> String {
+    use std::hash::{DefaultHasher, Hash, Hasher};
+    let mut hasher = DefaultHasher::new();
+    key.hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+/// now_millis returns the unix time in milliseconds
+pub fn now_millis() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
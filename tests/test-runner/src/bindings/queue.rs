@@ -0,0 +1,210 @@
+use super::{now_millis, State};
+use anyhow::Result;
+use axum::{
+    extract::{Path, State as AxumState},
+    http::StatusCode,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::{info, warn};
+
+// The messages are kept in memory and delivered to the consumer scripts in batches,
+// a message is retried until it is acked or it is delivered MAX_ATTEMPTS times
+const BATCH_SIZE: usize = 10;
+const MAX_ATTEMPTS: u64 = 3;
+const DELIVERY_INTERVAL: Duration = Duration::from_millis(100);
+
+// DELIVERY_HEADER marks the request to the queue handler of the consumer script,
+// SIGNATURE_HEADER is the hex HMAC-SHA256 of the body with the queue secret
+const DELIVERY_HEADER: &str = "x-land-queue";
+const SIGNATURE_HEADER: &str = "x-land-queue-signature";
+
+#[derive(Debug, Clone, Serialize)]
+struct Message {
+    id: String,
+    timestamp: u64,
+    body: String,
+    content_type: String,
+    attempts: u64,
+}
+
+/// Queues are the in-memory queues of the local bindings service
+#[derive(Clone, Default)]
+pub struct Queues {
+    messages: Arc<Mutex<HashMap<String, VecDeque<Message>>>>,
+    next_id: Arc<AtomicU64>,
+}
+
+impl Queues {
+    // take returns the next batch of queue, the attempts of the messages are counted
+    fn take(&self, queue: &str) -> Vec<Message> {
+        let mut queues = self.messages.lock().unwrap();
+        let messages = match queues.get_mut(queue) {
+            Some(messages) => messages,
+            None => return vec![],
+        };
+        let count = messages.len().min(BATCH_SIZE);
+        messages
+            .drain(..count)
+            .map(|mut message| {
+                message.attempts += 1;
+                message
+            })
+            .collect()
+    }
+
+    fn push(&self, queue: &str, messages: Vec<Message>) {
+        let mut queues = self.messages.lock().unwrap();
+        queues
+            .entry(queue.to_string())
+            .or_default()
+            .extend(messages);
+    }
+}
+
+/// Consumers are the consumer scripts of the queues, declared as "JOBS=path/to/consumer.wasm"
+#[derive(Debug, Clone)]
+pub struct Consumers {
+    server: String,
+    modules: HashMap<String, String>,
+    secret: String,
+}
+
+impl Consumers {
+    /// new returns the consumers declared by declared, server is the url of the wasm server,
+    /// the deliveries are signed with secret
+    pub fn new(server: String, declared: &str, secret: &str) -> Self {
+        let modules = declared
+            .split(',')
+            .filter_map(|item| item.split_once('='))
+            .map(|(queue, module)| (queue.trim().to_string(), module.trim().to_string()))
+            .filter(|(queue, module)| !queue.is_empty() && !module.is_empty())
+            .collect();
+        Self {
+            server,
+            modules,
+            secret: secret.to_string(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct SendRequest {
+    messages: Vec<OutgoingMessage>,
+}
+
+#[derive(Deserialize)]
+struct OutgoingMessage {
+    body: String,
+    content_type: String,
+}
+
+pub async fn send(
+    AxumState(state): AxumState<State>,
+    Path(queue): Path<String>,
+    Json(request): Json<SendRequest>,
+) -> StatusCode {
+    let messages = request
+        .messages
+        .into_iter()
+        .map(|message| Message {
+            id: format!(
+                "{:016x}",
+                state.queues.next_id.fetch_add(1, Ordering::Relaxed)
+            ),
+            timestamp: now_millis(),
+            body: message.body,
+            content_type: message.content_type,
+            attempts: 0,
+        })
+        .collect();
+    state.queues.push(&queue, messages);
+    StatusCode::OK
+}
+
+#[derive(Deserialize)]
+struct DeliveryResult {
+    outcomes: HashMap<String, String>,
+    error: Option<String>,
+}
+
+// sign returns the hex HMAC-SHA256 of body with secret
+fn sign(secret: &str, body: &[u8]) -> String {
+    use hmac::{Hmac, Mac};
+    let mut mac = Hmac::<sha2::Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+    mac.update(body);
+    mac.finalize()
+        .into_bytes()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+async fn deliver_batch(
+    client: &reqwest::Client,
+    consumers: &Consumers,
+    queue: &str,
+    module: &str,
+    messages: &[Message],
+) -> Result<DeliveryResult> {
+    let body = serde_json::to_vec(&json!({ "queue": queue, "messages": messages }))?;
+    let signature = sign(&consumers.secret, &body);
+    let response = client
+        .post(&consumers.server)
+        .header("x-land-m", module)
+        .header(DELIVERY_HEADER, queue)
+        .header(SIGNATURE_HEADER, signature)
+        .header("content-type", "application/json")
+        .body(body)
+        .send()
+        .await?
+        .error_for_status()?;
+    Ok(response.json().await?)
+}
+
+/// deliver sends the messages of the queues to their consumers until the process exits
+pub async fn deliver(queues: Queues, consumers: Consumers) {
+    let client = reqwest::Client::new();
+    loop {
+        tokio::time::sleep(DELIVERY_INTERVAL).await;
+        for (queue, module) in consumers.modules.iter() {
+            let messages = queues.take(queue);
+            if messages.is_empty() {
+                continue;
+            }
+            let outcomes = match deliver_batch(&client, &consumers, queue, module, &messages).await
+            {
+                Ok(result) => {
+                    if let Some(error) = result.error {
+                        warn!("Queue {} handler error: {}", queue, error);
+                    }
+                    result.outcomes
+                }
+                Err(err) => {
+                    warn!("Queue {} delivery error: {:?}", queue, err);
+                    HashMap::new()
+                }
+            };
+            let mut retries = vec![];
+            for message in messages {
+                if outcomes.get(&message.id).map(|outcome| outcome.as_str()) == Some("ack") {
+                    continue;
+                }
+                if message.attempts >= MAX_ATTEMPTS {
+                    info!(
+                        "Queue {} message {} is dropped after {} attempts",
+                        queue, message.id, message.attempts
+                    );
+                    continue;
+                }
+                retries.push(message);
+            }
+            queues.push(queue, retries);
+        }
+    }
+}
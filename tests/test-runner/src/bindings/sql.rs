@@ -0,0 +1,127 @@
+use super::{file_name, State};
+use anyhow::{anyhow, Result};
+use axum::{
+    extract::{Path, State as AxumState},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use rusqlite::{
+    params_from_iter,
+    types::{Value as SqlValue, ValueRef},
+    Connection,
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::path::Path as FsPath;
+use std::time::Instant;
+
+// Every database is a SQLite file, the statements of a request run in one transaction
+
+#[derive(Deserialize)]
+pub struct QueryRequest {
+    statements: Vec<Statement>,
+}
+
+#[derive(Deserialize)]
+struct Statement {
+    sql: String,
+    #[serde(default)]
+    params: Vec<Value>,
+}
+
+// sql_value converts a JSON parameter to a SQLite value, a blob is {"blob": "hex bytes"}
+fn sql_value(value: &Value) -> Result<SqlValue> {
+    Ok(match value {
+        Value::Null => SqlValue::Null,
+        Value::Bool(value) => SqlValue::Integer(*value as i64),
+        Value::Number(number) => match number.as_i64() {
+            Some(value) => SqlValue::Integer(value),
+            None => SqlValue::Real(number.as_f64().unwrap_or(f64::NAN)),
+        },
+        Value::String(value) => SqlValue::Text(value.clone()),
+        Value::Object(object) => {
+            let hex = object
+                .get("blob")
+                .and_then(|hex| hex.as_str())
+                .ok_or_else(|| anyhow!("parameter object must be a blob"))?;
+            let bytes = (0..hex.len() / 2)
+                .map(|index| u8::from_str_radix(&hex[index * 2..index * 2 + 2], 16))
+                .collect::<Result<Vec<u8>, _>>()?;
+            SqlValue::Blob(bytes)
+        }
+        Value::Array(_) => return Err(anyhow!("parameter must not be an array")),
+    })
+}
+
+fn json_value(value: ValueRef) -> Value {
+    match value {
+        ValueRef::Null => Value::Null,
+        ValueRef::Integer(value) => json!(value),
+        ValueRef::Real(value) => json!(value),
+        ValueRef::Text(value) => json!(String::from_utf8_lossy(value)),
+        ValueRef::Blob(value) => {
+            let hex: String = value.iter().map(|byte| format!("{:02x}", byte)).collect();
+            json!({ "blob": hex })
+        }
+    }
+}
+
+fn run(path: &FsPath, statements: &[Statement]) -> Result<Vec<Value>> {
+    std::fs::create_dir_all(path.parent().unwrap())?;
+    let mut conn = Connection::open(path)?;
+    let tx = conn.transaction()?;
+    let mut results = vec![];
+    for statement in statements {
+        let start = Instant::now();
+        let params = statement
+            .params
+            .iter()
+            .map(sql_value)
+            .collect::<Result<Vec<_>>>()?;
+        let mut stmt = tx.prepare(&statement.sql)?;
+        let columns: Vec<String> = stmt.column_names().into_iter().map(String::from).collect();
+        let readonly = stmt.readonly();
+        let mut rows = vec![];
+        let mut query = stmt.query(params_from_iter(params.iter()))?;
+        while let Some(row) = query.next()? {
+            let mut values = vec![];
+            for index in 0..columns.len() {
+                values.push(json_value(row.get_ref(index)?));
+            }
+            rows.push(values);
+        }
+        drop(query);
+        drop(stmt);
+        // the changes of a read statement are the changes of the last write, they are not reported
+        let changes = if readonly { 0 } else { tx.changes() };
+        results.push(json!({
+            "columns": columns,
+            "rows": rows,
+            "changes": changes,
+            "last_row_id": tx.last_insert_rowid(),
+            "duration": start.elapsed().as_secs_f64() * 1000.0,
+        }));
+    }
+    tx.commit()?;
+    Ok(results)
+}
+
+pub async fn query(
+    AxumState(state): AxumState<State>,
+    Path(database): Path<String>,
+    Json(request): Json<QueryRequest>,
+) -> Response {
+    let path = state
+        .dir
+        .join("sql")
+        .join(format!("{}.sqlite", file_name(&database)));
+    match run(&path, &request.statements) {
+        Ok(results) => Json(json!({ "results": results })).into_response(),
+        Err(err) => (
+            StatusCode::BAD_REQUEST,
+            Json(json!({ "error": err.to_string() })),
+        )
+            .into_response(),
+    }
+}
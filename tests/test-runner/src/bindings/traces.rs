@@ -0,0 +1,50 @@
+use super::State;
+use axum::{
+    extract::{Path, State as AxumState},
+    http::{HeaderMap, StatusCode},
+    Json,
+};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tracing::info;
+
+// The OTLP-JSON spans of the scripts with LAND_TRACE_EXPORT=host, they are kept in memory by trace id,
+// so the tests can check the spans of their requests
+
+/// Traces are the exported spans by trace id
+#[derive(Clone, Default)]
+pub struct Traces {
+    spans: Arc<Mutex<HashMap<String, Vec<Value>>>>,
+}
+
+pub async fn export(AxumState(state): AxumState<State>, Json(request): Json<Value>) -> StatusCode {
+    let mut traces = state.traces.spans.lock().unwrap();
+    for resource in request["resourceSpans"].as_array().into_iter().flatten() {
+        for scope in resource["scopeSpans"].as_array().into_iter().flatten() {
+            for span in scope["spans"].as_array().into_iter().flatten() {
+                let trace_id = span["traceId"].as_str().unwrap_or_default().to_string();
+                info!("Script span: {} {}", trace_id, span["name"]);
+                traces.entry(trace_id).or_default().push(span.clone());
+            }
+        }
+    }
+    StatusCode::OK
+}
+
+pub async fn get(
+    AxumState(state): AxumState<State>,
+    Path(trace_id): Path<String>,
+) -> Json<Vec<Value>> {
+    let traces = state.traces.spans.lock().unwrap();
+    Json(traces.get(&trace_id).cloned().unwrap_or_default())
+}
+
+// context returns the trace context headers of the request, the scripts check the propagation with it
+pub async fn context(headers: HeaderMap) -> Json<Value> {
+    let header = |name: &str| headers.get(name).and_then(|value| value.to_str().ok());
+    Json(json!({
+        "traceparent": header("traceparent"),
+        "tracestate": header("tracestate"),
+    }))
+}
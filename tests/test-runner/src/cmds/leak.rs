@@ -0,0 +1,46 @@
+use anyhow::{anyhow, Result};
+use clap::Args;
+use color_print::cprintln;
+
+/// Command Leak, it sends requests to a script of a running `up` server with `--leak-check`,
+/// and fails if the leak detector of the script reports a leak
+#[derive(Args, Debug)]
+pub struct Leak {
+    /// The wasm file of the script, such as "tests/js-files/01-hello.js.wasm"
+    pub script: String,
+    /// The address of the running `up` server
+    #[clap(long = "address", default_value("127.0.0.1:9830"))]
+    pub address: String,
+    /// The number of requests to send
+    #[clap(short = 'n', long = "requests", default_value("20"))]
+    pub requests: usize,
+}
+
+impl Leak {
+    pub async fn run(&self) -> Result<()> {
+        println!("Leak command: {:?}", self);
+        let client = reqwest::Client::new();
+        for i in 1..=self.requests {
+            let resp = client
+                .get(format!("http://{}", self.address))
+                .header("x-land-m", &self.script)
+                .send()
+                .await?;
+            let leak = resp
+                .headers()
+                .get("x-land-leak")
+                .and_then(|value| value.to_str().ok())
+                .map(|value| value.to_string());
+            if let Some(leak) = leak {
+                cprintln!("<red>Request {}: leak suspected, {}</red>", i, leak);
+                return Err(anyhow!("'{}' leaks memory: {}", self.script, leak));
+            }
+        }
+        cprintln!(
+            "<green>'{}' has no leak in {} requests</green>",
+            self.script,
+            self.requests
+        );
+        Ok(())
+    }
+}
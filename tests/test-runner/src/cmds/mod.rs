@@ -1,5 +1,8 @@
 mod build;
 pub use build::Build;
 
+mod leak;
+pub use leak::Leak;
+
 mod up;
-pub use up::Up;
\ No newline at end of file
+pub use up::Up;
@@ -6,11 +6,104 @@ use clap::Args;
 pub struct Up {
     #[clap(long = "listen", default_value("127.0.0.1:9830"))]
     pub address: Option<String>,
+    /// The address of the local bindings service, such as the cache
+    #[clap(long = "bindings-listen", default_value("127.0.0.1:9831"))]
+    pub bindings_address: String,
+    /// The directory to store the data of the local bindings
+    #[clap(long = "bindings-dir", default_value(".land-bindings"))]
+    pub bindings_dir: String,
+    /// The KV namespace bindings, such as "SESSIONS,FLAGS=feature-flags"
+    #[clap(
+        long = "kv-bindings",
+        env = "LAND_KV_BINDINGS",
+        default_value("SESSIONS")
+    )]
+    pub kv_bindings: String,
+    /// The object storage bucket bindings, such as "ASSETS,UPLOADS=user-uploads"
+    #[clap(
+        long = "bucket-bindings",
+        env = "LAND_BUCKET_BINDINGS",
+        default_value("ASSETS")
+    )]
+    pub bucket_bindings: String,
+    /// The SQL database bindings, such as "DB,ANALYTICS=analytics-db"
+    #[clap(long = "sql-bindings", env = "LAND_SQL_BINDINGS", default_value("DB"))]
+    pub sql_bindings: String,
+    /// The queue producer bindings, such as "JOBS,EMAILS=email-queue"
+    #[clap(
+        long = "queue-bindings",
+        env = "LAND_QUEUE_BINDINGS",
+        default_value("JOBS")
+    )]
+    pub queue_bindings: String,
+    /// The queue consumer scripts, such as "JOBS=consumer.js.wasm"
+    #[clap(
+        long = "queue-consumers",
+        default_value("JOBS=tests/js-files/24-queue.js.wasm")
+    )]
+    pub queue_consumers: String,
+    /// The secret of the signed queue deliveries, the scripts read it from LAND_QUEUE_SECRET
+    #[clap(
+        long = "queue-secret",
+        env = "LAND_QUEUE_SECRET",
+        default_value("test-queue-secret")
+    )]
+    pub queue_secret: String,
+    /// Enable the metrics of the server and the request metrics of the scripts,
+    /// they are sent to the bindings service and listed at /metrics
+    #[clap(long = "enable-metrics")]
+    pub enable_metrics: bool,
+    /// Enable the leak detector of the scripts, it warns when the retained heap grows in this many
+    /// requests in a row, run `test-runner leak` to send the requests
+    #[clap(long = "leak-check")]
+    pub leak_check: Option<usize>,
+    /// The files of the env vars of the wasm instances, one KEY=VALUE in a line,
+    /// the vars of the first file are kept if a var is in more files
+    #[clap(long = "env-file", default_value("tests/test.env"))]
+    pub env_files: Vec<String>,
 }
 
 impl Up {
     pub async fn run(&self) -> Result<()> {
         println!("Up command: {:?}", self);
+        for env_file in self.env_files.iter() {
+            load_env_file(env_file)?;
+        }
+
+        // Start bindings service, the wasm instances call it with LAND_BINDINGS_URL
+        let bindings_addr = self.bindings_address.parse()?;
+        let bindings_dir = self.bindings_dir.clone().into();
+        std::env::set_var(
+            "LAND_BINDINGS_URL",
+            format!("http://{}", self.bindings_address),
+        );
+        // the test scripts can not read the LAND_ platform configuration,
+        // they call the bindings service with TEST_BINDINGS_URL
+        std::env::set_var(
+            "TEST_BINDINGS_URL",
+            format!("http://{}", self.bindings_address),
+        );
+        std::env::set_var("LAND_KV_BINDINGS", &self.kv_bindings);
+        std::env::set_var("LAND_BUCKET_BINDINGS", &self.bucket_bindings);
+        std::env::set_var("LAND_SQL_BINDINGS", &self.sql_bindings);
+        std::env::set_var("LAND_QUEUE_BINDINGS", &self.queue_bindings);
+        std::env::set_var("LAND_QUEUE_SECRET", &self.queue_secret);
+        if self.enable_metrics {
+            std::env::set_var("LAND_METRICS", "header,host");
+        }
+        if let Some(requests) = self.leak_check {
+            std::env::set_var("LAND_LEAK_CHECK", requests.to_string());
+        }
+        let consumers = crate::bindings::Consumers::new(
+            format!("http://{}", self.address.clone().unwrap()),
+            &self.queue_consumers,
+            &self.queue_secret,
+        );
+        tokio::spawn(async move {
+            if let Err(err) = crate::bindings::start(bindings_addr, bindings_dir, consumers).await {
+                tracing::error!("Bindings service error: {:?}", err);
+            }
+        });
 
         // Start server
         let opts = land_wasm_server::Opts {
@@ -19,10 +112,30 @@ impl Up {
             default_wasm: None,
             enable_wasmtime_aot: false,
             endpoint_name: Some("localhost".to_string()),
-            enable_metrics: false,
+            enable_metrics: self.enable_metrics,
             metrics_addr: None,
         };
         land_wasm_server::start(opts).await?;
         Ok(())
     }
 }
+
+// load_env_file sets the env vars in path, the vars that are already set are not changed
+fn load_env_file(path: &str) -> Result<()> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(err) => return Err(err.into()),
+    };
+    for line in content.lines().map(|line| line.trim()) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if let Some((key, value)) = line.split_once('=') {
+            if std::env::var_os(key.trim()).is_none() {
+                std::env::set_var(key.trim(), value.trim());
+            }
+        }
+    }
+    Ok(())
+}
@@ -1,6 +1,7 @@
 use clap::{CommandFactory, Parser};
 use color_print::cprintln;
 
+mod bindings;
 mod cmds;
 mod tests;
 
@@ -8,6 +9,7 @@ mod tests;
 enum SubCommands {
     Build(cmds::Build),
     Up(cmds::Up),
+    Leak(cmds::Leak),
 }
 
 #[derive(Parser, Debug)]
@@ -34,6 +36,7 @@ async fn main() {
     let res = match args.cmd {
         Some(SubCommands::Build(b)) => b.run().await,
         Some(SubCommands::Up(u)) => u.run().await,
+        Some(SubCommands::Leak(l)) => l.run().await,
         None => {
             CliArgs::command().print_long_help().unwrap();
             std::process::exit(2);
@@ -3,6 +3,11 @@ use reqwest::StatusCode;
 
 #[cfg(test)]
 static URL_ADDRESS: &str = "http://127.0.0.1:9830";
+// DEV_URL_ADDRESS is the development server of the tests of the development modes, see tests/development.env
+#[cfg(test)]
+static DEV_URL_ADDRESS: &str = "http://127.0.0.1:9832";
+#[cfg(test)]
+static DEV_BINDINGS_ADDRESS: &str = "http://127.0.0.1:9833";
 #[cfg(test)]
 static X_LAND_M: &str = "x-land-m";
 
@@ -242,3 +247,708 @@ async fn js_10_atob_btoa() {
     let body = req.text().await.unwrap();
     assert_eq!(body, "All tests passed!");
 }
+
+#[tokio::test]
+async fn js_11_crypto_aes() {
+    let req = reqwest::Client::new()
+        .get(URL_ADDRESS)
+        .header(X_LAND_M, "tests/js-files/11-crypto-aes.js.wasm")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(req.status(), StatusCode::OK);
+    let body = req.text().await.unwrap();
+    assert_eq!(body, "All tests passed!");
+}
+
+#[tokio::test]
+async fn js_12_crypto_sign() {
+    let req = reqwest::Client::new()
+        .get(URL_ADDRESS)
+        .header(X_LAND_M, "tests/js-files/12-crypto-sign.js.wasm")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(req.status(), StatusCode::OK);
+    let body = req.text().await.unwrap();
+    assert_eq!(body, "All tests passed!");
+}
+
+#[tokio::test]
+async fn js_13_crypto_derive() {
+    let req = reqwest::Client::new()
+        .get(URL_ADDRESS)
+        .header(X_LAND_M, "tests/js-files/13-crypto-derive.js.wasm")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(req.status(), StatusCode::OK);
+    let body = req.text().await.unwrap();
+    assert_eq!(body, "All tests passed!");
+}
+
+#[tokio::test]
+async fn js_14_password() {
+    let req = reqwest::Client::new()
+        .get(URL_ADDRESS)
+        .header(X_LAND_M, "tests/js-files/14-password.js.wasm")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(req.status(), StatusCode::OK);
+    let body = req.text().await.unwrap();
+    assert_eq!(body, "All tests passed!");
+}
+
+#[tokio::test]
+async fn js_14_password_budget() {
+    // the second request passes only if the budget spent by the first one is reset
+    for _ in 0..2 {
+        let req = reqwest::Client::new()
+            .get(format!("{}/budget", URL_ADDRESS))
+            .header(X_LAND_M, "tests/js-files/14-password.js.wasm")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(req.status(), StatusCode::OK);
+        let body = req.text().await.unwrap();
+        assert_eq!(body, "All tests passed!");
+    }
+}
+
+#[tokio::test]
+async fn js_15_math_random() {
+    let req = reqwest::Client::new()
+        .get(URL_ADDRESS)
+        .header(X_LAND_M, "tests/js-files/15-math-random.js.wasm")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(req.status(), StatusCode::OK);
+    let body = req.text().await.unwrap();
+    assert_eq!(body, "All tests passed!");
+}
+
+// random_sequence returns the first Math.random value of the instance of wasm and count - 1 next values
+#[cfg(test)]
+async fn random_sequence(wasm: &str, count: usize) -> Vec<f64> {
+    let req = reqwest::Client::new()
+        .get(format!("{}/?sequence={}", URL_ADDRESS, count))
+        .header(X_LAND_M, wasm)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(req.status(), StatusCode::OK);
+    req.json().await.unwrap()
+}
+
+#[tokio::test]
+async fn js_15_math_random_seeded() {
+    // built with LAND_RANDOM_SEED=42 by tests/test-js-files.sh, the xoshiro256** sequence of the seed
+    let sequence = random_sequence("tests/js-files/15-math-random-seeded.js.wasm", 3).await;
+    assert_eq!(
+        sequence,
+        vec![0.08386297105988216, 0.3789802506626686, 0.6800434110281394]
+    );
+}
+
+#[tokio::test]
+async fn js_15_math_random_instances() {
+    // the two builds of the same script are restored from their snapshots without a seed
+    let first = random_sequence("tests/js-files/15-math-random.js.wasm", 1).await;
+    let copy = random_sequence("tests/js-files/15-math-random-copy.js.wasm", 1).await;
+    assert_ne!(first[0], copy[0]);
+}
+
+#[tokio::test]
+async fn js_16_text_encoding() {
+    let req = reqwest::Client::new()
+        .get(URL_ADDRESS)
+        .header(X_LAND_M, "tests/js-files/16-text-encoding.js.wasm")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(req.status(), StatusCode::OK);
+    let body = req.text().await.unwrap();
+    assert_eq!(body, "All tests passed!");
+}
+
+#[tokio::test]
+async fn js_17_compression() {
+    let req = reqwest::Client::new()
+        .get(URL_ADDRESS)
+        .header(X_LAND_M, "tests/js-files/17-compression.js.wasm")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(req.status(), StatusCode::OK);
+    let body = req.text().await.unwrap();
+    assert_eq!(body, "All tests passed!");
+}
+
+#[tokio::test]
+async fn js_18_response_compression() {
+    let plain_size = "<p>hello compression</p>\n".len() * 200;
+    let client = reqwest::Client::new();
+
+    for (accept_encoding, content_encoding) in [("gzip", "gzip"), ("gzip, br", "br")] {
+        let req = client
+            .get(URL_ADDRESS)
+            .header(X_LAND_M, "tests/js-files/18-response-compression.js.wasm")
+            .header("accept-encoding", accept_encoding)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(req.status(), StatusCode::OK);
+        let headers = req.headers().clone();
+        assert_eq!(headers.get("content-encoding").unwrap(), content_encoding);
+        assert_eq!(headers.get("vary").unwrap(), "Accept-Encoding");
+        let body = req.bytes().await.unwrap();
+        assert!(body.len() < plain_size);
+    }
+
+    // without Accept-Encoding, the body is plain but still varies
+    let req = client
+        .get(URL_ADDRESS)
+        .header(X_LAND_M, "tests/js-files/18-response-compression.js.wasm")
+        .send()
+        .await
+        .unwrap();
+    assert!(req.headers().get("content-encoding").is_none());
+    assert_eq!(req.headers().get("vary").unwrap(), "Accept-Encoding");
+    assert_eq!(req.bytes().await.unwrap().len(), plain_size);
+
+    // small and already encoded bodies are not compressed
+    for path in ["small", "encoded"] {
+        let req = client
+            .get(format!("{}/{}", URL_ADDRESS, path))
+            .header(X_LAND_M, "tests/js-files/18-response-compression.js.wasm")
+            .header("accept-encoding", "gzip")
+            .send()
+            .await
+            .unwrap();
+        assert_ne!(
+            req.headers()
+                .get("content-encoding")
+                .map(|v| v.to_str().unwrap()),
+            Some("gzip")
+        );
+    }
+}
+
+#[tokio::test]
+async fn js_19_html_rewriter() {
+    let req = reqwest::Client::new()
+        .get(URL_ADDRESS)
+        .header(X_LAND_M, "tests/js-files/19-html-rewriter.js.wasm")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(req.status(), StatusCode::OK);
+    let body = req.text().await.unwrap();
+    assert_eq!(body, "All tests passed!");
+}
+
+#[tokio::test]
+async fn js_20_cache() {
+    let req = reqwest::Client::new()
+        .get(URL_ADDRESS)
+        .header(X_LAND_M, "tests/js-files/20-cache.js.wasm")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(req.status(), StatusCode::OK);
+    let body = req.text().await.unwrap();
+    assert_eq!(body, "All tests passed!");
+}
+
+#[tokio::test]
+async fn js_21_kv() {
+    let req = reqwest::Client::new()
+        .get(URL_ADDRESS)
+        .header(X_LAND_M, "tests/js-files/21-kv.js.wasm")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(req.status(), StatusCode::OK);
+    let body = req.text().await.unwrap();
+    assert_eq!(body, "All tests passed!");
+}
+
+#[tokio::test]
+async fn js_22_bucket() {
+    let req = reqwest::Client::new()
+        .get(URL_ADDRESS)
+        .header(X_LAND_M, "tests/js-files/22-bucket.js.wasm")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(req.status(), StatusCode::OK);
+    let body = req.text().await.unwrap();
+    assert_eq!(body, "All tests passed!");
+}
+
+#[tokio::test]
+async fn js_23_sql() {
+    let req = reqwest::Client::new()
+        .get(URL_ADDRESS)
+        .header(X_LAND_M, "tests/js-files/23-sql.js.wasm")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(req.status(), StatusCode::OK);
+    let body = req.text().await.unwrap();
+    assert_eq!(body, "All tests passed!");
+}
+
+#[tokio::test]
+async fn js_24_queue() {
+    let run = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let client = reqwest::Client::new();
+    let req = client
+        .get(format!("{}/?step=send&run={}", URL_ADDRESS, run))
+        .header(X_LAND_M, "tests/js-files/24-queue.js.wasm")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(req.status(), StatusCode::OK);
+    assert_eq!(req.text().await.unwrap(), "Sent");
+
+    // the messages are delivered to the queue handler in the background
+    let mut body = String::new();
+    for _ in 0..50 {
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+        let req = client
+            .get(format!("{}/?step=check&run={}", URL_ADDRESS, run))
+            .header(X_LAND_M, "tests/js-files/24-queue.js.wasm")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(req.status(), StatusCode::OK);
+        body = req.text().await.unwrap();
+        if body != "Pending" {
+            break;
+        }
+    }
+    assert_eq!(body, "All tests passed!");
+}
+
+#[tokio::test]
+async fn js_24_queue_forged_delivery() {
+    // a client can send the delivery header, the unsigned and wrongly signed deliveries are rejected
+    let body = r#"{"queue":"JOBS","messages":[{"id":"forged","timestamp":0,"body":"7b7d","content_type":"json","attempts":1}]}"#;
+    for signature in [None, Some("00".repeat(32))] {
+        let mut req = reqwest::Client::new()
+            .post(URL_ADDRESS)
+            .header(X_LAND_M, "tests/js-files/24-queue.js.wasm")
+            .header("x-land-queue", "JOBS")
+            .body(body);
+        if let Some(signature) = signature {
+            req = req.header("x-land-queue-signature", signature);
+        }
+        let req = req.send().await.unwrap();
+        assert_eq!(req.status(), StatusCode::FORBIDDEN);
+    }
+}
+
+#[tokio::test]
+async fn js_25_env() {
+    let req = reqwest::Client::new()
+        .get(URL_ADDRESS)
+        .header(X_LAND_M, "tests/js-files/25-env.js.wasm")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(req.status(), StatusCode::OK);
+    let body = req.text().await.unwrap();
+    assert_eq!(body, "All tests passed!");
+}
+
+#[tokio::test]
+async fn js_26_console() {
+    let req = reqwest::Client::new()
+        .get(URL_ADDRESS)
+        .header(X_LAND_M, "tests/js-files/26-console.js.wasm")
+        .header("x-request-id", "js-26-console")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(req.status(), StatusCode::OK);
+    let body = req.text().await.unwrap();
+    assert_eq!(body, "All tests passed!");
+}
+
+#[tokio::test]
+async fn js_27_format() {
+    let req = reqwest::Client::new()
+        .get(URL_ADDRESS)
+        .header(X_LAND_M, "tests/js-files/27-format.js.wasm")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(req.status(), StatusCode::OK);
+    let body = req.text().await.unwrap();
+    assert_eq!(body, "All tests passed!");
+}
+
+#[tokio::test]
+async fn js_28_console_api() {
+    let req = reqwest::Client::new()
+        .get(URL_ADDRESS)
+        .header(X_LAND_M, "tests/js-files/28-console-api.js.wasm")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(req.status(), StatusCode::OK);
+    let body = req.text().await.unwrap();
+    assert_eq!(body, "All tests passed!");
+}
+
+#[tokio::test]
+async fn js_29_performance() {
+    let req = reqwest::Client::new()
+        .get(URL_ADDRESS)
+        .header(X_LAND_M, "tests/js-files/29-performance.js.wasm")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(req.status(), StatusCode::OK);
+    let server_timing = req
+        .headers()
+        .get("server-timing")
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .to_string();
+    assert!(server_timing.starts_with("script;dur="));
+    assert!(server_timing.contains("fetch;desc=\"GET 127.0.0.1\";dur="));
+    assert!(server_timing.contains("upstream;dur="));
+    assert!(server_timing.contains("render;dur=1.000"));
+    let body = req.text().await.unwrap();
+    assert_eq!(body, "All tests passed!");
+}
+
+#[tokio::test]
+async fn js_29_performance_error_page() {
+    let req = reqwest::Client::new()
+        .get(format!("{}/throw", URL_ADDRESS))
+        .header(X_LAND_M, "tests/js-files/29-performance.js.wasm")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(req.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    let server_timing = req
+        .headers()
+        .get("server-timing")
+        .unwrap()
+        .to_str()
+        .unwrap();
+    assert!(server_timing.starts_with("script;dur="));
+}
+
+#[tokio::test]
+async fn js_30_trace() {
+    let trace_id = format!(
+        "{:032x}",
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos()
+    );
+    let client = reqwest::Client::new();
+    let req = client
+        .get(format!("{}/?token=trace-secret", DEV_URL_ADDRESS))
+        .header(X_LAND_M, "tests/js-files/30-trace.js.wasm")
+        .header(
+            "traceparent",
+            format!("00-{}-00f067aa0ba902b7-01", trace_id),
+        )
+        .header("tracestate", "land=tests")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(req.status(), StatusCode::OK);
+    let body = req.text().await.unwrap();
+    assert_eq!(body, "All tests passed!");
+
+    // the spans are exported to the bindings service before the response is sent
+    let spans: Vec<serde_json::Value> = client
+        .get(format!("{}/traces/{}", DEV_BINDINGS_ADDRESS, trace_id))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let named = |name: &str| {
+        spans
+            .iter()
+            .filter(|span| span["name"] == name)
+            .collect::<Vec<_>>()
+    };
+    let handler = named("handler");
+    assert_eq!(handler.len(), 1);
+    assert_eq!(handler[0]["parentSpanId"], "00f067aa0ba902b7");
+    assert_eq!(handler[0]["kind"], 2);
+    let fetches = named("fetch");
+    assert_eq!(fetches.len(), 2);
+    assert!(fetches
+        .iter()
+        .all(|span| span["parentSpanId"] == handler[0]["spanId"]));
+    assert_eq!(named("bindings kv").len(), 1);
+    // the query of the urls is not exported
+    assert!(!serde_json::to_string(&spans)
+        .unwrap()
+        .contains("trace-secret"));
+    assert!(handler[0]["attributes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(
+            |attribute| attribute["key"] == "url.path" && attribute["value"]["stringValue"] == "/"
+        ));
+}
+
+#[tokio::test]
+async fn js_31_metrics() {
+    let req = reqwest::Client::new()
+        .post(DEV_URL_ADDRESS)
+        .header(X_LAND_M, "tests/js-files/31-metrics.js.wasm")
+        .body("metrics request body")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(req.status(), StatusCode::OK);
+    let metrics = req
+        .headers()
+        .get("x-land-metrics")
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .to_string();
+    assert!(metrics.contains("fetch_count=1"));
+    assert!(!metrics.contains("heap_after=0,"));
+    assert!(metrics.contains("response_body_bytes=17"));
+    let body = req.text().await.unwrap();
+    assert_eq!(body, "All tests passed!");
+}
+
+#[tokio::test]
+async fn js_32_leak() {
+    // the development server runs with --leak-check 3, the heap must grow in 3 requests after the first one
+    let mut leak = None;
+    for _ in 0..4 {
+        let req = reqwest::Client::new()
+            .get(DEV_URL_ADDRESS)
+            .header(X_LAND_M, "tests/js-files/32-leak.js.wasm")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(req.status(), StatusCode::OK);
+        leak = req
+            .headers()
+            .get("x-land-leak")
+            .map(|value| value.to_str().unwrap().to_string());
+        let body = req.text().await.unwrap();
+        assert_eq!(body, "All tests passed!");
+    }
+    let leak = leak.unwrap();
+    assert!(leak.contains("requests=3"));
+    assert!(leak.contains("globals=leakedItems"));
+}
+
+// debug_header returns the signed x-land-debug header of method and path with LAND_DEBUG_SECRET in tests/development.env
+#[cfg(test)]
+fn debug_header(method: &str, path: &str) -> String {
+    use hmac::{Hmac, Mac};
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+        .to_string();
+    let mut mac = Hmac::<sha2::Sha256>::new_from_slice(b"test-debug-secret").unwrap();
+    mac.update(format!("{}\n{}\n{}", timestamp, method, path).as_bytes());
+    let signature: String = mac
+        .finalize()
+        .into_bytes()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    format!("{}.{}", timestamp, signature)
+}
+
+#[tokio::test]
+async fn js_33_debug() {
+    let client = reqwest::Client::new();
+
+    // the debug headers are not returned without the signed header
+    let req = client
+        .get(DEV_URL_ADDRESS)
+        .header(X_LAND_M, "tests/js-files/33-debug.js.wasm")
+        .header("x-land-debug", "1700000000.00")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(req.status(), StatusCode::OK);
+    assert!(req.headers().get("x-land-debug-logs").is_none());
+
+    // the signature of another path or method does not enable the debug headers
+    for (method, path) in [("GET", "/other"), ("POST", "/")] {
+        let req = client
+            .get(DEV_URL_ADDRESS)
+            .header(X_LAND_M, "tests/js-files/33-debug.js.wasm")
+            .header("x-land-debug", debug_header(method, path))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(req.status(), StatusCode::OK);
+        assert!(req.headers().get("x-land-debug-logs").is_none());
+    }
+
+    let req = client
+        .get(DEV_URL_ADDRESS)
+        .header(X_LAND_M, "tests/js-files/33-debug.js.wasm")
+        .header("x-land-debug", debug_header("GET", "/"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(req.status(), StatusCode::OK);
+    let header = |name: &str| {
+        req.headers()
+            .get(name)
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_string()
+    };
+    let logs: serde_json::Value = serde_json::from_str(&header("x-land-debug-logs")).unwrap();
+    assert_eq!(logs[0]["level"], "info");
+    assert_eq!(logs[0]["message"], "debug log line { step: 1 }");
+    assert_eq!(logs[1]["level"], "warn");
+    assert_eq!(logs[1]["message"], "debug warning");
+    let timings: serde_json::Value = serde_json::from_str(&header("x-land-debug-timings")).unwrap();
+    assert!(timings
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|timing| timing["name"] == "fetch"));
+    let memory: serde_json::Value = serde_json::from_str(&header("x-land-debug-memory")).unwrap();
+    assert!(memory["heap_before"].as_i64().unwrap() > 0);
+    assert!(req.headers().get("x-land-debug-error").is_none());
+    let body = req.text().await.unwrap();
+    assert_eq!(body, "All tests passed!");
+
+    let req = client
+        .get(format!("{}/?throw=1", DEV_URL_ADDRESS))
+        .header(X_LAND_M, "tests/js-files/33-debug.js.wasm")
+        .header("x-land-debug", debug_header("GET", "/"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(req.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    let error = req
+        .headers()
+        .get("x-land-debug-error")
+        .unwrap()
+        .to_str()
+        .unwrap();
+    let error: String = serde_json::from_str(error).unwrap();
+    assert!(error.starts_with("Error: debug handler failure\n"));
+    assert!(error.contains("handleRequest"));
+}
+
+#[tokio::test]
+async fn js_34_error_page() {
+    let client = reqwest::Client::new();
+
+    // LAND_MODE=development in tests/development.env, the error page is HTML if the client accepts it
+    let req = client
+        .get(DEV_URL_ADDRESS)
+        .header(X_LAND_M, "tests/js-files/34-error-page.js.wasm")
+        .header("accept", "text/html,application/xhtml+xml")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(req.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    assert_eq!(
+        req.headers().get("content-type").unwrap(),
+        "text/html; charset=utf-8"
+    );
+    let error_id = req
+        .headers()
+        .get("x-land-error-id")
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .to_string();
+    let body = req.text().await.unwrap();
+    assert!(body.contains("<h1>TypeError</h1>"));
+    assert!(body.contains("page failure &lt;b&gt;"));
+    assert!(body.contains("<li>RangeError: inner failure</li>"));
+    assert!(body.contains("<code>failingHandler</code>"));
+    assert!(body.contains("<span class=\"failing\">&gt;"));
+    assert!(body.contains("throw error;"));
+    assert!(body.contains(&error_id));
+
+    let req = client
+        .get(DEV_URL_ADDRESS)
+        .header(X_LAND_M, "tests/js-files/34-error-page.js.wasm")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(req.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    let body = req.text().await.unwrap();
+    assert!(body.starts_with("TypeError: page failure <b>\n    at failingHandler (user.js:"));
+    assert!(body.contains("Caused by: RangeError: inner failure\n"));
+    assert!(body.contains("Error ID: "));
+}
+
+#[tokio::test]
+async fn js_35_rejection() {
+    let client = reqwest::Client::new();
+
+    // the unhandled rejections are logged, they are read from the debug headers
+    let req = client
+        .get(DEV_URL_ADDRESS)
+        .header(X_LAND_M, "tests/js-files/35-rejection.js.wasm")
+        .header("x-land-debug", debug_header("GET", "/"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(req.status(), StatusCode::OK);
+    let logs = req
+        .headers()
+        .get("x-land-debug-logs")
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .to_string();
+    let logs: serde_json::Value = serde_json::from_str(&logs).unwrap();
+    let messages: Vec<&str> = logs
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|record| record["level"] == "error")
+        .map(|record| record["message"].as_str().unwrap())
+        .collect();
+    assert_eq!(messages.len(), 1);
+    assert!(messages[0].starts_with("Unhandled promise rejection: Error: forgotten rejection\n"));
+    assert!(messages[0].contains("\nCaused by: RangeError: root cause\n"));
+    let body = req.text().await.unwrap();
+    assert_eq!(body, "All tests passed!");
+
+    let req = client
+        .get(format!("{}/?throw=aggregate", DEV_URL_ADDRESS))
+        .header(X_LAND_M, "tests/js-files/35-rejection.js.wasm")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(req.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    let body = req.text().await.unwrap();
+    assert!(body.starts_with("AggregateError: all failed\n"));
+    assert!(body.contains("\n  [errors[0]] Error: first failure\n"));
+    assert!(body.contains("\n  [errors[1]] TypeError: second failure\n"));
+}